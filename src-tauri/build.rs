@@ -1,3 +1,5 @@
 fn main() {
+    // Only the Tauri app needs the Tauri build step
+    #[cfg(feature = "ui")]
     tauri_build::build()
 }
@@ -0,0 +1,614 @@
+//! Headless Solis.
+//!
+//! Runs the telemetry side of Solis without the UI, audio or LLM,
+//! e.g. on a Linux box next to the sim rig or in CI.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    env,
+    io::{self, Write},
+    net::ToSocketAddrs,
+    path::PathBuf,
+    process::ExitCode,
+    sync::mpsc::{sync_channel, RecvTimeoutError},
+    thread,
+    time::{Duration, Instant},
+};
+
+use solis_lib::{
+    core::{
+        ids::PacketType, parse_packet, PacketFilter, RelayTarget, SessionManager, SubscribeOptions,
+        TelemetryPacket, DEFAULT_LISTENER_ID, DEFAULT_QUEUE_LEN,
+    },
+    storage::{Replay, ReplayOutput, SessionFile},
+};
+
+const USAGE: &str = "\
+Usage: solis-cli <command> [options]
+
+Commands:
+  listen                Print every packet received
+  record <directory>    Record every datagram received to session files
+  replay <file>         Send a recorded session over UDP as if the game was running
+  dump <file>           Print every packet in a recorded session
+  stats <file>          Print a summary of a recorded session
+
+Options:
+  --address <address>   Address to listen on, or replay to (default 0.0.0.0, 127.0.0.1 for replay)
+  --port <port>         Port to listen on, or replay to (default 20777)
+  --listeners <list>    Listen on several ports at once instead, as comma separated
+                        id=address:port, e.g rig1=0.0.0.0:20777,rig2=0.0.0.0:20778.
+                        Each listener records to its own sub directory
+  --duration <seconds>  Stop listening or recording after this long
+  --speed <multiplier>  Replay speed (default 1)
+  --from <seconds>      Start the replay this far into the recording
+  --type <packet id>    Only print packets of this type, e.g 1 for Session
+  --relay <addresses>   Forward every datagram received to these comma separated addresses
+  --relay-type <ids>    Only forward these comma separated packet ids, e.g 0,6
+  --json                Print packets as JSON, one per line";
+
+const DEFAULT_PORT: &str = "20777";
+
+/// Options that don't take a value
+const FLAGS: &[&str] = &["json"];
+
+type CliResult = Result<(), String>;
+
+/// Command line arguments following the command
+#[derive(Debug, Default)]
+struct Args {
+    positional: Vec<String>,
+    options: HashMap<String, String>,
+    flags: Vec<String>,
+}
+
+impl Args {
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Self, String> {
+        let mut parsed = Args::default();
+
+        while let Some(arg) = args.next() {
+            let Some(name) = arg.strip_prefix("--") else {
+                parsed.positional.push(arg);
+                continue;
+            };
+
+            if FLAGS.contains(&name) {
+                parsed.flags.push(name.to_string());
+                continue;
+            }
+
+            let value = args
+                .next()
+                .ok_or_else(|| format!("missing value for --{name}"))?;
+            parsed.options.insert(name.to_string(), value);
+        }
+
+        Ok(parsed)
+    }
+
+    fn flag(&self, name: &str) -> bool {
+        self.flags.iter().any(|flag| flag == name)
+    }
+
+    fn option(&self, name: &str) -> Option<&str> {
+        self.options.get(name).map(String::as_str)
+    }
+
+    /// Parse an option's value, if given
+    fn parsed<T: std::str::FromStr>(&self, name: &str) -> Result<Option<T>, String> {
+        self.option(name)
+            .map(|value| {
+                value
+                    .parse()
+                    .map_err(|_| format!("invalid value for --{name}: {value}"))
+            })
+            .transpose()
+    }
+
+    /// The nth positional argument
+    fn path(&self, n: usize, what: &str) -> Result<PathBuf, String> {
+        self.positional
+            .get(n)
+            .map(PathBuf::from)
+            .ok_or_else(|| format!("missing {what}"))
+    }
+
+    fn duration(&self) -> Result<Option<Duration>, String> {
+        Ok(self
+            .parsed::<f64>("duration")?
+            .map(|seconds| Duration::from_secs_f64(seconds.max(0.0))))
+    }
+
+    fn packet_type(&self) -> Result<Option<u8>, String> {
+        self.parsed("type")
+    }
+
+    /// Id, address and port of every listener to start
+    fn listeners(&self) -> Result<Vec<(String, String, String)>, String> {
+        let Some(listeners) = self.option("listeners") else {
+            return Ok(vec![(
+                DEFAULT_LISTENER_ID.to_string(),
+                self.option("address").unwrap_or("0.0.0.0").to_string(),
+                self.option("port").unwrap_or(DEFAULT_PORT).to_string(),
+            )]);
+        };
+
+        listeners
+            .split(',')
+            .map(|listener| {
+                let (id, address, port) = listener
+                    .trim()
+                    .split_once('=')
+                    .and_then(|(id, address)| {
+                        let (address, port) = address.rsplit_once(':')?;
+                        Some((id, address, port))
+                    })
+                    .filter(|(id, _, _)| !id.is_empty())
+                    .ok_or_else(|| format!("invalid listener for --listeners: {listener}"))?;
+
+                Ok((id.to_string(), address.to_string(), port.to_string()))
+            })
+            .collect()
+    }
+
+    fn relay_targets(&self) -> Result<Vec<RelayTarget>, String> {
+        let Some(addresses) = self.option("relay") else {
+            return Ok(Vec::new());
+        };
+
+        let packet_types = self
+            .option("relay-type")
+            .map(|ids| {
+                ids.split(',')
+                    .map(|id| {
+                        id.trim()
+                            .parse::<u8>()
+                            .ok()
+                            .and_then(|id| PacketType::try_from(id).ok())
+                            .ok_or_else(|| format!("invalid packet id for --relay-type: {id}"))
+                    })
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .transpose()?
+            .unwrap_or_default();
+
+        addresses
+            .split(',')
+            .map(|address| {
+                let address = address
+                    .trim()
+                    .to_socket_addrs()
+                    .ok()
+                    .and_then(|mut addrs| addrs.next())
+                    .ok_or_else(|| format!("invalid address for --relay: {address}"))?;
+
+                Ok(RelayTarget {
+                    address,
+                    packet_types: packet_types.clone(),
+                })
+            })
+            .collect()
+    }
+}
+
+fn main() -> ExitCode {
+    let mut args = env::args().skip(1);
+    let Some(command) = args.next() else {
+        eprintln!("{USAGE}");
+        return ExitCode::FAILURE;
+    };
+
+    let result = Args::parse(args).and_then(|args| match command.as_str() {
+        "listen" => listen(&args),
+        "record" => record(&args),
+        "replay" => replay(&args),
+        "dump" => dump(&args),
+        "stats" => stats(&args),
+        "help" | "--help" | "-h" => {
+            println!("{USAGE}");
+            Ok(())
+        }
+        _ => Err(format!("unknown command '{command}'\n\n{USAGE}")),
+    });
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Start every listener given in `args`
+fn connect(args: &Args) -> Result<SessionManager, String> {
+    let relay_targets = args.relay_targets()?;
+    let mut manager = SessionManager::new();
+
+    for (id, address, port) in args.listeners()? {
+        let session = manager
+            .start(id, address, port)
+            .map_err(|e| e.to_string())?;
+
+        for target in &relay_targets {
+            session
+                .lock()
+                .unwrap()
+                .relay
+                .add_target(target.clone())
+                .map_err(|e| format!("failed to start relaying: {e}"))?;
+        }
+    }
+
+    Ok(manager)
+}
+
+/// Receive packets from every listener until the duration given in
+/// `args` has passed, or forever, calling `on_packet` with the id of
+/// the listener and each packet. Stops early if `on_packet` returns false.
+fn receive(
+    args: &Args,
+    manager: &SessionManager,
+    mut on_packet: impl FnMut(&str, &TelemetryPacket) -> bool,
+) -> CliResult {
+    let duration = args.duration()?;
+    let started = Instant::now();
+    let (sender, packets) = sync_channel(DEFAULT_QUEUE_LEN);
+
+    // Gather the packets of every listener into one queue.
+    // Each thread ends when its listener is stopped.
+    for id in manager.ids() {
+        let Some(session) = manager.session(id) else {
+            continue;
+        };
+
+        let subscription = session.lock().unwrap().bus.subscribe_packets(
+            PacketFilter::default(),
+            SubscribeOptions {
+                queue_len: DEFAULT_QUEUE_LEN,
+                max_rate_hz: None,
+            },
+        );
+
+        let id = id.to_string();
+        let sender = sender.clone();
+        thread::spawn(move || loop {
+            match subscription.recv_timeout(Duration::from_millis(100)) {
+                Ok(packet) => {
+                    if sender.send((id.clone(), packet)).is_err() {
+                        break;
+                    }
+                }
+                Err(RecvTimeoutError::Timeout) => {
+                    if !session.lock().unwrap().connected() {
+                        break;
+                    }
+                }
+                Err(RecvTimeoutError::Disconnected) => break,
+            }
+        });
+    }
+    drop(sender);
+
+    while duration.is_none_or(|duration| started.elapsed() < duration) {
+        let (id, packet) = match packets.recv_timeout(Duration::from_millis(100)) {
+            Ok(packet) => packet,
+            Err(RecvTimeoutError::Timeout) => continue,
+            Err(RecvTimeoutError::Disconnected) => break,
+        };
+
+        if !on_packet(&id, &packet) {
+            break;
+        }
+    }
+
+    for listener in manager.list() {
+        let stats = listener.ingest;
+        if manager.len() > 1 || stats.dropped > 0 || stats.undecodable > 0 {
+            eprintln!(
+                "[cli] {}: {} packets received, {} dropped, {} undecodable",
+                listener.id, stats.received, stats.dropped, stats.undecodable
+            );
+        }
+    }
+
+    Ok(())
+}
+
+fn listen(args: &Args) -> CliResult {
+    let packet_type = args.packet_type()?;
+    let json = args.flag("json");
+    let mut manager = connect(args)?;
+    let several = manager.len() > 1;
+    let mut out = io::stdout().lock();
+
+    // Stop once stdout is closed, e.g piped into head
+    receive(args, &manager, |id, packet| {
+        packet_type.is_some_and(|packet_type| packet.packet_id().as_u8() != packet_type)
+            || print_packet(&mut out, several.then_some(id), packet, json).is_ok()
+    })?;
+
+    manager.stop_all();
+    Ok(())
+}
+
+/// Recording is finished when the duration has passed. If the process
+/// is killed instead, the last session file is left unfinished but can
+/// still be read.
+fn record(args: &Args) -> CliResult {
+    let directory = args.path(0, "directory to record to")?;
+    let mut manager = connect(args)?;
+
+    for id in manager.ids() {
+        // Keep the session files of each listener apart
+        let directory = if manager.len() > 1 {
+            directory.join(id)
+        } else {
+            directory.clone()
+        };
+
+        if let Some(session) = manager.session(id) {
+            session
+                .lock()
+                .unwrap()
+                .start_recording(directory)
+                .map_err(|e| format!("failed to start recording: {e}"))?;
+        }
+    }
+
+    let mut last_report = Instant::now();
+    let mut received = 0u64;
+    receive(args, &manager, |_, _| {
+        received += 1;
+        if last_report.elapsed() >= Duration::from_secs(5) {
+            println!("[cli] {received} packets recorded");
+            last_report = Instant::now();
+        }
+        true
+    })?;
+
+    for id in manager.ids() {
+        if let Some(session) = manager.session(id) {
+            session
+                .lock()
+                .unwrap()
+                .stop_recording()
+                .map_err(|e| format!("failed to finish recording: {e}"))?;
+        }
+    }
+
+    manager.stop_all();
+    Ok(())
+}
+
+fn replay(args: &Args) -> CliResult {
+    let path = args.path(0, "session file to replay")?;
+    let address = args.option("address").unwrap_or("127.0.0.1");
+    let port = args.option("port").unwrap_or(DEFAULT_PORT);
+    let target = format!("{address}:{port}")
+        .to_socket_addrs()
+        .ok()
+        .and_then(|mut addrs| addrs.next())
+        .ok_or_else(|| format!("invalid address {address}:{port}"))?;
+
+    let replay = Replay::open(&path, ReplayOutput::Udp(target))
+        .map_err(|e| format!("failed to open {}: {e}", path.display()))?;
+
+    if let Some(speed) = args.parsed::<f32>("speed")? {
+        replay.set_speed(speed);
+    }
+    if let Some(from) = args.parsed::<f64>("from")? {
+        replay.seek(Duration::from_secs_f64(from.max(0.0)));
+    }
+
+    let info = replay.info();
+    println!(
+        "[cli] Replaying session {:016x} (format {}, {:.0}s) to {target}",
+        info.session_uid,
+        info.packet_format,
+        info.duration.as_secs_f64()
+    );
+
+    replay.play();
+    loop {
+        thread::sleep(Duration::from_secs(1));
+        let status = replay.status();
+        println!(
+            "[cli] {:.0}/{:.0}s frame {} ({} datagrams sent)",
+            status.position.as_secs_f64(),
+            status.duration.as_secs_f64(),
+            status.frame_identifier,
+            status.datagrams_sent
+        );
+
+        if status.finished {
+            return Ok(());
+        }
+    }
+}
+
+fn open(args: &Args) -> Result<SessionFile, String> {
+    let path = args.path(0, "session file")?;
+    SessionFile::open(&path).map_err(|e| format!("failed to open {}: {e}", path.display()))
+}
+
+fn dump(args: &Args) -> CliResult {
+    let mut file = open(args)?;
+    let packet_type = args.packet_type()?;
+    let json = args.flag("json");
+    let mut out = io::stdout().lock();
+
+    for record in file.records() {
+        let record = record.map_err(|e| format!("failed to read record: {e}"))?;
+        let Ok(packet) = parse_packet(&record.data) else {
+            continue;
+        };
+
+        if packet_type.is_none_or(|id| packet.packet_id().as_u8() == id)
+            && print_packet(&mut out, None, &packet, json).is_err()
+        {
+            // stdout was closed, e.g piped into head
+            break;
+        }
+    }
+
+    Ok(())
+}
+
+/// Packets and bytes received of one packet type
+#[derive(Debug, Default)]
+struct PacketCount {
+    name: &'static str,
+    packets: u64,
+    bytes: u64,
+}
+
+fn stats(args: &Args) -> CliResult {
+    let mut file = open(args)?;
+    let info = file.info().clone();
+
+    println!("Session:        {:016x}", info.session_uid);
+    println!(
+        "Game:           F1 {} v{}.{:02} (format {})",
+        info.game_year, info.game_major_version, info.game_minor_version, info.packet_format
+    );
+    println!("Duration:       {:.1}s", info.duration.as_secs_f64());
+    println!("Chunks:         {}", file.index().len());
+    println!(
+        "Complete:       {}",
+        if file.is_complete() {
+            "yes"
+        } else {
+            "no, recording was not finished"
+        }
+    );
+
+    let drivers: Vec<String> = info.participants.iter().map(|d| d.name.clone()).collect();
+    if !drivers.is_empty() {
+        println!("Drivers:        {}", drivers.join(", "));
+    }
+
+    let mut counts: BTreeMap<u8, PacketCount> = BTreeMap::new();
+    let mut undecodable = 0u64;
+    let mut frames: Option<(u32, u32)> = None;
+
+    for record in file.records() {
+        let record = record.map_err(|e| format!("failed to read record: {e}"))?;
+
+        if let Some(frame) = record.frame_identifier() {
+            frames = Some(match frames {
+                Some((first, last)) => (first.min(frame), last.max(frame)),
+                None => (frame, frame),
+            });
+        }
+
+        let Ok(packet) = parse_packet(&record.data) else {
+            undecodable += 1;
+            continue;
+        };
+
+        let count = counts.entry(packet.packet_id().as_u8()).or_default();
+        count.name = packet.name();
+        count.packets += 1;
+        count.bytes += record.data.len() as u64;
+    }
+
+    if let Some((first, last)) = frames {
+        println!("Frames:         {first} - {last}");
+    }
+
+    let seconds = info.duration.as_secs_f64().max(f64::EPSILON);
+    println!();
+    println!(
+        "{:<4} {:<22} {:>10} {:>12} {:>8}",
+        "ID", "Packet", "Count", "Bytes", "Hz"
+    );
+    for (id, count) in &counts {
+        println!(
+            "{:<4} {:<22} {:>10} {:>12} {:>8.1}",
+            id,
+            count.name,
+            count.packets,
+            count.bytes,
+            count.packets as f64 / seconds
+        );
+    }
+
+    let total: u64 = counts.values().map(|count| count.packets).sum();
+    println!("{:<4} {:<22} {:>10}", "", "Total", total + undecodable);
+    if undecodable > 0 {
+        println!("{:<4} {:<22} {:>10}", "", "Undecodable", undecodable);
+    }
+
+    Ok(())
+}
+
+/// Print a packet as a single line, or as JSON, labelled
+/// with the id of the listener it came from if given
+fn print_packet(
+    out: &mut impl Write,
+    listener: Option<&str>,
+    packet: &TelemetryPacket,
+    json: bool,
+) -> io::Result<()> {
+    if json {
+        return match packet_json(packet) {
+            Ok(json) => match listener {
+                Some(id) => writeln!(
+                    out,
+                    "{{\"listener\":{},\"packet\":{json}}}",
+                    serde_json::Value::from(id)
+                ),
+                None => writeln!(out, "{json}"),
+            },
+            Err(e) => {
+                eprintln!("error: failed to serialize {}: {e}", packet.name());
+                Ok(())
+            }
+        };
+    }
+
+    let header = packet.header();
+    let detail = packet
+        .as_event()
+        .map(|event| format!(" {} ({})", event.event_name(), event.code_as_string()))
+        .unwrap_or_default();
+
+    if let Some(id) = listener {
+        write!(out, "{id:<10} ")?;
+    }
+
+    writeln!(
+        out,
+        "{:>10.3} {:>8} {}{detail}",
+        header.session_time,
+        header.frame_identifier,
+        packet.name()
+    )
+}
+
+fn packet_json(packet: &TelemetryPacket) -> serde_json::Result<String> {
+    match packet {
+        TelemetryPacket::Motion(p) => serde_json::to_string(p),
+        TelemetryPacket::Session(p) => serde_json::to_string(p),
+        TelemetryPacket::LapData(p) => serde_json::to_string(p),
+        TelemetryPacket::Event(p) => serde_json::to_string(&serde_json::json!({
+            "header": p.header,
+            "code": p.code_as_string(),
+            "name": p.event_name(),
+            "event": p.event,
+        })),
+        TelemetryPacket::Participants(p) => serde_json::to_string(p),
+        TelemetryPacket::CarSetups(p) => serde_json::to_string(p),
+        TelemetryPacket::CarTelemetry(p) => serde_json::to_string(p),
+        TelemetryPacket::CarStatus(p) => serde_json::to_string(p),
+        TelemetryPacket::FinalClassification(p) => serde_json::to_string(p),
+        TelemetryPacket::LobbyInfo(p) => serde_json::to_string(p),
+        TelemetryPacket::CarDamage(p) => serde_json::to_string(p),
+        TelemetryPacket::SessionHistory(p) => serde_json::to_string(p),
+        TelemetryPacket::TyreSets(p) => serde_json::to_string(p),
+        TelemetryPacket::MotionEx(p) => serde_json::to_string(p),
+        TelemetryPacket::TimeTrial(p) => serde_json::to_string(p),
+        TelemetryPacket::LapPositions(p) => serde_json::to_string(p),
+    }
+}
@@ -1,14 +1,26 @@
 use crate::audio::AudioInput;
 use crate::bridge::{DataRow, TranscribeEvent};
 use crate::core::ids::PacketType;
-use crate::core::Session;
+use crate::core::{
+    DerivedEventFilter, ErsAnalysis, EventFilter, ForecastTimeline, FuelAnalysis, LapHistory,
+    LifecycleEvent, ListenerInfo, PacketFilter, RaceStateChange, RelayTarget, Session,
+    SessionManager, SubscribeOptions, TelemetryPacket, TimingTower, TyreAnalysis, WeatherAnalysis,
+    DEFAULT_LISTENER_ID,
+};
+use crate::storage::{RecordingInfo, Replay, ReplayOutput, ReplayStatus};
+use crate::strategy::{
+    plan_strategy, RejoinPrediction, SafetyCarAdvice, SafetyCarInput, StrategyInput, StrategyReport,
+};
+use std::io;
+use std::net::ToSocketAddrs;
+use std::path::PathBuf;
 use std::sync::LazyLock;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use std::{
-    sync::{Arc, Mutex},
+    sync::{mpsc::RecvTimeoutError, Arc, Mutex, MutexGuard},
     thread,
 };
-use tauri::{AppHandle, Emitter, Error, Listener};
+use tauri::{AppHandle, Emitter, Error, Listener, Manager};
 
 macro_rules! send_packet_to_buffer {
     ($buffer:expr, $packet_title:tt, $packet_broad:tt, $packet_specific:tt) => {{
@@ -43,150 +55,516 @@ pub fn get_output_devices() -> Vec<String> {
     AudioInput::get_audio_output_devices()
 }
 
+/// Every listener started with start_udp_listener, by id
+pub static SESSIONS: LazyLock<Mutex<SessionManager>> =
+    LazyLock::new(|| Mutex::new(SessionManager::new()));
+
+/// Listen for events from the frontend. Called once when the app starts.
+pub fn listen_for_events(app: &AppHandle) {
+    // Listen for the stop_udp_listener event from the frontend.
+    // Will cut the session UDP 'connection' of the listener in the
+    // payload, or the default listener, making the backend ready
+    // to establish a fresh, new connection later on.
+    app.listen("stop_udp_listener", |event| {
+        let id = serde_json::from_str::<Option<String>>(event.payload())
+            .ok()
+            .flatten()
+            .unwrap_or_else(|| DEFAULT_LISTENER_ID.to_string());
+
+        SESSIONS.lock().unwrap().stop(&id);
+    });
+}
+
+/// Listen for packets on `address`:`port` as the listener `id`, or the
+/// default listener, replacing any listener with the same id.
+/// Every datagram is also forwarded to the `relay` targets, if any.
 #[tauri::command]
-pub fn start_udp_listener(app: AppHandle, address: String, port: String) -> Result<bool, Error> {
+pub fn start_udp_listener(
+    app: AppHandle,
+    address: String,
+    port: String,
+    id: Option<String>,
+    relay: Option<Vec<RelayTarget>>,
+) -> Result<bool, Error> {
+    let id = id.unwrap_or_else(|| DEFAULT_LISTENER_ID.to_string());
     let app_arc = Arc::new(app);
-    let app_clone = Arc::clone(&app_arc);
 
+    let session = {
+        let mut sessions = SESSIONS.lock().unwrap();
+        sessions.stop(&id);
+
+        match sessions.start(id.clone(), address, port) {
+            Ok(session) => session,
+            Err(e) => {
+                println!("[backend] Failed to start listener: {e}");
+                return Ok(false);
+            }
+        }
+    };
+
+    for target in relay.unwrap_or_default() {
+        session.lock().unwrap().relay.add_target(target)?;
+    }
+
+    // The frontend is one of the subscribers to the session's packet bus
+    let (packets, lifecycle, derived) = {
+        let session_guard = session.lock().unwrap();
+        let bus = &session_guard.bus;
+        (
+            bus.subscribe_packets(PacketFilter::default(), SubscribeOptions::default()),
+            bus.subscribe_events(
+                EventFilter {
+                    changes: vec![RaceStateChange::Lifecycle(LifecycleEvent::SessionStarted {
+                        session_uid: 0,
+                    })],
+                },
+                SubscribeOptions::default(),
+            ),
+            bus.subscribe_derived(DerivedEventFilter::default(), SubscribeOptions::default()),
+        )
+    };
+
+    // Thread that will dispatch the neccessary info about every packet
+    // on the bus to the frontend to be displayed as a data row, along
+    // with every session start and end, every derived event, and the
+    // listener's statistics and timing tower every second.
+    // Ends when the listener is stopped.
+    let buffer = spawn_data_row_batches(Arc::clone(&app_arc));
+    thread::spawn(move || {
+        let mut last_info = Instant::now();
+
+        loop {
+            match packets.recv_timeout(Duration::from_millis(100)) {
+                Ok(packet) => dispatch_packet(&buffer, &packet, session.lock().unwrap()),
+                Err(RecvTimeoutError::Timeout) => {
+                    if !session.lock().unwrap().connected() {
+                        break;
+                    }
+                }
+                Err(RecvTimeoutError::Disconnected) => break,
+            }
+
+            for update in lifecycle.try_iter() {
+                let _ = app_arc.emit("sessionLifecycle", update);
+            }
+
+            for update in derived.try_iter() {
+                let _ = app_arc.emit("derivedEvent", update);
+            }
+
+            if last_info.elapsed() >= Duration::from_secs(1) {
+                if let Some(info) = SESSIONS.lock().unwrap().info(&id) {
+                    let _ = app_arc.emit("listenerInfo", info);
+                }
+                let _ = app_arc.emit("timingTower", session.lock().unwrap().timing.tower());
+                last_info = Instant::now();
+            }
+        }
+
+        println!("Not connected. breaking from thread");
+    });
+
+    Ok(true)
+}
+
+/// Stop the listener `id`. Returns false if there was no such listener.
+#[tauri::command]
+pub fn stop_listener(id: String) -> bool {
+    SESSIONS.lock().unwrap().stop(&id)
+}
+
+#[tauri::command]
+pub fn list_listeners() -> Vec<ListenerInfo> {
+    SESSIONS.lock().unwrap().list()
+}
+
+/// Live timing of the listener `id`, or the default listener
+#[tauri::command]
+pub fn get_timing_tower(id: Option<String>) -> Option<TimingTower> {
+    let id = id.unwrap_or_else(|| DEFAULT_LISTENER_ID.to_string());
+    let session = SESSIONS.lock().unwrap().session(&id)?;
+    let tower = session.lock().unwrap().timing.tower();
+    Some(tower)
+}
+
+/// Every lap and tyre stint of the car at `vehicle_idx`,
+/// from the listener `id`, or the default listener
+#[tauri::command]
+pub fn get_lap_history(id: Option<String>, vehicle_idx: u8) -> Option<LapHistory> {
+    let id = id.unwrap_or_else(|| DEFAULT_LISTENER_ID.to_string());
+    let session = SESSIONS.lock().unwrap().session(&id)?;
+    let history = session.lock().unwrap().history.car(vehicle_idx).cloned();
+    history
+}
+
+/// Wear, temperature and predicted life of the tyres of the car
+/// at `vehicle_idx`, from the listener `id`, or the default listener
+#[tauri::command]
+pub fn get_tyre_analysis(id: Option<String>, vehicle_idx: u8) -> Option<TyreAnalysis> {
+    let id = id.unwrap_or_else(|| DEFAULT_LISTENER_ID.to_string());
+    let session = SESSIONS.lock().unwrap().session(&id)?;
+    let analysis = session.lock().unwrap().tyres.analysis(vehicle_idx);
+    analysis
+}
+
+/// Fuel burn and end of race projection of the car at `vehicle_idx`,
+/// from the listener `id`, or the default listener
+#[tauri::command]
+pub fn get_fuel_analysis(id: Option<String>, vehicle_idx: u8) -> Option<FuelAnalysis> {
+    let id = id.unwrap_or_else(|| DEFAULT_LISTENER_ID.to_string());
+    let session = SESSIONS.lock().unwrap().session(&id)?;
+    let analysis = session.lock().unwrap().fuel.analysis(vehicle_idx);
+    analysis
+}
+
+/// Fuel to have left at the end of the race, in laps of fuel,
+/// for the listener `id`, or the default listener
+#[tauri::command]
+pub fn set_fuel_target_margin(id: Option<String>, margin_laps: f32) -> bool {
+    let id = id.unwrap_or_else(|| DEFAULT_LISTENER_ID.to_string());
+    let Some(session) = SESSIONS.lock().unwrap().session(&id) else {
+        return false;
+    };
+
+    session
+        .lock()
+        .unwrap()
+        .fuel
+        .set_target_margin_laps(margin_laps);
+    true
+}
+
+/// Weather and when to switch between slicks and wets for the car at
+/// `vehicle_idx`, from the listener `id`, or the default listener
+#[tauri::command]
+pub fn get_weather_analysis(id: Option<String>, vehicle_idx: u8) -> Option<WeatherAnalysis> {
+    let id = id.unwrap_or_else(|| DEFAULT_LISTENER_ID.to_string());
+    let session = SESSIONS.lock().unwrap().session(&id)?;
+    let analysis = session.lock().unwrap().weather.analysis(vehicle_idx);
+    analysis
+}
+
+/// Latest forecast of every session of the weekend,
+/// from the listener `id`, or the default listener
+#[tauri::command]
+pub fn get_weather_forecast(id: Option<String>) -> Vec<ForecastTimeline> {
+    let id = id.unwrap_or_else(|| DEFAULT_LISTENER_ID.to_string());
+    let Some(session) = SESSIONS.lock().unwrap().session(&id) else {
+        return Vec::new();
+    };
+
+    let timelines = session.lock().unwrap().weather.timelines().to_vec();
+    timelines
+}
+
+/// One, two and three stop plans for the car at `vehicle_idx`, ranked by
+/// predicted race time, from the listener `id`, or the default listener
+#[tauri::command]
+pub fn get_pit_strategy(id: Option<String>, vehicle_idx: u8) -> Option<StrategyReport> {
+    let id = id.unwrap_or_else(|| DEFAULT_LISTENER_ID.to_string());
+    let session = SESSIONS.lock().unwrap().session(&id)?;
+    let input = StrategyInput::new(&session.lock().unwrap(), vehicle_idx)?;
+    Some(plan_strategy(&input))
+}
+
+/// Where the car at `vehicle_idx` would rejoin if it pitted this lap,
+/// from the listener `id`, or the default listener
+#[tauri::command]
+pub fn get_rejoin_prediction(id: Option<String>, vehicle_idx: u8) -> Option<RejoinPrediction> {
+    let id = id.unwrap_or_else(|| DEFAULT_LISTENER_ID.to_string());
+    let session = SESSIONS.lock().unwrap().session(&id)?;
+    let prediction = RejoinPrediction::new(&session.lock().unwrap(), vehicle_idx);
+    prediction
+}
+
+/// Whether the car at `vehicle_idx` should box under the current safety car
+/// or virtual safety car, from the listener `id`, or the default listener
+#[tauri::command]
+pub fn get_safety_car_advice(id: Option<String>, vehicle_idx: u8) -> Option<SafetyCarAdvice> {
+    let id = id.unwrap_or_else(|| DEFAULT_LISTENER_ID.to_string());
+    let session = SESSIONS.lock().unwrap().session(&id)?;
+    let input = SafetyCarInput::new(&session.lock().unwrap(), vehicle_idx)?;
+    Some(SafetyCarAdvice::new(&input))
+}
+
+/// ERS deployment, harvesting and the deploy mode to run for the car
+/// at `vehicle_idx`, from the listener `id`, or the default listener
+#[tauri::command]
+pub fn get_ers_analysis(id: Option<String>, vehicle_idx: u8) -> Option<ErsAnalysis> {
+    let id = id.unwrap_or_else(|| DEFAULT_LISTENER_ID.to_string());
+    let session = SESSIONS.lock().unwrap().session(&id)?;
+    let session = session.lock().unwrap();
+    let analysis = session.ers.analysis(vehicle_idx, &session.timing.tower());
+    analysis
+}
+
+/// Record every datagram the listener `id`, or the default listener,
+/// receives to `directory`, or 'recordings' in the app data directory.
+#[tauri::command]
+pub fn start_session_recording(
+    app: AppHandle,
+    id: Option<String>,
+    directory: Option<String>,
+) -> Result<bool, Error> {
+    let id = id.unwrap_or_else(|| DEFAULT_LISTENER_ID.to_string());
+    let Some(session) = SESSIONS.lock().unwrap().session(&id) else {
+        return Ok(false);
+    };
+
+    let directory = match directory {
+        Some(directory) => PathBuf::from(directory),
+        None => app.path().app_data_dir()?.join("recordings"),
+    };
+
+    session.lock().unwrap().start_recording(directory)?;
+    Ok(true)
+}
+
+/// Stop recording the listener `id`, or the default listener.
+///
+/// Returns the path of the finished session file, if any.
+#[tauri::command]
+pub fn stop_session_recording(id: Option<String>) -> Result<Option<PathBuf>, Error> {
+    let id = id.unwrap_or_else(|| DEFAULT_LISTENER_ID.to_string());
+    let Some(session) = SESSIONS.lock().unwrap().session(&id) else {
+        return Ok(None);
+    };
+
+    let path = session.lock().unwrap().stop_recording()?;
+    Ok(path)
+}
+
+/// Replay currently running, started with start_replay
+pub static REPLAY: LazyLock<Mutex<Option<Replay>>> = LazyLock::new(|| Mutex::new(None));
+
+/// Replay a recorded session file.
+///
+/// With an address and port, the datagrams are sent over UDP as if the game
+/// was running, e.g. to a listener started with start_udp_listener.
+/// Otherwise they are parsed directly and dispatched to the frontend.
+#[tauri::command]
+pub fn start_replay(
+    app: AppHandle,
+    path: String,
+    address: Option<String>,
+    port: Option<String>,
+    speed: Option<f32>,
+) -> Result<RecordingInfo, Error> {
+    let output = match (address, port) {
+        (Some(address), Some(port)) => {
+            let socket_addr = format!("{address}:{port}")
+                .to_socket_addrs()?
+                .next()
+                .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "no address"))?;
+            ReplayOutput::Udp(socket_addr)
+        }
+        _ => {
+            let buffer = spawn_data_row_batches(Arc::new(app));
+            let session = Arc::new(Mutex::new(Session::default()));
+            ReplayOutput::Direct(Box::new(move |datagram| {
+                let mut session_guard = session.lock().unwrap();
+                if let Some(packet) = session_guard.ingest_datagram(datagram) {
+                    dispatch_packet(&buffer, &packet, session_guard);
+                }
+            }))
+        }
+    };
+
+    let replay = Replay::open(path, output)?;
+    if let Some(speed) = speed {
+        replay.set_speed(speed);
+    }
+    replay.play();
+
+    let info = replay.info().clone();
+
+    // Replacing the previous replay stops it
+    *REPLAY.lock().unwrap() = Some(replay);
+    Ok(info)
+}
+
+#[tauri::command]
+pub fn stop_replay() {
+    REPLAY.lock().unwrap().take();
+}
+
+#[tauri::command]
+pub fn pause_replay() {
+    if let Some(replay) = REPLAY.lock().unwrap().as_ref() {
+        replay.pause();
+    }
+}
+
+#[tauri::command]
+pub fn resume_replay() {
+    if let Some(replay) = REPLAY.lock().unwrap().as_ref() {
+        replay.play();
+    }
+}
+
+#[tauri::command]
+pub fn seek_replay(seconds: f64) {
+    if let Some(replay) = REPLAY.lock().unwrap().as_ref() {
+        replay.seek(Duration::from_secs_f64(seconds.max(0.0)));
+    }
+}
+
+#[tauri::command]
+pub fn seek_replay_frame(frame_identifier: u32) {
+    if let Some(replay) = REPLAY.lock().unwrap().as_ref() {
+        replay.seek_frame(frame_identifier);
+    }
+}
+
+/// Pause the replay and send the next `frames` frames
+#[tauri::command]
+pub fn step_replay(frames: u32) {
+    if let Some(replay) = REPLAY.lock().unwrap().as_ref() {
+        replay.step(frames);
+    }
+}
+
+#[tauri::command]
+pub fn set_replay_speed(speed: f32) {
+    if let Some(replay) = REPLAY.lock().unwrap().as_ref() {
+        replay.set_speed(speed);
+    }
+}
+
+#[tauri::command]
+pub fn get_replay_status() -> Option<ReplayStatus> {
+    REPLAY.lock().unwrap().as_ref().map(Replay::status)
+}
+
+/// Spawn a thread that sends the DataRows pushed to the
+/// returned buffer to the frontend in batches.
+fn spawn_data_row_batches(app: Arc<AppHandle>) -> Arc<Mutex<Vec<DataRow>>> {
     // Empty batch of DataRow every 25 seconds.
     // A batch of DataRows containing info about packets will build up
     // and then be sent to the frontned.
+    // The thread stops once every other owner of the buffer is dropped.
     let buffer: Arc<Mutex<Vec<DataRow>>> = Arc::new(Mutex::new(Vec::new()));
-    let buffer_clone = buffer.clone();
+    let buffer_weak = Arc::downgrade(&buffer);
     thread::spawn(move || loop {
         thread::sleep(Duration::from_millis(25));
-        let mut batch = buffer_clone.lock().unwrap();
+        let Some(buffer) = buffer_weak.upgrade() else {
+            break;
+        };
+        let mut batch = buffer.lock().unwrap();
         if !batch.is_empty() {
             let to_send = batch.clone();
             batch.clear();
-            let _ = app_clone.emit("createDataRowBatch", to_send);
+            let _ = app.emit("createDataRowBatch", to_send);
         }
     });
 
-    let session = Session::new(address, port);
-    let session_clone = Arc::clone(&session);
-
-    // UDP 'Connection' in the below context just means
-    // the underlying Session.connection struct has an active
-    // UDP socket.
+    buffer
+}
 
-    // Thread to listen for the stop_udp_listener event from
-    // the frontend. Will cut the session UDP 'connection', causing
-    // the thread receiving packets to break, making the backend ready
-    // to establish a fresh, new connection later on.
-    thread::spawn(move || {
-        app_arc.listen("stop_udp_listener", move |_| {
-            if let Ok(mut session) = session_clone.lock() {
-                session.drop_connection();
+/// Push a DataRow describing `packet` to the buffer to be
+/// displayed by the frontend.
+fn dispatch_packet(
+    buffer: &Mutex<Vec<DataRow>>,
+    packet: &TelemetryPacket,
+    session_guard: MutexGuard<'_, Session>,
+) {
+    // Dispatch packet data to frontend
+    match packet.packet_id() {
+        PacketType::Motion => {
+            if let Some(motion) = packet.as_motion() {
+                send_packet_to_buffer!(buffer, "Motion Data", packet, motion)
             }
-        })
-    });
-
-    // Thread that will get the lastest telemtry packet with an established
-    // UDP 'connection' and then dispatch the neccessary info to the frontend
-    // to be displayed as a data row
-    thread::spawn(move || loop {
-        // Give the 'stop_udp_listener' thread a chance to
-        // obtain the lock before this thread.
-        thread::sleep(Duration::from_millis(10));
-
-        let session_guard = session.lock().unwrap();
-        if !session_guard.connected() {
-            println!("Not connected. breaking from thread");
-            break;
         }
+        PacketType::Session => {
+            if let Some(session) = packet.as_session() {
+                send_packet_to_buffer!(buffer, "Session Data", packet, session)
+            }
+        }
+        PacketType::LapData => {
+            if let Some(lap_data) = packet.as_lap_data() {
+                send_packet_to_buffer!(buffer, "Lap Data", packet, lap_data)
+            }
+        }
+        PacketType::Event => {
+            if let Some(event) = packet.as_event() {
+                let payload = DataRow {
+                    title: "Events",
+                    row_title: format!("{} ({})", event.event_name(), event.code_as_string()),
+                    timestamp: packet.session_time().to_string(),
+                    packet_id: packet.packet_id().as_u8().to_string(),
+                    raw_data: event.event_message(&session_guard.participants),
+                };
 
-        let Some(packet) = session_guard.get_latest_packet() else {
-            continue;
-        };
-
-        // Dispatch packet data to frontend
-        match packet.packet_id() {
-            PacketType::Motion => {
-                if let Some(motion) = packet.as_motion() {
-                    send_packet_to_buffer!(buffer, "Motion Data", packet, motion)
+                let mut buf = buffer.lock().unwrap();
+                buf.push(payload);
+                if buf.len() > 250 {
+                    let excess = buf.len() - 250;
+                    buf.drain(0..excess);
                 }
             }
-            PacketType::Session => {
-                if let Some(session) = packet.as_session() {
-                    send_packet_to_buffer!(buffer, "Session Data", packet, session)
-                }
+        }
+        PacketType::Participants => {
+            if let Some(participants) = packet.as_participants() {
+                send_packet_to_buffer!(buffer, "Participants", packet, participants);
             }
-            PacketType::LapData => {
-                if let Some(lap_data) = packet.as_lap_data() {
-                    send_packet_to_buffer!(buffer, "Lap Data", packet, lap_data)
-                }
+        }
+        PacketType::CarSetups => {
+            if let Some(car_setups) = packet.as_car_setups() {
+                send_packet_to_buffer!(buffer, "Car Setups", packet, car_setups)
             }
-            PacketType::Event => {
-                if let Some(event) = packet.as_event() {
-                    let payload = DataRow {
-                        title: "Events",
-                        row_title: format!("{} ({})", event.event_name(), event.code_as_string()),
-                        timestamp: packet.session_time().to_string(),
-                        packet_id: packet.packet_id().as_u8().to_string(),
-                        raw_data: event.event_message(session_guard),
-                    };
-
-                    let mut buf = buffer.lock().unwrap();
-                    buf.push(payload);
-                    if buf.len() > 250 {
-                        let excess = buf.len() - 250;
-                        buf.drain(0..excess);
-                    }
-                }
+        }
+        PacketType::CarTelemetry => {
+            if let Some(car_telemetry) = packet.as_car_telemetry() {
+                send_packet_to_buffer!(buffer, "Car Telemetry", packet, car_telemetry)
             }
-            PacketType::Participants => {
-                if let Some(participants) = packet.as_participants() {
-                    send_packet_to_buffer!(buffer, "Participants", packet, participants);
-                }
+        }
+        PacketType::CarStatus => {
+            if let Some(car_status) = packet.as_car_status() {
+                send_packet_to_buffer!(buffer, "Car Status", packet, car_status)
             }
-            PacketType::CarSetups => {
-                if let Some(car_setups) = packet.as_car_setups() {
-                    send_packet_to_buffer!(buffer, "Car Setups", packet, car_setups)
-                }
+        }
+        PacketType::FinalClassification => {
+            if let Some(final_classification) = packet.as_final_classification() {
+                send_packet_to_buffer!(buffer, "Final Classification", packet, final_classification)
             }
-            PacketType::CarTelemetry => {
-                if let Some(car_telemetry) = packet.as_car_telemetry() {
-                    send_packet_to_buffer!(buffer, "Car Telemetry", packet, car_telemetry)
-                }
+        }
+        PacketType::LobbyInfo => {
+            if let Some(lobby_info) = packet.as_lobby_info() {
+                send_packet_to_buffer!(buffer, "Lobby Info", packet, lobby_info)
             }
-            PacketType::CarStatus => {
-                if let Some(car_status) = packet.as_car_status() {
-                    send_packet_to_buffer!(buffer, "Car Status", packet, car_status)
-                }
+        }
+        PacketType::CarDamage => {
+            if let Some(car_damage) = packet.as_car_damage() {
+                send_packet_to_buffer!(buffer, "Car Damage", packet, car_damage)
             }
-            PacketType::FinalClassification => {
-                if let Some(final_classification) = packet.as_final_classification() {
-                    send_packet_to_buffer!(
-                        buffer,
-                        "Final Classification",
-                        packet,
-                        final_classification
-                    )
-                }
+        }
+        PacketType::SessionHistory => {
+            if let Some(session_history) = packet.as_session_history() {
+                send_packet_to_buffer!(buffer, "Session History", packet, session_history)
             }
-            PacketType::LobbyInfo => {
-                if let Some(lobby_info) = packet.as_lobby_info() {
-                    send_packet_to_buffer!(buffer, "Lobby Info", packet, lobby_info)
-                }
+        }
+        PacketType::TyreSets => {
+            if let Some(tyre_sets) = packet.as_tyre_sets() {
+                send_packet_to_buffer!(buffer, "Tyre Sets", packet, tyre_sets)
             }
-            PacketType::CarDamage => {
-                if let Some(car_damage) = packet.as_car_damage() {
-                    send_packet_to_buffer!(buffer, "Car Damage", packet, car_damage)
-                }
+        }
+        PacketType::MotionEx => {
+            if let Some(motion_ex) = packet.as_motion_ex() {
+                send_packet_to_buffer!(buffer, "Motion Ex Data", packet, motion_ex)
             }
-            PacketType::SessionHistory => {
-                if let Some(session_history) = packet.as_session_history() {
-                    send_packet_to_buffer!(buffer, "Session History", packet, session_history)
-                }
+        }
+        PacketType::TimeTrial => {
+            if let Some(time_trial) = packet.as_time_trial() {
+                send_packet_to_buffer!(buffer, "Time Trial", packet, time_trial)
             }
-            _ => (),
         }
-    });
-
-    thread::sleep(Duration::from_millis(100));
-
-    Ok(true)
+        PacketType::LapPositions => {
+            if let Some(lap_positions) = packet.as_lap_positions() {
+                send_packet_to_buffer!(buffer, "Lap Positions", packet, lap_positions)
+            }
+        }
+        _ => (),
+    }
 }
 
 #[tauri::command]
@@ -0,0 +1,313 @@
+use std::{
+    collections::HashMap,
+    fmt,
+    hash::Hash,
+    mem::{discriminant, Discriminant},
+    ops::Deref,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        mpsc::{sync_channel, Receiver, SyncSender, TrySendError},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+use crate::core::{
+    ids::PacketType, DerivedEvent, DerivedEventFilter, DerivedEventUpdate, RaceStateChange,
+    RaceStateUpdate, TelemetryPacket,
+};
+
+/// Queue length used by [`SubscribeOptions::default`]
+pub const DEFAULT_SUBSCRIPTION_QUEUE_LEN: usize = 1024;
+
+/// Which packets a subscriber receives.
+#[derive(Debug, Clone, Default)]
+pub struct PacketFilter {
+    /// Packet types to receive, or every type if empty
+    pub packet_types: Vec<PacketType>,
+
+    /// Only receive packets with data about this car
+    pub vehicle_idx: Option<u8>,
+}
+
+impl PacketFilter {
+    /// Every packet of `packet_type`
+    pub fn packet_type(packet_type: PacketType) -> Self {
+        Self {
+            packet_types: vec![packet_type],
+            vehicle_idx: None,
+        }
+    }
+
+    /// Every packet with data about the car at `vehicle_idx`
+    pub fn car(vehicle_idx: u8) -> Self {
+        Self {
+            packet_types: Vec::new(),
+            vehicle_idx: Some(vehicle_idx),
+        }
+    }
+
+    pub fn matches(&self, packet: &TelemetryPacket) -> bool {
+        if !self.packet_types.is_empty() && !self.packet_types.contains(&packet.packet_id()) {
+            return false;
+        }
+
+        let Some(vehicle_idx) = self.vehicle_idx else {
+            return true;
+        };
+
+        match packet {
+            TelemetryPacket::Event(event) => event.vehicle_indices().contains(&vehicle_idx),
+            TelemetryPacket::TyreSets(tyre_sets) => tyre_sets.car_idx == vehicle_idx,
+            TelemetryPacket::SessionHistory(history) => history.car_idx == vehicle_idx,
+
+            // Only sent for the player's car
+            TelemetryPacket::MotionEx(_) => packet.header().player_car_index == vehicle_idx,
+
+            // Not about any car
+            TelemetryPacket::Session(_) | TelemetryPacket::TimeTrial(_) => false,
+
+            // Data for every car
+            _ => true,
+        }
+    }
+}
+
+/// Which race state changes a subscriber receives.
+#[derive(Debug, Clone, Default)]
+pub struct EventFilter {
+    /// Kinds of change to receive, or every change if empty.
+    /// Only the kind is compared, e.g. any `TyreSets { .. }`
+    /// matches every `TyreSets` change.
+    pub changes: Vec<RaceStateChange>,
+}
+
+impl EventFilter {
+    pub fn matches(&self, change: &RaceStateChange) -> bool {
+        self.changes.is_empty()
+            || self
+                .changes
+                .iter()
+                .any(|kind| discriminant(kind) == discriminant(change))
+    }
+}
+
+/// Queue and rate limit of a subscription.
+#[derive(Debug, Clone, Copy)]
+pub struct SubscribeOptions {
+    /// Messages that can be waiting to be received before new
+    /// ones are dropped for this subscriber
+    pub queue_len: usize,
+
+    /// Max messages per second for each packet type, or each kind of
+    /// change. Messages over the limit are skipped. None for no limit.
+    pub max_rate_hz: Option<f32>,
+}
+
+impl Default for SubscribeOptions {
+    fn default() -> Self {
+        Self {
+            queue_len: DEFAULT_SUBSCRIPTION_QUEUE_LEN,
+            max_rate_hz: None,
+        }
+    }
+}
+
+/// The receiving end of a subscription to a [`PacketBus`].
+///
+/// Dereferences to the underlying channel. Dropping it unsubscribes.
+#[derive(Debug)]
+pub struct Subscription<T> {
+    receiver: Receiver<T>,
+    dropped: Arc<AtomicU64>,
+}
+
+impl<T> Subscription<T> {
+    /// Messages dropped because the queue was full
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+}
+
+impl<T> Deref for Subscription<T> {
+    type Target = Receiver<T>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.receiver
+    }
+}
+
+struct Subscriber<T, F, K> {
+    filter: F,
+    sender: SyncSender<T>,
+    min_interval: Option<Duration>,
+
+    /// When a message was last sent, for each rate limited key
+    last_sent: HashMap<K, Instant>,
+    dropped: Arc<AtomicU64>,
+}
+
+impl<T, F, K: Hash + Eq> Subscriber<T, F, K> {
+    fn new(filter: F, options: SubscribeOptions) -> (Self, Subscription<T>) {
+        let (sender, receiver) = sync_channel(options.queue_len);
+        let dropped = Arc::new(AtomicU64::new(0));
+
+        let min_interval = options
+            .max_rate_hz
+            .filter(|hz| hz.is_finite() && *hz > 0.0)
+            .map(|hz| Duration::from_secs_f32(1.0 / hz));
+
+        let subscriber = Self {
+            filter,
+            sender,
+            min_interval,
+            last_sent: HashMap::new(),
+            dropped: Arc::clone(&dropped),
+        };
+
+        (subscriber, Subscription { receiver, dropped })
+    }
+
+    /// Send `message` unless rate limited.
+    ///
+    /// Returns false if the subscription was dropped.
+    fn send(&mut self, key: K, message: T) -> bool {
+        if let Some(min_interval) = self.min_interval {
+            let now = Instant::now();
+            match self.last_sent.get(&key) {
+                Some(last) if now.duration_since(*last) < min_interval => return true,
+                _ => {
+                    self.last_sent.insert(key, now);
+                }
+            }
+        }
+
+        match self.sender.try_send(message) {
+            Ok(()) => true,
+            Err(TrySendError::Full(_)) => {
+                self.dropped.fetch_add(1, Ordering::Relaxed);
+                true
+            }
+            Err(TrySendError::Disconnected(_)) => false,
+        }
+    }
+}
+
+#[derive(Default)]
+struct Subscribers {
+    packets: Vec<Subscriber<Arc<TelemetryPacket>, PacketFilter, PacketType>>,
+    events: Vec<Subscriber<RaceStateUpdate, EventFilter, Discriminant<RaceStateChange>>>,
+    derived: Vec<Subscriber<DerivedEventUpdate, DerivedEventFilter, Discriminant<DerivedEvent>>>,
+}
+
+impl fmt::Debug for Subscribers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Subscribers")
+            .field("packets", &self.packets.len())
+            .field("events", &self.events.len())
+            .field("derived", &self.derived.len())
+            .finish()
+    }
+}
+
+/// Publish/subscribe bus for packets, race state changes and derived events.
+///
+/// Every subscriber has its own queue and rate limit, so a slow
+/// subscriber only drops its own messages and never holds up the
+/// others or the publisher. Clones share the same subscribers.
+#[derive(Debug, Clone, Default)]
+pub struct PacketBus {
+    subscribers: Arc<Mutex<Subscribers>>,
+}
+
+impl PacketBus {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Receive every packet matching `filter`
+    pub fn subscribe_packets(
+        &self,
+        filter: PacketFilter,
+        options: SubscribeOptions,
+    ) -> Subscription<Arc<TelemetryPacket>> {
+        let (subscriber, subscription) = Subscriber::new(filter, options);
+        self.subscribers.lock().unwrap().packets.push(subscriber);
+        subscription
+    }
+
+    /// Receive every race state change matching `filter`
+    pub fn subscribe_events(
+        &self,
+        filter: EventFilter,
+        options: SubscribeOptions,
+    ) -> Subscription<RaceStateUpdate> {
+        let (subscriber, subscription) = Subscriber::new(filter, options);
+        self.subscribers.lock().unwrap().events.push(subscriber);
+        subscription
+    }
+
+    /// Receive every derived event matching `filter`, see [`EventDetector`](crate::core::EventDetector)
+    pub fn subscribe_derived(
+        &self,
+        filter: DerivedEventFilter,
+        options: SubscribeOptions,
+    ) -> Subscription<DerivedEventUpdate> {
+        let (subscriber, subscription) = Subscriber::new(filter, options);
+        self.subscribers.lock().unwrap().derived.push(subscriber);
+        subscription
+    }
+
+    /// Number of packet, event and derived event subscribers
+    pub fn num_subscribers(&self) -> usize {
+        let subscribers = self.subscribers.lock().unwrap();
+        subscribers.packets.len() + subscribers.events.len() + subscribers.derived.len()
+    }
+
+    /// Send a packet to every subscriber it matches
+    pub fn publish_packet(&self, packet: &TelemetryPacket) {
+        let mut subscribers = self.subscribers.lock().unwrap();
+        if subscribers.packets.is_empty() {
+            return;
+        }
+
+        // Shared by every subscriber instead of copied for each
+        let shared = Arc::new(*packet);
+        let packet_type = packet.packet_id();
+
+        // Subscribers whose subscription was dropped are removed
+        subscribers.packets.retain_mut(|subscriber| {
+            !subscriber.filter.matches(packet) || subscriber.send(packet_type, Arc::clone(&shared))
+        });
+    }
+
+    /// Send race state changes to every subscriber they match
+    pub fn publish_events(&self, updates: &[RaceStateUpdate]) {
+        let mut subscribers = self.subscribers.lock().unwrap();
+        if subscribers.events.is_empty() {
+            return;
+        }
+
+        subscribers.events.retain_mut(|subscriber| {
+            updates.iter().all(|update| {
+                !subscriber.filter.matches(&update.change)
+                    || subscriber.send(discriminant(&update.change), *update)
+            })
+        });
+    }
+
+    /// Send derived events to every subscriber they match
+    pub fn publish_derived(&self, updates: &[DerivedEventUpdate]) {
+        let mut subscribers = self.subscribers.lock().unwrap();
+        if subscribers.derived.is_empty() {
+            return;
+        }
+
+        subscribers.derived.retain_mut(|subscriber| {
+            updates.iter().all(|update| {
+                !subscriber.filter.matches(&update.event)
+                    || subscriber.send(discriminant(&update.event), *update)
+            })
+        });
+    }
+}
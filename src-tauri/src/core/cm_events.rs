@@ -22,6 +22,10 @@
 /// Stop go served       -   "SGSV"   -   Stop go penalty served
 /// Flashback            -   "FLBK"   -   Flashback activated
 /// Button status        -   "BUTN"   -   Button status changed
+/// Red Flag             -   "RDFL"   -   Red flag shown
+/// Overtake             -   "OVTK"   -   Overtake occurred
+/// Safety Car           -   "SCAR"   -   Safety car event - details in event
+/// Collision            -   "COLL"   -   Collision between two vehicles has occurred
 ///
 /// 'Code' is a 4 character code defined in CodeMasters' PacketEventData
 /// to represent an event. See [`PacketEventData`](PacketEventData)
@@ -193,5 +197,41 @@ pub static CM_EVENTS: LazyLock<HashMap<&str, Event>> = LazyLock::new(|| {
             id: EventId::ButtonStatus,
         },
     );
+    m.insert(
+        "RDFL",
+        Event {
+            event: "Red Flag",
+            code: "RDFL",
+            description: "Red flag shown",
+            id: EventId::RedFlag,
+        },
+    );
+    m.insert(
+        "OVTK",
+        Event {
+            event: "Overtake",
+            code: "OVTK",
+            description: "Overtake occurred",
+            id: EventId::Overtake,
+        },
+    );
+    m.insert(
+        "SCAR",
+        Event {
+            event: "Safety Car",
+            code: "SCAR",
+            description: "Safety car event - details in event",
+            id: EventId::SafetyCar,
+        },
+    );
+    m.insert(
+        "COLL",
+        Event {
+            event: "Collision",
+            code: "COLL",
+            description: "Collision between two vehicles has occurred",
+            id: EventId::Collision,
+        },
+    );
     m
 });
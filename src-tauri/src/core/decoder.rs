@@ -1,70 +1,1348 @@
-use crate::core::{ids::PacketType, PacketHeader, TelemetryPacket};
+use std::{fmt, ops::RangeInclusive, time::Duration};
 
-/// Attempt to create a TelemetryPacket from a buffer
-///
-/// Inspects the PacketHeader's packet_id field to create the respective
-/// Packet structure. If the length of the buffer is less than the size of PacketHeader, or
-/// packet_id is invalid, None will be returned.
-///
-/// See [`packet_id`](PacketHeader::packet_id) for a list of valid packet ID's.
-///
-/// # Safety
-/// This function casts the buffer to a 'PacketHeader' struct using
-/// std::ptr::read(). Based on the newly casted PacketHeader's packet_id field,
-/// buffer will be casted to the respective packet struct, again using std::ptr::read().
+use crate::core::{
+    ids::{
+        DriverId, FormulaType, GameModeId, InfringementType, NationalityId, PacketType,
+        PenaltyType, RulesetId, SessionLength, SessionType, SurfaceType, TeamId, TrackId,
+        WeatherType,
+    },
+    packets::*,
+    RaceEvent,
+};
+
+/// Packet formats (F1 22 - F1 25) understood by the decoder.
+pub const SUPPORTED_PACKET_FORMATS: RangeInclusive<u16> = 2022..=2025;
+
+/// Reasons a datagram could not be decoded into a TelemetryPacket.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DecodeError {
+    /// The buffer ended before a field could be read.
+    TooShort { needed: usize, available: usize },
+
+    /// The buffer length does not match the documented size
+    /// of the packet type in the header.
+    WrongSize {
+        packet_id: PacketType,
+        expected: usize,
+        actual: usize,
+    },
+
+    /// A byte did not match any variant of the enum it encodes.
+    UnknownEnumValue { name: &'static str, value: i64 },
+
+    /// The header's packet_format is not one Solis can decode.
+    UnsupportedFormat(u16),
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecodeError::TooShort { needed, available } => write!(
+                f,
+                "buffer too short: needed {needed} bytes, {available} available"
+            ),
+            DecodeError::WrongSize {
+                packet_id,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "wrong size for {packet_id:?} packet: expected {expected} bytes, got {actual}"
+            ),
+            DecodeError::UnknownEnumValue { name, value } => {
+                write!(f, "unknown {name} value: {value}")
+            }
+            DecodeError::UnsupportedFormat(format) => {
+                write!(f, "unsupported packet format: {format}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+/// Bounds checked, little endian cursor over a received datagram.
 ///
+/// Every read advances the cursor and fails with [`DecodeError::TooShort`]
+/// instead of reading past the end of the buffer.
 ///
-/// The caller is responsible for ensuring the buffer is the correct
-/// size and layout.
-pub fn parse_packet(buffer: &[u8]) -> Option<TelemetryPacket> {
-    if buffer.len() < std::mem::size_of::<PacketHeader>() {
-        return None;
+/// The reader also carries the packet format of the datagram so
+/// decoders can pick the layout of the season that sent it.
+pub struct PacketReader<'a> {
+    buffer: &'a [u8],
+    position: usize,
+    format: u16,
+}
+
+impl<'a> PacketReader<'a> {
+    pub fn new(buffer: &'a [u8], format: u16) -> Self {
+        Self {
+            buffer,
+            position: 0,
+            format,
+        }
     }
 
-    // Cast buffer to header to read the packet_id
-    let header = unsafe { std::ptr::read(buffer.as_ptr() as *const PacketHeader) };
+    /// Packet format (e.g. 2023) of the datagram being read
+    pub fn format(&self) -> u16 {
+        self.format
+    }
 
-    // Based on packet id, cast to a specific structure for
-    // respective, relevant data
-    let packet = match header.packet_id {
-        PacketType::Motion => {
-            TelemetryPacket::Motion(unsafe { std::ptr::read(buffer.as_ptr() as *const _) })
+    /// Number of bytes read so far
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Number of bytes left to read
+    pub fn remaining(&self) -> usize {
+        self.buffer.len() - self.position
+    }
+
+    /// Read the next `len` bytes as a slice.
+    pub fn bytes(&mut self, len: usize) -> Result<&'a [u8], DecodeError> {
+        if self.remaining() < len {
+            return Err(DecodeError::TooShort {
+                needed: self.position + len,
+                available: self.buffer.len(),
+            });
         }
-        PacketType::Session => {
-            TelemetryPacket::Session(unsafe { std::ptr::read(buffer.as_ptr() as *const _) })
+
+        let bytes = &self.buffer[self.position..self.position + len];
+        self.position += len;
+        Ok(bytes)
+    }
+
+    /// Split off a reader over the next `len` bytes, advancing
+    /// this reader past them regardless of how much of the
+    /// split reader is consumed.
+    pub fn split(&mut self, len: usize) -> Result<PacketReader<'a>, DecodeError> {
+        Ok(PacketReader::new(self.bytes(len)?, self.format))
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], DecodeError> {
+        let mut out = [0u8; N];
+        out.copy_from_slice(self.bytes(N)?);
+        Ok(out)
+    }
+
+    /// Decode the next value of type T.
+    pub fn read<T: Decode>(&mut self) -> Result<T, DecodeError> {
+        T::decode(self)
+    }
+}
+
+/// A type that can be decoded from the F1 UDP wire format.
+pub trait Decode: Sized {
+    fn decode(reader: &mut PacketReader) -> Result<Self, DecodeError>;
+}
+
+macro_rules! decode_primitive {
+    ($($ty:ty),*) => {
+        $(
+            impl Decode for $ty {
+                fn decode(reader: &mut PacketReader) -> Result<Self, DecodeError> {
+                    Ok(<$ty>::from_le_bytes(reader.array()?))
+                }
+            }
+        )*
+    };
+}
+
+decode_primitive!(u8, i8, u16, i16, u32, u64, f32, f64);
+
+impl<T: Decode, const N: usize> Decode for [T; N] {
+    fn decode(reader: &mut PacketReader) -> Result<Self, DecodeError> {
+        let items = (0..N)
+            .map(|_| reader.read())
+            .collect::<Result<Vec<T>, _>>()?;
+
+        match items.try_into() {
+            Ok(array) => Ok(array),
+            Err(_) => unreachable!("exactly N items were decoded"),
         }
-        PacketType::LapData => {
-            TelemetryPacket::LapData(unsafe { std::ptr::read(buffer.as_ptr() as *const _) })
+    }
+}
+
+/// Decode an id enum from its underlying representation, rejecting
+/// values that have no matching variant.
+macro_rules! decode_id {
+    ($($ty:ident: $repr:ty),*) => {
+        $(
+            impl Decode for $ty {
+                fn decode(reader: &mut PacketReader) -> Result<Self, DecodeError> {
+                    let raw: $repr = reader.read()?;
+                    $ty::try_from(raw).map_err(|value| DecodeError::UnknownEnumValue {
+                        name: stringify!($ty),
+                        value: value as i64,
+                    })
+                }
+            }
+        )*
+    };
+}
+
+decode_id!(
+    PacketType: u8,
+    TrackId: i8,
+    NationalityId: u8,
+    GameModeId: u8,
+    RulesetId: u8,
+    SurfaceType: u8,
+    PenaltyType: u8,
+    InfringementType: u8,
+    WeatherType: u8,
+    FormulaType: u8,
+    SessionLength: u8
+);
+
+/// Decode an id enum whose values were reassigned between seasons,
+/// looking the raw value up in the table for the reader's packet format.
+macro_rules! decode_seasonal_id {
+    ($($ty:ident),*) => {
+        $(
+            impl Decode for $ty {
+                fn decode(reader: &mut PacketReader) -> Result<Self, DecodeError> {
+                    let raw: u8 = reader.read()?;
+                    $ty::from_format(reader.format(), raw).map_err(|value| {
+                        DecodeError::UnknownEnumValue {
+                            name: stringify!($ty),
+                            value: value as i64,
+                        }
+                    })
+                }
+            }
+        )*
+    };
+}
+
+decode_seasonal_id!(TeamId, DriverId, SessionType);
+
+/// Decode a single field for [`decode_struct`].
+macro_rules! decode_field {
+    ($reader:ident) => {
+        $reader.read()?
+    };
+    ($reader:ident, if $formats:pat) => {
+        if matches!($reader.format(), $formats) {
+            $reader.read()?
+        } else {
+            Default::default()
         }
-        PacketType::Event => {
-            TelemetryPacket::Event(unsafe { std::ptr::read(buffer.as_ptr() as *const _) })
+    };
+    ($reader:ident, with $decode:path) => {
+        $decode($reader)?
+    };
+}
+
+/// Decode a struct by reading each listed field in order.
+///
+/// Fields must be listed in the same order they appear on the wire.
+/// A field only sent by some seasons is written as `field if 2023..`,
+/// and is left as its default value for other packet formats. A field
+/// whose layout changes between seasons is written as
+/// `field => decode_fn` to decode it with a custom function.
+macro_rules! decode_struct {
+    ($name:ident { $($field:ident $(if $formats:pat)? $(=> $decode:path)?),* $(,)? }) => {
+        impl Decode for $name {
+            fn decode(reader: &mut PacketReader) -> Result<Self, DecodeError> {
+                Ok($name {
+                    $($field: decode_field!(reader $(, if $formats)? $(, with $decode)?),)*
+                })
+            }
         }
-        PacketType::Participants => {
-            TelemetryPacket::Participants(unsafe { std::ptr::read(buffer.as_ptr() as *const _) })
+    };
+}
+
+/// Decode `count` entries into the start of an array, leaving the
+/// rest of the array as default values.
+fn decode_prefix<T: Decode + Default + Copy, const N: usize>(
+    reader: &mut PacketReader,
+    count: usize,
+) -> Result<[T; N], DecodeError> {
+    let mut entries = [T::default(); N];
+    for entry in entries.iter_mut().take(count) {
+        *entry = reader.read()?;
+    }
+
+    Ok(entries)
+}
+
+/// Convert a time in seconds sent by the game to a Duration.
+/// Times that aren't set may be negative or NaN, and become 0.
+fn seconds(time: f32) -> Duration {
+    Duration::try_from_secs_f32(time).unwrap_or_default()
+}
+
+/// Names are 48 bytes before F1 25 and 32 bytes from F1 25 onwards.
+fn decode_name(reader: &mut PacketReader) -> Result<[u8; 48], DecodeError> {
+    let len = if reader.format() >= 2025 { 32 } else { 48 };
+    decode_prefix(reader, len)
+}
+
+/// F1 24 raised the number of weather forecast samples from 56 to 64.
+fn decode_forecast_samples(
+    reader: &mut PacketReader,
+) -> Result<[WeatherForecastSample; 64], DecodeError> {
+    let len = if reader.format() >= 2024 { 64 } else { 56 };
+    decode_prefix(reader, len)
+}
+
+decode_struct!(PacketHeader {
+    packet_format,
+    game_year if 2023..,
+    game_major_version,
+    game_minor_version,
+    packet_version,
+    packet_id,
+    session_uid,
+    session_time,
+    frame_identifier,
+    overall_frame_identifier if 2023..,
+    player_car_index,
+    secondary_player_car_index,
+});
+
+decode_struct!(CarMotionData {
+    world_position_x,
+    world_position_y,
+    world_position_z,
+    world_velocity_x,
+    world_velocity_y,
+    world_velocity_z,
+    world_forward_dir_x,
+    world_forward_dir_y,
+    world_forward_dir_z,
+    world_right_dir_x,
+    world_right_dir_y,
+    world_right_dir_z,
+    g_force_lateral,
+    g_force_longitudinal,
+    g_force_vertical,
+    yaw,
+    pitch,
+    roll,
+});
+
+decode_struct!(PacketMotionData {
+    header,
+    car_motion_data,
+    suspension_velocity if 2022,
+    suspension_acceleration if 2022,
+    suspension_position if 2022,
+    wheel_speed if 2022,
+    wheel_slip if 2022,
+    local_velocity_x if 2022,
+    local_velocity_y if 2022,
+    local_velocity_z if 2022,
+    angular_velocity_x if 2022,
+    angular_velocity_y if 2022,
+    angular_velocity_z if 2022,
+    angular_acceleration_x if 2022,
+    angular_acceleration_y if 2022,
+    angular_acceleration_z if 2022,
+    front_wheels_angle if 2022,
+});
+
+decode_struct!(PacketMotionExData {
+    header,
+    suspension_position,
+    suspension_velocity,
+    suspension_acceleration,
+    wheel_speed,
+    wheel_slip_ratio,
+    wheel_slip_angle,
+    wheel_lat_force,
+    wheel_long_force,
+    height_of_cog_above_ground,
+    local_velocity_x,
+    local_velocity_y,
+    local_velocity_z,
+    angular_velocity_x,
+    angular_velocity_y,
+    angular_velocity_z,
+    angular_acceleration_x,
+    angular_acceleration_y,
+    angular_acceleration_z,
+    front_wheels_angle,
+    wheel_vert_force,
+    front_aero_height if 2024..,
+    rear_aero_height if 2024..,
+    front_roll_angle if 2024..,
+    rear_roll_angle if 2024..,
+    chassis_yaw if 2024..,
+    chassis_pitch if 2025..,
+    wheel_camber if 2025..,
+    wheel_camber_gain if 2025..,
+});
+
+decode_struct!(MarshalZone {
+    zone_start,
+    zone_flag,
+});
+
+decode_struct!(WeatherForecastSample {
+    session_type,
+    time_offset,
+    weather,
+    track_temperature,
+    track_temperature_change,
+    air_temperature,
+    air_temperature_change,
+    rain_percentage,
+});
+
+decode_struct!(PacketSessionData {
+    header,
+    weather,
+    track_temperature,
+    air_temperature,
+    total_laps,
+    track_length,
+    session_type,
+    track_id,
+    formula,
+    session_time_left,
+    session_duration,
+    pit_speed_limit,
+    game_paused,
+    is_spectating,
+    spectator_car_index,
+    sli_pro_native_support,
+    num_marshal_zones,
+    marshal_zones,
+    safety_car_status,
+    network_game,
+    num_weather_forecast_samples,
+    weather_forecast_samples => decode_forecast_samples,
+    forecast_accuracy,
+    ai_difficulty,
+    season_link_identifier,
+    weekend_link_identifier,
+    session_link_identifier,
+    pit_stop_window_ideal_lap,
+    pit_stop_window_latest_lap,
+    pit_stop_rejoin_position,
+    steering_assist,
+    braking_assist,
+    gearbox_assist,
+    pit_assist,
+    pit_release_assist,
+    ers_assist,
+    drs_assist,
+    dynamic_racing_line,
+    dynamic_racing_line_type,
+    game_mode,
+    rule_set,
+    time_of_day,
+    session_length,
+    speed_units_lead_player if 2023..,
+    temperature_units_lead_player if 2023..,
+    speed_units_secondary_player if 2023..,
+    temperature_units_secondary_player if 2023..,
+    num_safety_car_periods if 2023..,
+    num_virtual_safety_car_periods if 2023..,
+    num_red_flag_periods if 2023..,
+    equal_car_performance if 2024..,
+    recovery_mode if 2024..,
+    flashback_limit if 2024..,
+    surface_type if 2024..,
+    low_fuel_mode if 2024..,
+    race_starts if 2024..,
+    tyre_temperature if 2024..,
+    pit_lane_tyre_sim if 2024..,
+    car_damage if 2024..,
+    car_damage_rate if 2024..,
+    collisions if 2024..,
+    collisions_off_for_first_lap_only if 2024..,
+    mp_unsafe_pit_release if 2024..,
+    mp_off_for_griefing if 2024..,
+    corner_cutting_stringency if 2024..,
+    parc_ferme_rules if 2024..,
+    pit_stop_experience if 2024..,
+    safety_car if 2024..,
+    safety_car_experience if 2024..,
+    formation_lap if 2024..,
+    formation_lap_experience if 2024..,
+    red_flags if 2024..,
+    affects_licence_level_solo if 2024..,
+    affects_licence_level_mp if 2024..,
+    num_sessions_in_weekend if 2024..,
+    weekend_structure if 2024..,
+    sector2_lap_distance_start if 2024..,
+    sector3_lap_distance_start if 2024..,
+});
+
+decode_struct!(LapData {
+    last_lap_time_in_ms,
+    current_lap_time_in_ms,
+    sector1_time_in_ms,
+    sector1_time_minutes if 2023..,
+    sector2_time_in_ms,
+    sector2_time_minutes if 2023..,
+    delta_to_car_in_front_in_ms if 2023..,
+    delta_to_car_in_front_minutes if 2024..,
+    delta_to_race_leader_in_ms if 2023..,
+    delta_to_race_leader_minutes if 2024..,
+    lap_distance,
+    total_distance,
+    safety_car_delta,
+    car_position,
+    current_lap_num,
+    pit_status,
+    num_pit_stops,
+    sector,
+    current_lap_invalid,
+    penalties,
+    warnings,
+    corner_cutting_warnings if 2023..,
+    num_unserved_drive_through_pens,
+    num_unserved_stop_go_pens,
+    grid_position,
+    driver_status,
+    result_status,
+    pit_lane_timer_active,
+    pit_lane_time_in_lane_in_ms,
+    pit_stop_timer_in_ms,
+    pit_stop_should_serve_pen,
+    speed_trap_fastest_speed if 2024..,
+    speed_trap_fastest_lap if 2024..,
+});
+
+decode_struct!(PacketLapData {
+    header,
+    lap_data,
+    time_trial_pb_car_idx,
+    time_trial_rival_car_idx,
+});
+
+decode_struct!(FastestLap {
+    vehicle_idx,
+    lap_time,
+});
+
+decode_struct!(Retirement { vehicle_idx });
+decode_struct!(TeamMateInPits { vehicle_idx });
+decode_struct!(RaceWinner { vehicle_idx });
+
+decode_struct!(Penalty {
+    penalty_type,
+    infringement_type,
+    vehicle_idx,
+    other_vehicle_idx,
+    time,
+    lap_num,
+    places_gained,
+});
+
+decode_struct!(SpeedTrap {
+    vehicle_idx,
+    speed,
+    is_overall_fastest_in_session,
+    is_driver_fastest_in_session,
+    fastest_vehicle_idx_in_session,
+    fastest_speed_in_session,
+});
+
+decode_struct!(StartLights { num_lights });
+decode_struct!(DriveThroughPenaltyServed { vehicle_idx });
+decode_struct!(StopGoPenaltyServed { vehicle_idx });
+
+decode_struct!(Flashback {
+    flashback_frame_identifier,
+    flashback_session_time,
+});
+
+decode_struct!(Buttons { button_status });
+
+decode_struct!(Overtake {
+    overtaking_vehicle_idx,
+    being_overtaken_vehicle_idx,
+});
+
+decode_struct!(SafetyCar {
+    safety_car_type,
+    event_type,
+});
+
+decode_struct!(Collision {
+    vehicle1_idx,
+    vehicle2_idx,
+});
+
+impl Decode for PacketEventData {
+    fn decode(reader: &mut PacketReader) -> Result<Self, DecodeError> {
+        let header = reader.read()?;
+        let event_string_code: [u8; 4] = reader.read()?;
+
+        // Only the details matching the event code are decoded
+        let event = match &event_string_code {
+            b"SSTA" => RaceEvent::SessionStarted,
+            b"SEND" => RaceEvent::SessionEnded,
+            b"FTLP" => {
+                let details: FastestLap = reader.read()?;
+                RaceEvent::FastestLap {
+                    car: details.vehicle_idx,
+                    time: seconds(details.lap_time),
+                }
+            }
+            b"RTMT" => {
+                let details: Retirement = reader.read()?;
+                RaceEvent::Retirement {
+                    car: details.vehicle_idx,
+                }
+            }
+            b"DRSE" => RaceEvent::DrsEnabled,
+            b"DRSD" => RaceEvent::DrsDisabled,
+            b"TMPT" => {
+                let details: TeamMateInPits = reader.read()?;
+                RaceEvent::TeamMateInPits {
+                    car: details.vehicle_idx,
+                }
+            }
+            b"CHQF" => RaceEvent::ChequeredFlag,
+            b"RCWN" => {
+                let details: RaceWinner = reader.read()?;
+                RaceEvent::RaceWinner {
+                    car: details.vehicle_idx,
+                }
+            }
+            b"PENA" => {
+                let details: Penalty = reader.read()?;
+                RaceEvent::Penalty {
+                    penalty: details.penalty_type,
+                    infringement: details.infringement_type,
+                    car: details.vehicle_idx,
+                    other_car: Some(details.other_vehicle_idx).filter(|&idx| idx != 255),
+                    time: Duration::from_secs(details.time as u64),
+                    lap: details.lap_num,
+                    places_gained: details.places_gained,
+                }
+            }
+            b"SPTP" => {
+                let details: SpeedTrap = reader.read()?;
+                RaceEvent::SpeedTrap {
+                    car: details.vehicle_idx,
+                    speed_kph: details.speed,
+                    overall_fastest: details.is_overall_fastest_in_session == 1,
+                    driver_fastest: details.is_driver_fastest_in_session == 1,
+                    fastest_car: details.fastest_vehicle_idx_in_session,
+                    fastest_speed_kph: details.fastest_speed_in_session,
+                }
+            }
+            b"STLG" => {
+                let details: StartLights = reader.read()?;
+                RaceEvent::StartLights {
+                    num_lights: details.num_lights,
+                }
+            }
+            b"LGOT" => RaceEvent::LightsOut,
+            b"DTSV" => {
+                let details: DriveThroughPenaltyServed = reader.read()?;
+                RaceEvent::DriveThroughServed {
+                    car: details.vehicle_idx,
+                }
+            }
+            b"SGSV" => {
+                let details: StopGoPenaltyServed = reader.read()?;
+                RaceEvent::StopGoServed {
+                    car: details.vehicle_idx,
+                }
+            }
+            b"FLBK" => {
+                let details: Flashback = reader.read()?;
+                RaceEvent::Flashback {
+                    frame_identifier: details.flashback_frame_identifier,
+                    session_time: details.flashback_session_time,
+                }
+            }
+            b"BUTN" => {
+                let details: Buttons = reader.read()?;
+                RaceEvent::Buttons {
+                    status: details.button_status,
+                }
+            }
+            b"RDFL" => RaceEvent::RedFlag,
+            b"OVTK" => {
+                let details: Overtake = reader.read()?;
+                RaceEvent::Overtake {
+                    overtaking_car: details.overtaking_vehicle_idx,
+                    overtaken_car: details.being_overtaken_vehicle_idx,
+                }
+            }
+            b"SCAR" => {
+                let details: SafetyCar = reader.read()?;
+                RaceEvent::SafetyCar {
+                    kind: details.safety_car_type.into(),
+                    phase: details.event_type.into(),
+                }
+            }
+            b"COLL" => {
+                let details: Collision = reader.read()?;
+                RaceEvent::Collision {
+                    car: details.vehicle1_idx,
+                    other_car: details.vehicle2_idx,
+                }
+            }
+            code => RaceEvent::Unknown { code: *code },
+        };
+
+        Ok(PacketEventData {
+            header,
+            event_string_code,
+            event,
+        })
+    }
+}
+
+decode_struct!(ParticipantData {
+    ai_controlled,
+    driver_id,
+    network_id,
+    team_id,
+    my_team,
+    race_number,
+    nationality,
+    name => decode_name,
+    your_telemetry,
+    show_online_names if 2023..,
+    tech_level if 2024..,
+    platform if 2023..,
+    num_colours if 2025..,
+    livery_colours if 2025..,
+});
+
+decode_struct!(LiveryColour { red, green, blue });
+
+/// Size in bytes of a single ParticipantData entry on the wire
+fn participant_data_size(format: u16) -> usize {
+    match format {
+        ..=2022 => 56,
+        2023 => 58,
+        2024 => 60,
+        _ => 57,
+    }
+}
+
+/// Size in bytes of a single LobbyInfoData entry on the wire
+fn lobby_info_data_size(format: u16) -> usize {
+    match format {
+        ..=2022 => 53,
+        2023 => 54,
+        2024 => 58,
+        _ => 42,
+    }
+}
+
+/// Decode a fixed array of per-car entries where only the first
+/// `num_active` entries are required to be valid.
+///
+/// The game zero fills unused slots, which may not map to a valid id
+/// (e.g. nationality 0), so an inactive slot that fails to decode is left
+/// as the default value instead of rejecting the whole packet.
+fn decode_active_entries<T: Decode + Default + Copy, const N: usize>(
+    reader: &mut PacketReader,
+    entry_size: usize,
+    num_active: u8,
+) -> Result<[T; N], DecodeError> {
+    let mut entries = [T::default(); N];
+    for (idx, entry) in entries.iter_mut().enumerate() {
+        let mut slot = reader.split(entry_size)?;
+        match slot.read() {
+            Ok(value) => *entry = value,
+            Err(_) if idx >= num_active as usize => {}
+            Err(e) => return Err(e),
+        }
+    }
+
+    Ok(entries)
+}
+
+impl Decode for PacketParticipantsData {
+    fn decode(reader: &mut PacketReader) -> Result<Self, DecodeError> {
+        let header = reader.read()?;
+        let num_active_cars = reader.read()?;
+        let entry_size = participant_data_size(reader.format());
+        let participants = decode_active_entries(reader, entry_size, num_active_cars)?;
+
+        Ok(PacketParticipantsData {
+            header,
+            num_active_cars,
+            participants,
+        })
+    }
+}
+
+decode_struct!(CarSetupData {
+    front_wing,
+    rear_wing,
+    on_throttle,
+    off_throttle,
+    front_camber,
+    rear_camber,
+    front_toe,
+    rear_toe,
+    front_suspension,
+    rear_suspension,
+    front_anti_roll_bar,
+    rear_anti_roll_bar,
+    front_suspension_height,
+    rear_suspension_height,
+    brake_pressure,
+    brake_bias,
+    engine_braking if 2024..,
+    rear_left_tyre_pressure,
+    rear_right_tyre_pressure,
+    front_left_tyre_pressure,
+    front_right_tyre_pressure,
+    ballast,
+    fuel_load,
+});
+
+decode_struct!(PacketCarSetupData {
+    header,
+    car_setups,
+    next_front_wing_value if 2024..,
+});
+
+decode_struct!(CarTelemetryData {
+    speed,
+    throttle,
+    steer,
+    brake,
+    clutch,
+    gear,
+    engine_rpm,
+    drs,
+    rev_lights_percent,
+    rev_lights_bit_value,
+    brakes_temperature,
+    tyres_surface_temperature,
+    tyres_inner_temperature,
+    engine_temperature,
+    tyres_pressure,
+    surface_type,
+});
+
+decode_struct!(PacketCarTelemetryData {
+    header,
+    car_telemetry_data,
+    mfd_panel_index,
+    mfd_panel_index_secondary_player,
+    suggested_gear,
+});
+
+decode_struct!(CarStatusData {
+    traction_control,
+    anti_lock_brakes,
+    fuel_mix,
+    front_brake_bias,
+    pit_limiter_status,
+    fuel_in_tank,
+    fuel_capacity,
+    fuel_remaining_laps,
+    max_rpm,
+    idle_rpm,
+    max_gears,
+    drs_allowed,
+    drs_activation_distance,
+    actual_tyre_compound,
+    visual_tyre_compound,
+    tyres_age_laps,
+    vehicle_fia_flags,
+    engine_power_ice if 2023..,
+    engine_power_mguk if 2023..,
+    ers_store_energy,
+    ers_deploy_mode,
+    ers_harvested_this_lap_mguk,
+    ers_harvested_this_lap_mguh,
+    ers_deployed_this_lap,
+    network_paused,
+});
+
+decode_struct!(PacketCarStatusData {
+    header,
+    car_status_data,
+});
+
+decode_struct!(FinalClassificationData {
+    position,
+    num_laps,
+    grid_position,
+    points,
+    num_pit_stops,
+    result_status,
+    result_reason if 2025..,
+    best_lap_time_in_ms,
+    total_race_time,
+    penalties_time,
+    num_penalties,
+    num_tyre_stints,
+    tyre_stints_actual,
+    tyre_stints_visual,
+    tyre_stints_end_laps,
+});
+
+decode_struct!(PacketFinalClassificationData {
+    header,
+    num_cars,
+    classification_data,
+});
+
+decode_struct!(LobbyInfoData {
+    ai_controlled,
+    team_id,
+    nationality,
+    platform if 2023..,
+    name => decode_name,
+    car_number,
+    your_telemetry if 2024..,
+    show_online_names if 2024..,
+    tech_level if 2024..,
+    ready_status,
+});
+
+impl Decode for PacketLobbyInfoData {
+    fn decode(reader: &mut PacketReader) -> Result<Self, DecodeError> {
+        let header = reader.read()?;
+        let num_players = reader.read()?;
+        let entry_size = lobby_info_data_size(reader.format());
+        let lobby_players = decode_active_entries(reader, entry_size, num_players)?;
+
+        Ok(PacketLobbyInfoData {
+            header,
+            num_players,
+            lobby_players,
+        })
+    }
+}
+
+decode_struct!(CarDamageData {
+    tyres_wear,
+    tyres_damage,
+    brakes_damage,
+    tyre_blisters if 2025..,
+    front_left_wing_damage,
+    front_right_wing_damage,
+    rear_wing_damage,
+    floor_damage,
+    diffuser_damage,
+    sidepod_damage,
+    drs_fault,
+    ers_fault,
+    gear_box_damage,
+    engine_damage,
+    engine_mguh_wear,
+    engine_es_wear,
+    engine_ce_wear,
+    engine_ice_wear,
+    engine_mguk_wear,
+    engine_tc_wear,
+    engine_blown,
+    engine_seized,
+});
+
+decode_struct!(PacketCarDamageData {
+    header,
+    car_damage_data,
+});
+
+decode_struct!(LapHistoryData {
+    lap_time_in_ms,
+    sector1_time_in_ms,
+    sector1_time_minutes if 2023..,
+    sector2_time_in_ms,
+    sector2_time_minutes if 2023..,
+    sector3_time_in_ms,
+    sector3_time_minutes if 2023..,
+    lap_valid_bit_flags,
+});
+
+decode_struct!(TyreStintHistoryData {
+    end_lap,
+    tyre_actual_compound,
+    tyre_visual_compound,
+});
+
+decode_struct!(PacketSessionHistoryData {
+    header,
+    car_idx,
+    num_laps,
+    num_tyre_stints,
+    best_lap_time_lap_num,
+    best_sector1_lap_num,
+    best_sector2_lap_num,
+    best_sector3_lap_num,
+    lap_history_data,
+    tyre_stints_history_data,
+});
+
+decode_struct!(TyreSetData {
+    actual_tyre_compound,
+    visual_tyre_compound,
+    wear,
+    available,
+    recommended_session,
+    life_span,
+    usable_life,
+    lap_delta_time,
+    fitted,
+});
+
+decode_struct!(PacketTyreSetsData {
+    header,
+    car_idx,
+    tyre_set_data,
+    fitted_idx,
+});
+
+decode_struct!(TimeTrialDataSet {
+    car_idx,
+    team_id,
+    lap_time_in_ms,
+    sector1_time_in_ms,
+    sector2_time_in_ms,
+    sector3_time_in_ms,
+    traction_control,
+    gearbox_assist,
+    anti_lock_brakes,
+    equal_car_performance,
+    custom_setup,
+    valid,
+});
+
+decode_struct!(PacketTimeTrialData {
+    header,
+    player_session_best_data_set,
+    personal_best_data_set,
+    rival_data_set,
+});
+
+decode_struct!(PacketLapPositionsData {
+    header,
+    num_laps,
+    lap_start,
+    position_for_vehicle_idx,
+});
+
+/// Documented size in bytes of each packet type, header included,
+/// for the given packet format.
+///
+/// Returns None for packet types that have no payload to decode
+/// or that are not sent by that season.
+pub fn packet_size(packet_format: u16, packet_id: PacketType) -> Option<usize> {
+    let size = match (packet_format, packet_id) {
+        (2022, PacketType::Motion) => 1464,
+        (2022, PacketType::Session) => 632,
+        (2022, PacketType::LapData) => 972,
+        (2022, PacketType::Event) => 40,
+        (2022, PacketType::Participants) => 1257,
+        (2022, PacketType::CarSetups) => 1102,
+        (2022, PacketType::CarTelemetry) => 1347,
+        (2022, PacketType::CarStatus) => 1058,
+        (2022, PacketType::FinalClassification) => 1015,
+        (2022, PacketType::LobbyInfo) => 1191,
+        (2022, PacketType::CarDamage) => 948,
+        (2022, PacketType::SessionHistory) => 1155,
+
+        (2023, PacketType::Session) => 644,
+        (2023, PacketType::LapData) => 1131,
+        (2023, PacketType::Participants) => 1306,
+        (2023, PacketType::CarSetups) => 1107,
+        (2023, PacketType::LobbyInfo) => 1218,
+        (2023, PacketType::MotionEx) => 217,
+
+        (2024, PacketType::Participants) => 1350,
+        (2024, PacketType::LobbyInfo) => 1306,
+        (2024, PacketType::MotionEx) => 237,
+
+        (2025, PacketType::Participants) => 1284,
+        (2025, PacketType::FinalClassification) => 1042,
+        (2025, PacketType::LobbyInfo) => 954,
+        (2025, PacketType::CarDamage) => 1041,
+        (2025, PacketType::MotionEx) => 273,
+        (2025, PacketType::LapPositions) => 1131,
+
+        (2024.., PacketType::Session) => 753,
+        (2024.., PacketType::LapData) => 1285,
+        (2024.., PacketType::CarSetups) => 1133,
+        (2024.., PacketType::TimeTrial) => 101,
+
+        (2023.., PacketType::Motion) => 1349,
+        (2023.., PacketType::Event) => 45,
+        (2023.., PacketType::CarTelemetry) => 1352,
+        (2023.., PacketType::CarStatus) => 1239,
+        (2023.., PacketType::FinalClassification) => 1020,
+        (2023.., PacketType::CarDamage) => 953,
+        (2023.., PacketType::SessionHistory) => 1460,
+        (2023.., PacketType::TyreSets) => 231,
+
+        _ => return None,
+    };
+
+    Some(size)
+}
+
+/// Decode only the PacketHeader of a datagram.
+///
+/// Cheaper than [`parse_packet`] when only the session, frame or packet
+/// type is needed. The rest of the datagram is not validated.
+pub fn parse_header(buffer: &[u8]) -> Result<PacketHeader, DecodeError> {
+    // The format is the first field of every season's header
+    // and decides the layout of everything after it.
+    let packet_format: u16 = PacketReader::new(buffer, 0).read()?;
+    if !SUPPORTED_PACKET_FORMATS.contains(&packet_format) {
+        return Err(DecodeError::UnsupportedFormat(packet_format));
+    }
+
+    PacketReader::new(buffer, packet_format).read()
+}
+
+/// Attempt to create a TelemetryPacket from a buffer
+///
+/// Reads the PacketHeader to determine the packet format and type, checks
+/// the buffer is exactly the documented size for that packet type in that
+/// season, then decodes the packet field by field using that season's
+/// layout. Every read is bounds checked and every id enum is validated, so
+/// a malformed or truncated datagram is rejected with a [`DecodeError`]
+/// instead of being interpreted.
+///
+/// See [`packet_id`](PacketHeader::packet_id) for a list of valid packet ID's.
+pub fn parse_packet(buffer: &[u8]) -> Result<TelemetryPacket, DecodeError> {
+    let header = parse_header(buffer)?;
+    let packet_format = header.packet_format;
+    let unknown_packet_id = DecodeError::UnknownEnumValue {
+        name: "PacketType",
+        value: header.packet_id.as_u8() as i64,
+    };
+    let expected = packet_size(packet_format, header.packet_id).ok_or(unknown_packet_id.clone())?;
+
+    if buffer.len() != expected {
+        return Err(DecodeError::WrongSize {
+            packet_id: header.packet_id,
+            expected,
+            actual: buffer.len(),
+        });
+    }
+
+    let mut reader = PacketReader::new(buffer, packet_format);
+    let packet = match header.packet_id {
+        PacketType::Motion => TelemetryPacket::Motion(reader.read()?),
+        PacketType::Session => TelemetryPacket::Session(reader.read()?),
+        PacketType::LapData => TelemetryPacket::LapData(reader.read()?),
+        PacketType::Event => TelemetryPacket::Event(reader.read()?),
+        PacketType::Participants => TelemetryPacket::Participants(reader.read()?),
+        PacketType::CarSetups => TelemetryPacket::CarSetups(reader.read()?),
+        PacketType::CarTelemetry => TelemetryPacket::CarTelemetry(reader.read()?),
+        PacketType::CarStatus => TelemetryPacket::CarStatus(reader.read()?),
+        PacketType::FinalClassification => TelemetryPacket::FinalClassification(reader.read()?),
+        PacketType::LobbyInfo => TelemetryPacket::LobbyInfo(reader.read()?),
+        PacketType::CarDamage => TelemetryPacket::CarDamage(reader.read()?),
+        PacketType::SessionHistory => TelemetryPacket::SessionHistory(reader.read()?),
+        PacketType::TyreSets => TelemetryPacket::TyreSets(reader.read()?),
+        PacketType::MotionEx => TelemetryPacket::MotionEx(reader.read()?),
+        PacketType::TimeTrial => TelemetryPacket::TimeTrial(reader.read()?),
+        PacketType::LapPositions => TelemetryPacket::LapPositions(reader.read()?),
+        PacketType::None => return Err(unknown_packet_id),
+    };
+
+    Ok(packet)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PACKET_IDS: [PacketType; 16] = [
+        PacketType::Motion,
+        PacketType::Session,
+        PacketType::LapData,
+        PacketType::Event,
+        PacketType::Participants,
+        PacketType::CarSetups,
+        PacketType::CarTelemetry,
+        PacketType::CarStatus,
+        PacketType::FinalClassification,
+        PacketType::LobbyInfo,
+        PacketType::CarDamage,
+        PacketType::SessionHistory,
+        PacketType::TyreSets,
+        PacketType::MotionEx,
+        PacketType::TimeTrial,
+        PacketType::LapPositions,
+    ];
+
+    fn header_len(packet_format: u16) -> usize {
+        if packet_format >= 2023 {
+            29
+        } else {
+            24
         }
-        PacketType::CarSetups => {
-            TelemetryPacket::CarSetups(unsafe { std::ptr::read(buffer.as_ptr() as *const _) })
+    }
+
+    /// A zeroed datagram of `len` bytes with a header for `packet_id`
+    fn datagram(packet_format: u16, packet_id: u8, len: usize) -> Vec<u8> {
+        let mut buffer = vec![0; len];
+        buffer[..2].copy_from_slice(&packet_format.to_le_bytes());
+        buffer[if packet_format >= 2023 { 6 } else { 5 }] = packet_id;
+        buffer
+    }
+
+    /// Bytes the layout of `packet_id` reads from a valid datagram
+    fn decoded_len(buffer: &[u8], packet_format: u16, packet_id: PacketType) -> usize {
+        fn read<T: Decode>(reader: &mut PacketReader) {
+            reader.read::<T>().unwrap();
         }
-        PacketType::CarTelemetry => {
-            TelemetryPacket::CarTelemetry(unsafe { std::ptr::read(buffer.as_ptr() as *const _) })
+
+        let mut reader = PacketReader::new(buffer, packet_format);
+        match packet_id {
+            PacketType::Motion => read::<PacketMotionData>(&mut reader),
+            PacketType::Session => read::<PacketSessionData>(&mut reader),
+            PacketType::LapData => read::<PacketLapData>(&mut reader),
+            PacketType::Event => read::<PacketEventData>(&mut reader),
+            PacketType::Participants => read::<PacketParticipantsData>(&mut reader),
+            PacketType::CarSetups => read::<PacketCarSetupData>(&mut reader),
+            PacketType::CarTelemetry => read::<PacketCarTelemetryData>(&mut reader),
+            PacketType::CarStatus => read::<PacketCarStatusData>(&mut reader),
+            PacketType::FinalClassification => read::<PacketFinalClassificationData>(&mut reader),
+            PacketType::LobbyInfo => read::<PacketLobbyInfoData>(&mut reader),
+            PacketType::CarDamage => read::<PacketCarDamageData>(&mut reader),
+            PacketType::SessionHistory => read::<PacketSessionHistoryData>(&mut reader),
+            PacketType::TyreSets => read::<PacketTyreSetsData>(&mut reader),
+            PacketType::MotionEx => read::<PacketMotionExData>(&mut reader),
+            PacketType::TimeTrial => read::<PacketTimeTrialData>(&mut reader),
+            PacketType::LapPositions => read::<PacketLapPositionsData>(&mut reader),
+            PacketType::None => unreachable!(),
         }
-        PacketType::CarStatus => {
-            TelemetryPacket::CarStatus(unsafe { std::ptr::read(buffer.as_ptr() as *const _) })
+
+        reader.position()
+    }
+
+    fn decode_error(buffer: &[u8]) -> DecodeError {
+        match parse_packet(buffer) {
+            Ok(packet) => panic!("decoded a {:?} packet", packet.header().packet_id),
+            Err(e) => e,
         }
-        PacketType::FinalClassification => TelemetryPacket::FinalClassification(unsafe {
-            std::ptr::read(buffer.as_ptr() as *const _)
-        }),
-        PacketType::LobbyInfo => {
-            TelemetryPacket::LobbyInfo(unsafe { std::ptr::read(buffer.as_ptr() as *const _) })
+    }
+
+    #[test]
+    fn every_layout_reads_its_documented_size() {
+        for packet_format in SUPPORTED_PACKET_FORMATS {
+            for packet_id in PACKET_IDS {
+                let Some(size) = packet_size(packet_format, packet_id) else {
+                    continue;
+                };
+
+                // Event details are a union, only the event code's part is read
+                let buffer = datagram(packet_format, packet_id.as_u8(), size);
+                let decoded_len = decoded_len(&buffer, packet_format, packet_id);
+                if packet_id == PacketType::Event {
+                    assert_eq!(decoded_len, header_len(packet_format) + 4);
+                } else {
+                    assert_eq!(decoded_len, size, "{packet_format} {packet_id:?}");
+                }
+
+                let packet = parse_packet(&buffer).unwrap();
+                assert_eq!(packet.header().packet_id, packet_id);
+                assert_eq!(packet.header().packet_format, packet_format);
+            }
         }
-        PacketType::CarDamage => {
-            TelemetryPacket::CarDamage(unsafe { std::ptr::read(buffer.as_ptr() as *const _) })
+    }
+
+    #[test]
+    fn seasons_send_their_own_packet_types() {
+        assert_eq!(packet_size(2022, PacketType::TyreSets), None);
+        assert_eq!(packet_size(2023, PacketType::TimeTrial), None);
+        assert_eq!(packet_size(2024, PacketType::LapPositions), None);
+        assert_eq!(packet_size(2025, PacketType::LapPositions), Some(1131));
+        assert_eq!(packet_size(2023, PacketType::None), None);
+    }
+
+    #[test]
+    fn rejects_truncated_and_oversized_datagrams() {
+        for packet_format in SUPPORTED_PACKET_FORMATS {
+            let size = packet_size(packet_format, PacketType::Session).unwrap();
+            for len in [size - 1, size + 1] {
+                let buffer = datagram(packet_format, PacketType::Session.as_u8(), len);
+                assert_eq!(
+                    decode_error(&buffer),
+                    DecodeError::WrongSize {
+                        packet_id: PacketType::Session,
+                        expected: size,
+                        actual: len,
+                    }
+                );
+            }
+
+            let buffer = datagram(packet_format, PacketType::Session.as_u8(), size);
+            let header_len = header_len(packet_format);
+            assert!(matches!(
+                decode_error(&buffer[..header_len - 1]),
+                DecodeError::TooShort { .. }
+            ));
         }
-        PacketType::SessionHistory => {
-            TelemetryPacket::SessionHistory(unsafe { std::ptr::read(buffer.as_ptr() as *const _) })
+
+        assert_eq!(
+            decode_error(&[0xE7]),
+            DecodeError::TooShort {
+                needed: 2,
+                available: 1,
+            }
+        );
+    }
+
+    #[test]
+    fn rejects_unsupported_formats() {
+        for packet_format in [2021, 2026] {
+            let buffer = datagram(packet_format, PacketType::Session.as_u8(), 644);
+            assert_eq!(
+                decode_error(&buffer),
+                DecodeError::UnsupportedFormat(packet_format)
+            );
         }
-        _ => return None,
-    };
+    }
+
+    #[test]
+    fn rejects_invalid_enum_ids() {
+        let buffer = datagram(2023, 99, 644);
+        assert_eq!(
+            decode_error(&buffer),
+            DecodeError::UnknownEnumValue {
+                name: "PacketType",
+                value: 99,
+            }
+        );
 
-    Some(packet)
+        // A packet type that season doesn't send
+        let buffer = datagram(2022, PacketType::TyreSets.as_u8(), 231);
+        assert_eq!(
+            decode_error(&buffer),
+            DecodeError::UnknownEnumValue {
+                name: "PacketType",
+                value: PacketType::TyreSets.as_u8() as i64,
+            }
+        );
+
+        // The weather is the first field after the header
+        let mut buffer = datagram(2023, PacketType::Session.as_u8(), 644);
+        buffer[29] = 200;
+        assert_eq!(
+            decode_error(&buffer),
+            DecodeError::UnknownEnumValue {
+                name: "WeatherType",
+                value: 200,
+            }
+        );
+
+        // Only the details of the event code are checked
+        let mut buffer = datagram(2023, PacketType::Event.as_u8(), 45);
+        buffer[29..33].copy_from_slice(b"PENA");
+        buffer[33] = 200;
+        assert_eq!(
+            decode_error(&buffer),
+            DecodeError::UnknownEnumValue {
+                name: "PenaltyType",
+                value: 200,
+            }
+        );
+    }
 }
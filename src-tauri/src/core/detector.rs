@@ -0,0 +1,505 @@
+use std::{mem::discriminant, time::Duration};
+
+use serde::{Deserialize, Serialize};
+
+use crate::core::{
+    ids::SessionType, CarStatus, FiaFlag, Lap, PacketSessionData, PitStatus, ResultStatus,
+    SafetyCarStatus, TelemetryPacket, TyreCompound, VisualTyreCompound, MAX_CARS,
+};
+
+/// Number of sectors in a lap
+const NUM_SECTORS: usize = 3;
+
+/// Something an engineer cares about that the game doesn't send an
+/// event for, inferred by [`EventDetector`] from successive packets.
+///
+/// Cars are referred to by vehicle index, sectors are 0 indexed.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum DerivedEvent {
+    /// `car` passed `overtaken_car` on track for `position`
+    Overtake {
+        car: u8,
+        overtaken_car: u8,
+        position: u8,
+    },
+    PitEntry {
+        car: u8,
+    },
+    PitExit {
+        car: u8,
+
+        /// Time spent in the pit lane, if the game timed it
+        pit_lane_time: Option<Duration>,
+    },
+    LapCompleted {
+        car: u8,
+        lap: u8,
+        time: Duration,
+        valid: bool,
+    },
+
+    /// The car's fastest time in a sector this session,
+    /// and the fastest of anyone if `overall`
+    SectorBest {
+        car: u8,
+        sector: u8,
+        time: Duration,
+        overall: bool,
+    },
+
+    /// A different set of tyres was fitted, e.g. at a pit stop
+    TyreChange {
+        car: u8,
+        compound: TyreCompound,
+        visual_compound: VisualTyreCompound,
+        previous: VisualTyreCompound,
+    },
+
+    /// The car is being shown blue flags
+    BlueFlag {
+        car: u8,
+    },
+
+    /// Yellow flags are out in a marshal zone of the sector
+    YellowFlag {
+        sector: u8,
+    },
+
+    /// No more yellow flags in the sector
+    YellowFlagCleared {
+        sector: u8,
+    },
+
+    /// A safety car or virtual safety car neutralised the race
+    SafetyCarDeployed {
+        kind: SafetyCarStatus,
+    },
+
+    /// The race went green again after a safety car of `kind`
+    SafetyCarEnded {
+        kind: SafetyCarStatus,
+    },
+}
+
+impl DerivedEvent {
+    /// Vehicle indexes of the cars the event is about, if any
+    pub fn cars(&self) -> Vec<u8> {
+        match *self {
+            DerivedEvent::Overtake {
+                car, overtaken_car, ..
+            } => vec![car, overtaken_car],
+            DerivedEvent::PitEntry { car }
+            | DerivedEvent::PitExit { car, .. }
+            | DerivedEvent::LapCompleted { car, .. }
+            | DerivedEvent::SectorBest { car, .. }
+            | DerivedEvent::TyreChange { car, .. }
+            | DerivedEvent::BlueFlag { car } => vec![car],
+            DerivedEvent::YellowFlag { .. }
+            | DerivedEvent::YellowFlagCleared { .. }
+            | DerivedEvent::SafetyCarDeployed { .. }
+            | DerivedEvent::SafetyCarEnded { .. } => Vec::new(),
+        }
+    }
+}
+
+/// A derived event sent to [`PacketBus`](crate::core::PacketBus) subscribers.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub struct DerivedEventUpdate {
+    pub event: DerivedEvent,
+
+    /// Frame of the packet the event was detected in
+    pub frame_identifier: u32,
+    pub session_time: f32,
+}
+
+/// Which derived events a subscriber receives.
+#[derive(Debug, Clone, Default)]
+pub struct DerivedEventFilter {
+    /// Kinds of event to receive, or every event if empty.
+    /// Only the kind is compared, like [`EventFilter`](crate::core::EventFilter).
+    pub events: Vec<DerivedEvent>,
+
+    /// Only receive events about this car
+    pub vehicle_idx: Option<u8>,
+}
+
+impl DerivedEventFilter {
+    pub fn matches(&self, event: &DerivedEvent) -> bool {
+        let kind_matches = self.events.is_empty()
+            || self
+                .events
+                .iter()
+                .any(|kind| discriminant(kind) == discriminant(event));
+
+        kind_matches
+            && self
+                .vehicle_idx
+                .is_none_or(|vehicle_idx| event.cars().contains(&vehicle_idx))
+    }
+}
+
+/// What the detector last saw of a car
+#[derive(Debug, Clone, Default)]
+struct CarHistory {
+    lap: Option<Lap>,
+    status: Option<CarStatus>,
+    best_sectors: [Option<Duration>; NUM_SECTORS],
+}
+
+/// Infers [`DerivedEvent`]s by comparing every Lap Data, Car Status and
+/// Session packet with the previous one.
+#[derive(Debug, Clone)]
+pub struct EventDetector {
+    cars: Vec<CarHistory>,
+    best_sectors: [Option<Duration>; NUM_SECTORS],
+
+    /// Whether each sector had a yellow flag in the last Session packet
+    yellow_sectors: [bool; NUM_SECTORS],
+    safety_car: SafetyCarStatus,
+
+    /// From the last Session packet, None until one is received
+    session_type: Option<SessionType>,
+}
+
+impl Default for EventDetector {
+    fn default() -> Self {
+        Self {
+            cars: vec![CarHistory::default(); MAX_CARS],
+            best_sectors: [None; NUM_SECTORS],
+            yellow_sectors: [false; NUM_SECTORS],
+            safety_car: SafetyCarStatus::None,
+            session_type: None,
+        }
+    }
+}
+
+impl EventDetector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Forget everything seen, e.g. when a new session starts
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    /// Compare a packet with the previous one of its type.
+    ///
+    /// Returns the events detected, other packets are ignored.
+    pub fn ingest(&mut self, packet: &TelemetryPacket) -> Vec<DerivedEvent> {
+        let mut events = Vec::new();
+
+        if let Some(lap_data) = packet.as_lap_data() {
+            let laps: Vec<Option<Lap>> = (0..MAX_CARS as u8)
+                .map(|idx| lap_data.lap(idx).filter(is_racing))
+                .collect();
+
+            // Outside of races the order is by best lap, not on track
+            if self
+                .session_type
+                .is_some_and(|session_type| session_type.is_race())
+            {
+                self.detect_overtakes(&laps, &mut events);
+            }
+            for (idx, lap) in laps.into_iter().enumerate() {
+                if let Some(lap) = &lap {
+                    self.detect_lap(idx as u8, lap, &mut events);
+                }
+                self.cars[idx].lap = lap;
+            }
+        }
+
+        if let Some(car_status) = packet.as_car_status() {
+            for idx in 0..MAX_CARS as u8 {
+                let Some(status) = car_status.status(idx) else {
+                    continue;
+                };
+
+                self.detect_status(idx, &status, &mut events);
+                self.cars[idx as usize].status = Some(status);
+            }
+        }
+
+        if let Some(session) = packet.as_session() {
+            self.session_type = Some(session.session_type);
+            self.detect_yellow_flags(session, &mut events);
+            self.detect_safety_car(session, &mut events);
+        }
+
+        events
+    }
+
+    /// A car overtook another if it was behind it in the last
+    /// Lap Data packet and is ahead now, and neither pitted
+    fn detect_overtakes(&self, laps: &[Option<Lap>], events: &mut Vec<DerivedEvent>) {
+        let on_track = |lap: &Lap| lap.position > 0 && lap.pit_status == PitStatus::None;
+
+        let positions: Vec<Option<(u8, u8)>> = laps
+            .iter()
+            .zip(&self.cars)
+            .map(|(lap, car)| {
+                let (lap, previous) = (lap.as_ref()?, car.lap.as_ref()?);
+                (on_track(lap) && on_track(previous)).then_some((previous.position, lap.position))
+            })
+            .collect();
+
+        for (car, position) in positions.iter().enumerate() {
+            let Some((was, now)) = *position else {
+                continue;
+            };
+
+            for (other, other_position) in positions.iter().enumerate() {
+                let Some((other_was, other_now)) = *other_position else {
+                    continue;
+                };
+
+                if was > other_was && now < other_now {
+                    events.push(DerivedEvent::Overtake {
+                        car: car as u8,
+                        overtaken_car: other as u8,
+                        position: now,
+                    });
+                }
+            }
+        }
+    }
+
+    fn detect_lap(&mut self, idx: u8, lap: &Lap, events: &mut Vec<DerivedEvent>) {
+        let Some(previous) = self.cars[idx as usize].lap.clone() else {
+            return;
+        };
+
+        match (previous.pit_status, lap.pit_status) {
+            (PitStatus::None, PitStatus::Pitting | PitStatus::InPitArea) => {
+                events.push(DerivedEvent::PitEntry { car: idx });
+            }
+            (PitStatus::Pitting | PitStatus::InPitArea, PitStatus::None) => {
+                events.push(DerivedEvent::PitExit {
+                    car: idx,
+                    pit_lane_time: previous.pit_lane_time,
+                });
+            }
+            _ => {}
+        }
+
+        let valid = !previous.lap_invalid;
+        if lap.lap_number > previous.lap_number && previous.lap_number > 0 {
+            events.push(DerivedEvent::LapCompleted {
+                car: idx,
+                lap: previous.lap_number,
+                time: lap.last_lap_time,
+                valid,
+            });
+
+            // The last sector ends with the lap
+            let sector3 = lap
+                .last_lap_time
+                .checked_sub(previous.sector1_time + previous.sector2_time)
+                .filter(|_| !previous.sector1_time.is_zero() && !previous.sector2_time.is_zero());
+            if let Some(time) = sector3.filter(|_| valid) {
+                self.detect_sector_best(idx, 2, time, events);
+            }
+        } else if lap.sector > previous.sector && valid && !lap.lap_invalid {
+            let time = match lap.sector {
+                1 => lap.sector1_time,
+                _ => lap.sector2_time,
+            };
+            if !time.is_zero() {
+                self.detect_sector_best(idx, lap.sector - 1, time, events);
+            }
+        }
+    }
+
+    /// A time faster than the previous best, or the first
+    /// valid time set in the sector, is reported
+    fn detect_sector_best(
+        &mut self,
+        idx: u8,
+        sector: u8,
+        time: Duration,
+        events: &mut Vec<DerivedEvent>,
+    ) {
+        let sector_idx = sector as usize;
+        let personal = &mut self.cars[idx as usize].best_sectors[sector_idx];
+        let personal_best = personal.is_none_or(|best| time < best);
+        if personal_best {
+            *personal = Some(time);
+        }
+
+        let overall = &mut self.best_sectors[sector_idx];
+        let overall_best = overall.is_none_or(|best| time < best);
+        if overall_best {
+            *overall = Some(time);
+        }
+
+        if personal_best || overall_best {
+            events.push(DerivedEvent::SectorBest {
+                car: idx,
+                sector,
+                time,
+                overall: overall_best,
+            });
+        }
+    }
+
+    fn detect_status(&self, idx: u8, status: &CarStatus, events: &mut Vec<DerivedEvent>) {
+        let Some(previous) = &self.cars[idx as usize].status else {
+            return;
+        };
+
+        // A new set of the same compound starts with fewer laps on it
+        let tyres = &status.tyres;
+        let previous_tyres = &previous.tyres;
+        if tyres.compound != previous_tyres.compound
+            || tyres.visual_compound != previous_tyres.visual_compound
+            || tyres.age_laps < previous_tyres.age_laps
+        {
+            events.push(DerivedEvent::TyreChange {
+                car: idx,
+                compound: tyres.compound,
+                visual_compound: tyres.visual_compound,
+                previous: previous_tyres.visual_compound,
+            });
+        }
+
+        if status.fia_flag == FiaFlag::Blue && previous.fia_flag != FiaFlag::Blue {
+            events.push(DerivedEvent::BlueFlag { car: idx });
+        }
+    }
+
+    fn detect_yellow_flags(&mut self, session: &PacketSessionData, events: &mut Vec<DerivedEvent>) {
+        let track_length = session.track_length as f32;
+
+        // Sector starts are only sent by F1 24+,
+        // otherwise the lap is split in thirds
+        let sector_starts = match (
+            session.sector2_lap_distance_start,
+            session.sector3_lap_distance_start,
+        ) {
+            (sector2, sector3) if sector2 > 0.0 && sector3 > 0.0 && track_length > 0.0 => {
+                [sector2 / track_length, sector3 / track_length]
+            }
+            _ => [1.0 / 3.0, 2.0 / 3.0],
+        };
+
+        let mut yellow_sectors = [false; NUM_SECTORS];
+        for zone in session
+            .marshal_zones
+            .iter()
+            .take(session.num_marshal_zones as usize)
+        {
+            if FiaFlag::from(zone.zone_flag) == FiaFlag::Yellow {
+                let sector = sector_starts
+                    .iter()
+                    .filter(|&&start| zone.zone_start >= start)
+                    .count();
+                yellow_sectors[sector] = true;
+            }
+        }
+
+        for (sector, (&yellow, &was_yellow)) in
+            yellow_sectors.iter().zip(&self.yellow_sectors).enumerate()
+        {
+            let sector = sector as u8;
+            match (was_yellow, yellow) {
+                (false, true) => events.push(DerivedEvent::YellowFlag { sector }),
+                (true, false) => events.push(DerivedEvent::YellowFlagCleared { sector }),
+                _ => {}
+            }
+        }
+
+        self.yellow_sectors = yellow_sectors;
+    }
+
+    fn detect_safety_car(&mut self, session: &PacketSessionData, events: &mut Vec<DerivedEvent>) {
+        let safety_car = session.safety_car();
+        if safety_car == self.safety_car {
+            return;
+        }
+
+        // The formation lap isn't a neutralisation of the race
+        let neutralises = |status: SafetyCarStatus| {
+            matches!(status, SafetyCarStatus::Full | SafetyCarStatus::Virtual)
+        };
+        if neutralises(self.safety_car) {
+            events.push(DerivedEvent::SafetyCarEnded {
+                kind: self.safety_car,
+            });
+        }
+        if neutralises(safety_car) {
+            events.push(DerivedEvent::SafetyCarDeployed { kind: safety_car });
+        }
+
+        self.safety_car = safety_car;
+    }
+}
+
+/// Cars that aren't in the session or have retired
+/// are left out of comparisons
+fn is_racing(lap: &Lap) -> bool {
+    matches!(
+        lap.result_status,
+        ResultStatus::Active | ResultStatus::Finished
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::parse_packet;
+
+    fn datagram(packet_id: u8, len: usize) -> Vec<u8> {
+        let mut buffer = vec![0u8; len];
+        buffer[0..2].copy_from_slice(&2023u16.to_le_bytes());
+        buffer[6] = packet_id;
+        buffer[7..15].copy_from_slice(&1u64.to_le_bytes());
+        buffer
+    }
+
+    /// A 2023 Session packet, 10 is a race and 5 the first qualifying session
+    fn session(session_type: u8) -> TelemetryPacket {
+        let mut buffer = datagram(1, 644);
+        buffer[35] = session_type;
+        parse_packet(&buffer).unwrap()
+    }
+
+    /// A 2023 Lap Data packet with the active cars in `positions`, by vehicle index
+    fn lap_data(positions: &[u8]) -> TelemetryPacket {
+        let mut buffer = datagram(2, 1131);
+        for (idx, &position) in positions.iter().enumerate() {
+            let entry = 29 + idx * 50;
+            buffer[entry + 30] = position;
+            buffer[entry + 31] = 2;
+            buffer[entry + 43] = 2;
+        }
+        parse_packet(&buffer).unwrap()
+    }
+
+    fn overtakes(session_type: u8) -> Vec<DerivedEvent> {
+        let mut detector = EventDetector::new();
+        detector.ingest(&session(session_type));
+        detector.ingest(&lap_data(&[1, 2]));
+        detector
+            .ingest(&lap_data(&[2, 1]))
+            .into_iter()
+            .filter(|event| matches!(event, DerivedEvent::Overtake { .. }))
+            .collect()
+    }
+
+    #[test]
+    fn detects_overtakes_in_races() {
+        assert_eq!(
+            overtakes(10),
+            vec![DerivedEvent::Overtake {
+                car: 1,
+                overtaken_car: 0,
+                position: 1,
+            }]
+        );
+    }
+
+    #[test]
+    fn ignores_order_changes_in_qualifying() {
+        assert!(overtakes(5).is_empty());
+    }
+}
@@ -0,0 +1,512 @@
+use serde::{Deserialize, Serialize};
+
+use crate::core::{
+    CarStatus, ErsDeployMode, Gap, Lap, ResultStatus, TelemetryPacket, TimingEntry, TimingTower,
+    MAX_CARS,
+};
+
+/// Energy the battery holds when full
+pub const ERS_STORE_CAPACITY_J: f32 = 4_000_000.0;
+
+/// Most energy that can be deployed on a lap
+pub const ERS_DEPLOY_LIMIT_PER_LAP_J: f32 = 4_000_000.0;
+
+/// Length of the stretches of a lap deployment is measured over
+const DISTANCE_BUCKET_M: f32 = 100.0;
+
+/// Longer than any track, lap distances beyond it are bad data
+const MAX_LAP_LENGTH_M: f32 = 10_000.0;
+
+/// Stretches deploying less than this aren't part of a deployment zone
+const MIN_ZONE_DEPLOYED_J: f32 = 10_000.0;
+
+/// Gap to a car at which DRS, and an overtake, is possible
+const OVERTAKE_GAP_S: f32 = 1.0;
+
+/// Gap to the car ahead at which it's worth chasing
+const ATTACK_GAP_S: f32 = 2.0;
+
+/// Battery needed to attack or defend with more than the balanced mode
+const MIN_ATTACK_STORE_PERCENT: f32 = 30.0;
+
+/// Below this the battery is recharged unless defending
+const LOW_STORE_PERCENT: f32 = 15.0;
+
+/// Energy deployed and harvested over part of a lap
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq)]
+pub struct ErsEnergy {
+    pub deployed_j: f32,
+    pub harvested_mguk_j: f32,
+    pub harvested_mguh_j: f32,
+}
+
+impl ErsEnergy {
+    fn from_status(status: &CarStatus) -> Self {
+        Self {
+            deployed_j: status.ers_deployed_this_lap_j,
+            harvested_mguk_j: status.ers_harvested_this_lap_mguk_j,
+            harvested_mguh_j: status.ers_harvested_this_lap_mguh_j,
+        }
+    }
+
+    pub fn harvested_j(&self) -> f32 {
+        self.harvested_mguk_j + self.harvested_mguh_j
+    }
+
+    /// Energy added to the battery, negative if more was deployed than harvested
+    pub fn balance_j(&self) -> f32 {
+        self.harvested_j() - self.deployed_j
+    }
+
+    /// The game's counters only go up during a lap,
+    /// so the highest seen is the lap's total
+    fn max(self, other: Self) -> Self {
+        Self {
+            deployed_j: self.deployed_j.max(other.deployed_j),
+            harvested_mguk_j: self.harvested_mguk_j.max(other.harvested_mguk_j),
+            harvested_mguh_j: self.harvested_mguh_j.max(other.harvested_mguh_j),
+        }
+    }
+
+    fn minus(self, other: Self) -> Self {
+        Self {
+            deployed_j: (self.deployed_j - other.deployed_j).max(0.0),
+            harvested_mguk_j: (self.harvested_mguk_j - other.harvested_mguk_j).max(0.0),
+            harvested_mguh_j: (self.harvested_mguh_j - other.harvested_mguh_j).max(0.0),
+        }
+    }
+}
+
+/// A stretch of the lap where the battery was spent
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct DeploymentZone {
+    pub start_m: f32,
+    pub end_m: f32,
+    pub deployed_j: f32,
+}
+
+/// Energy used on a completed lap
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ErsLap {
+    pub lap: u8,
+    pub energy: ErsEnergy,
+
+    /// Energy of each sector, None for sectors that weren't seen from the start
+    pub sectors: [Option<ErsEnergy>; 3],
+
+    /// Battery at the start and end of the lap, None if the lap wasn't seen from the start
+    pub start_store_j: Option<f32>,
+    pub end_store_j: f32,
+
+    /// Where the battery was spent
+    pub zones: Vec<DeploymentZone>,
+}
+
+/// Who is close enough to race
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErsSituation {
+    /// Within DRS range of the car ahead
+    Overtake,
+
+    /// Close enough to chase the car ahead
+    Attack,
+
+    /// The car behind is within DRS range
+    Defend,
+
+    /// Nobody close, a chance to recharge
+    Free,
+}
+
+/// Battery state and deployment advice of a car.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ErsAnalysis {
+    pub vehicle_idx: u8,
+    pub store_j: f32,
+    pub store_percent: f32,
+    pub mode: ErsDeployMode,
+
+    /// Energy used on the lap so far
+    pub this_lap: ErsEnergy,
+
+    /// Energy that can still be deployed this lap, limited by the battery
+    pub deploy_remaining_j: f32,
+
+    /// Gaps to the cars directly ahead and behind on the same lap, in races
+    pub gap_ahead_s: Option<f32>,
+    pub gap_behind_s: Option<f32>,
+
+    /// Always `Free` outside of races
+    pub situation: ErsSituation,
+    pub recommended_mode: ErsDeployMode,
+    pub laps: Vec<ErsLap>,
+}
+
+#[derive(Debug, Clone, Default)]
+struct CarErs {
+    status: Option<CarStatus>,
+    lap: Option<Lap>,
+
+    /// Highest counters seen this lap
+    energy: ErsEnergy,
+    lap_start_store_j: Option<f32>,
+    sector_start: Option<ErsEnergy>,
+    sectors: [Option<ErsEnergy>; 3],
+
+    /// Energy deployed in every stretch of the current lap
+    deployment: Vec<f32>,
+    laps: Vec<ErsLap>,
+}
+
+impl CarErs {
+    fn update_status(&mut self, status: CarStatus) {
+        let energy = ErsEnergy::from_status(&status);
+
+        // Deployment since the last packet, at the car's latest position
+        let deployed_j = energy.deployed_j - self.energy.deployed_j;
+        let lap_distance_m = self.lap.as_ref().map_or(-1.0, |lap| lap.lap_distance_m);
+        if deployed_j > 0.0 && (0.0..=MAX_LAP_LENGTH_M).contains(&lap_distance_m) {
+            let bucket = (lap_distance_m / DISTANCE_BUCKET_M) as usize;
+            if self.deployment.len() <= bucket {
+                self.deployment.resize(bucket + 1, 0.0);
+            }
+            self.deployment[bucket] += deployed_j;
+        }
+
+        self.energy = self.energy.max(energy);
+        self.status = Some(status);
+    }
+
+    fn update_lap(&mut self, lap: Lap) {
+        let (lap_number, sector) = (lap.lap_number, lap.sector);
+        let Some(previous) = self.lap.replace(lap) else {
+            return;
+        };
+        let store_j = self.status.as_ref().map(|status| status.ers_store_energy_j);
+
+        if lap_number != previous.lap_number {
+            let sector = previous.sector as usize;
+            if let (Some(start), Some(slot)) = (self.sector_start, self.sectors.get_mut(sector)) {
+                *slot = Some(self.energy.minus(start));
+            }
+
+            if lap_number == previous.lap_number + 1 && previous.lap_number > 0 {
+                if let Some(end_store_j) = store_j {
+                    self.laps.push(ErsLap {
+                        lap: previous.lap_number,
+                        energy: self.energy,
+                        sectors: self.sectors,
+                        start_store_j: self.lap_start_store_j,
+                        end_store_j,
+                        zones: deployment_zones(&self.deployment),
+                    });
+                }
+            } else {
+                // Flashback, or laps were missed
+                self.laps.retain(|completed| completed.lap < lap_number);
+            }
+
+            self.energy = ErsEnergy::default();
+            self.lap_start_store_j = store_j;
+            self.sector_start = Some(ErsEnergy::default());
+            self.sectors = [None; 3];
+            self.deployment.clear();
+        } else if sector != previous.sector {
+            let sector = previous.sector as usize;
+            if let (Some(start), Some(slot)) = (self.sector_start, self.sectors.get_mut(sector)) {
+                *slot = Some(self.energy.minus(start));
+            }
+            self.sector_start = Some(self.energy);
+        }
+    }
+}
+
+/// Gaps from the car at `vehicle_idx` to the cars directly ahead and behind
+fn gaps(tower: &TimingTower, vehicle_idx: u8) -> (Option<f32>, Option<f32>) {
+    // Outside of races the order is by best lap, not on track
+    if !tower.session_type.is_race() {
+        return (None, None);
+    }
+
+    let racing = |entry: &&TimingEntry| entry.result_status == ResultStatus::Active;
+    let Some(entry) = tower.entry(vehicle_idx).filter(racing) else {
+        return (None, None);
+    };
+
+    let gap_behind_s = tower
+        .entries
+        .iter()
+        .filter(racing)
+        .find(|behind| behind.position == entry.position + 1)
+        .and_then(interval_s);
+
+    (interval_s(entry), gap_behind_s)
+}
+
+/// Gap to the car ahead in seconds, None if laps down or unknown
+fn interval_s(entry: &TimingEntry) -> Option<f32> {
+    match entry.interval? {
+        Gap::Time(time) => Some(time.as_secs_f32()),
+        Gap::Laps(_) => None,
+    }
+}
+
+/// Merge neighbouring stretches where the battery was spent
+fn deployment_zones(deployment: &[f32]) -> Vec<DeploymentZone> {
+    let mut zones: Vec<DeploymentZone> = Vec::new();
+
+    for (bucket, &deployed_j) in deployment.iter().enumerate() {
+        if deployed_j < MIN_ZONE_DEPLOYED_J {
+            continue;
+        }
+
+        let start_m = bucket as f32 * DISTANCE_BUCKET_M;
+        match zones.last_mut() {
+            Some(zone) if zone.end_m == start_m => {
+                zone.end_m += DISTANCE_BUCKET_M;
+                zone.deployed_j += deployed_j;
+            }
+            _ => zones.push(DeploymentZone {
+                start_m,
+                end_m: start_m + DISTANCE_BUCKET_M,
+                deployed_j,
+            }),
+        }
+    }
+
+    zones
+}
+
+/// Tracks the energy every car deploys and harvests on each lap and sector
+/// from the Car Status and Lap Data packets, where along the lap it's spent,
+/// and which deploy mode suits the cars around it.
+#[derive(Debug, Clone)]
+pub struct ErsModel {
+    cars: Vec<CarErs>,
+}
+
+impl Default for ErsModel {
+    fn default() -> Self {
+        Self {
+            cars: vec![CarErs::default(); MAX_CARS],
+        }
+    }
+}
+
+impl ErsModel {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Forget every car's laps, e.g. when a new session starts
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    /// Apply a Lap Data or Car Status packet, other packets are ignored
+    pub fn ingest(&mut self, packet: &TelemetryPacket) {
+        match packet {
+            TelemetryPacket::LapData(lap_data) => {
+                for (idx, car) in self.cars.iter_mut().enumerate() {
+                    if let Some(lap) = lap_data.lap(idx as u8) {
+                        car.update_lap(lap);
+                    }
+                }
+            }
+            TelemetryPacket::CarStatus(car_status) => {
+                for (idx, car) in self.cars.iter_mut().enumerate() {
+                    if let Some(status) = car_status.status(idx as u8) {
+                        car.update_status(status);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Energy used on every lap the car at `vehicle_idx` completed
+    pub fn laps(&self, vehicle_idx: u8) -> &[ErsLap] {
+        self.cars
+            .get(vehicle_idx as usize)
+            .map_or(&[], |car| car.laps.as_slice())
+    }
+
+    /// Battery state of the car at `vehicle_idx`, and the deploy mode
+    /// for its situation against the cars around it in `tower`
+    pub fn analysis(&self, vehicle_idx: u8, tower: &TimingTower) -> Option<ErsAnalysis> {
+        let car = self.cars.get(vehicle_idx as usize)?;
+        let status = car.status.as_ref()?;
+        // Sent by the game, so may be negative or NaN
+        let store_j = status.ers_store_energy_j.max(0.0);
+        let store_percent = store_j / ERS_STORE_CAPACITY_J * 100.0;
+
+        let (gap_ahead_s, gap_behind_s) = gaps(tower, vehicle_idx);
+        let within = |gap: Option<f32>, limit_s: f32| gap.is_some_and(|gap| gap <= limit_s);
+        let situation = if within(gap_ahead_s, OVERTAKE_GAP_S) {
+            ErsSituation::Overtake
+        } else if within(gap_behind_s, OVERTAKE_GAP_S) {
+            ErsSituation::Defend
+        } else if within(gap_ahead_s, ATTACK_GAP_S) {
+            ErsSituation::Attack
+        } else {
+            ErsSituation::Free
+        };
+
+        let can_push = store_percent >= MIN_ATTACK_STORE_PERCENT;
+        let recommended_mode = match situation {
+            ErsSituation::Defend if can_push => ErsDeployMode::Overtake,
+            ErsSituation::Defend => ErsDeployMode::Medium,
+            _ if store_percent < LOW_STORE_PERCENT => ErsDeployMode::None,
+            ErsSituation::Overtake if can_push => ErsDeployMode::Overtake,
+            ErsSituation::Attack if can_push => ErsDeployMode::Hotlap,
+            _ => ErsDeployMode::Medium,
+        };
+
+        Some(ErsAnalysis {
+            vehicle_idx,
+            store_j,
+            store_percent,
+            mode: status.ers_deploy_mode,
+            this_lap: car.energy,
+            deploy_remaining_j: (ERS_DEPLOY_LIMIT_PER_LAP_J - car.energy.deployed_j)
+                .min(store_j)
+                .max(0.0),
+            gap_ahead_s,
+            gap_behind_s,
+            situation,
+            recommended_mode,
+            laps: car.laps.clone(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::core::{ids::SessionType, parse_packet, PitStatus};
+
+    fn datagram(packet_id: u8, len: usize) -> Vec<u8> {
+        let mut buffer = vec![0u8; len];
+        buffer[0..2].copy_from_slice(&2023u16.to_le_bytes());
+        buffer[6] = packet_id;
+        buffer
+    }
+
+    /// A 2023 Lap Data packet with the first car on lap 2 at `lap_distance_m`
+    fn lap_data(lap_distance_m: f32) -> TelemetryPacket {
+        let mut buffer = datagram(2, 1131);
+        buffer[29 + 18..29 + 22].copy_from_slice(&lap_distance_m.to_le_bytes());
+        buffer[29 + 30] = 1;
+        buffer[29 + 31] = 2;
+        buffer[29 + 43] = 2;
+        parse_packet(&buffer).unwrap()
+    }
+
+    /// A 2023 Car Status packet with the first car having
+    /// `store_j` in the battery and deployed `deployed_j` this lap
+    fn car_status(store_j: f32, deployed_j: f32) -> TelemetryPacket {
+        let mut buffer = datagram(7, 1239);
+        buffer[29 + 37..29 + 41].copy_from_slice(&store_j.to_le_bytes());
+        buffer[29 + 50..29 + 54].copy_from_slice(&deployed_j.to_le_bytes());
+        parse_packet(&buffer).unwrap()
+    }
+
+    #[test]
+    fn deployment_is_measured_where_the_car_is() {
+        let mut ers = ErsModel::new();
+        ers.ingest(&lap_data(250.0));
+        ers.ingest(&car_status(0.0, 50_000.0));
+
+        assert_eq!(ers.cars[0].deployment, [0.0, 0.0, 50_000.0]);
+    }
+
+    #[test]
+    fn deployment_at_bad_lap_distances_is_skipped() {
+        let mut ers = ErsModel::new();
+        for (lap_distance_m, deployed_j) in [
+            (f32::MAX, 10_000.0),
+            (f32::INFINITY, 20_000.0),
+            (f32::NAN, 30_000.0),
+            (-100.0, 40_000.0),
+        ] {
+            ers.ingest(&lap_data(lap_distance_m));
+            ers.ingest(&car_status(0.0, deployed_j));
+        }
+
+        assert!(ers.cars[0].deployment.is_empty());
+        assert_eq!(ers.cars[0].energy.deployed_j, 40_000.0);
+    }
+
+    fn entry(vehicle_idx: u8, position: u8, interval: Option<Gap>) -> TimingEntry {
+        TimingEntry {
+            vehicle_idx,
+            position,
+            lap: 5,
+            sector: 0,
+            gap_to_leader: None,
+            interval,
+            last_lap: None,
+            best_lap: None,
+            last_lap_delta_s: None,
+            live_delta_s: None,
+            best_sectors: [None; 3],
+            pit_status: PitStatus::None,
+            num_pit_stops: 0,
+            result_status: ResultStatus::Active,
+        }
+    }
+
+    /// The first car in second place, 0.8 s behind the leader,
+    /// with the car in third `behind` it
+    fn tower(session_type: SessionType, behind: Gap) -> TimingTower {
+        TimingTower {
+            session_type,
+            entries: vec![
+                entry(1, 1, None),
+                entry(0, 2, Some(Gap::Time(Duration::from_millis(800)))),
+                entry(2, 3, Some(behind)),
+            ],
+            ..Default::default()
+        }
+    }
+
+    fn analysis(tower: &TimingTower) -> ErsAnalysis {
+        let mut ers = ErsModel::new();
+        ers.ingest(&car_status(ERS_STORE_CAPACITY_J, 0.0));
+        ers.analysis(0, tower).unwrap()
+    }
+
+    #[test]
+    fn gaps_in_races_are_the_tower_intervals() {
+        let analysis = analysis(&tower(
+            SessionType::R,
+            Gap::Time(Duration::from_millis(1500)),
+        ));
+
+        assert_eq!(analysis.gap_ahead_s, Some(0.8));
+        assert_eq!(analysis.gap_behind_s, Some(1.5));
+        assert_eq!(analysis.situation, ErsSituation::Overtake);
+        assert_eq!(analysis.recommended_mode, ErsDeployMode::Overtake);
+    }
+
+    #[test]
+    fn lapped_cars_are_not_raced() {
+        let analysis = analysis(&tower(SessionType::R, Gap::Laps(1)));
+
+        assert_eq!(analysis.gap_behind_s, None);
+    }
+
+    #[test]
+    fn gaps_are_ignored_outside_of_races() {
+        let analysis = analysis(&tower(
+            SessionType::Q1,
+            Gap::Time(Duration::from_millis(500)),
+        ));
+
+        assert_eq!(analysis.gap_ahead_s, None);
+        assert_eq!(analysis.gap_behind_s, None);
+        assert_eq!(analysis.situation, ErsSituation::Free);
+    }
+}
@@ -0,0 +1,417 @@
+use serde::{Deserialize, Serialize};
+
+use crate::core::{CarStatus, FuelMix, PitStatus, SafetyCarStatus, TelemetryPacket, MAX_CARS};
+
+/// Fuel to have left at the end of a race, in laps of fuel
+pub const DEFAULT_FUEL_MARGIN_LAPS: f32 = 0.2;
+
+/// Number of recent laps the burn of a mix is averaged over
+const RECENT_LAPS: usize = 3;
+
+/// Fuel mixes from leanest to richest
+const MIXES: [FuelMix; 4] = [
+    FuelMix::Lean,
+    FuelMix::Standard,
+    FuelMix::Rich,
+    FuelMix::Max,
+];
+
+/// Fuel used on a lap
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct FuelLap {
+    pub lap: u8,
+    pub burn_kg: f32,
+
+    /// Mix used for the whole lap, None if it was changed during the lap
+    pub mix: Option<FuelMix>,
+}
+
+/// What to do to finish the race with the target fuel margin
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum FuelAdvice {
+    /// The margin is on target
+    Hold,
+
+    /// The mix's measured burn finishes the race with the target margin,
+    /// richer if there is fuel to spare, leaner if there isn't enough
+    SwitchMix { mix: FuelMix },
+
+    /// No measured mix saves enough, lift and coast to save this much every lap
+    LiftAndCoast { save_per_lap_kg: f32 },
+}
+
+/// Fuel state and projection of a car.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct FuelAnalysis {
+    pub vehicle_idx: u8,
+    pub fuel_in_tank_kg: f32,
+    pub mix: FuelMix,
+
+    /// Average burn per lap of the current mix, or of
+    /// any mix if no lap was driven on it
+    pub burn_per_lap_kg: Option<f32>,
+
+    /// Average burn per lap of every mix a lap was driven on
+    pub burn_per_mix_kg: Vec<(FuelMix, f32)>,
+
+    /// Laps left in the race, including the rest of the current lap
+    pub laps_remaining: f32,
+
+    /// Fuel left at the end of the race at the current burn,
+    /// negative if the car would run out
+    pub margin_kg: Option<f32>,
+    pub margin_laps: Option<f32>,
+    pub target_margin_laps: f32,
+
+    /// None until a lap's burn was measured
+    pub advice: Option<FuelAdvice>,
+
+    /// The advice as the engineer would say it on the radio
+    pub message: Option<String>,
+    pub laps: Vec<FuelLap>,
+}
+
+impl FuelAnalysis {
+    fn radio_message(&self) -> Option<String> {
+        let advice = self.advice?;
+        let margin_laps = self.margin_laps?;
+        let margin = format!("{margin_laps:+.1} laps at the flag");
+        let rank = |mix: FuelMix| MIXES.iter().position(|&m| m == mix);
+
+        let message = match advice {
+            FuelAdvice::Hold if margin_laps >= self.target_margin_laps => {
+                format!("Fuel is on target, {margin}.")
+            }
+            FuelAdvice::Hold => format!("Fuel is {margin}, nothing more to save."),
+            FuelAdvice::SwitchMix { mix } if rank(mix) > rank(self.mix) => {
+                format!("Fuel is good, {margin}. Switch to {mix:?} mix.")
+            }
+            FuelAdvice::SwitchMix { mix } => {
+                format!("Fuel is tight, {margin}. Switch to {mix:?} mix.")
+            }
+            FuelAdvice::LiftAndCoast { save_per_lap_kg } => format!(
+                "We're short on fuel, {margin}. Lift and coast, save {save_per_lap_kg:.2} kg a lap."
+            ),
+        };
+
+        Some(message)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct CarFuel {
+    status: Option<CarStatus>,
+    lap_number: u8,
+    lap_distance_m: f32,
+
+    /// Fuel when the current lap started, None if the lap wasn't seen from the start
+    lap_start_fuel_kg: Option<f32>,
+
+    /// Mix the current lap started on
+    lap_mix: Option<FuelMix>,
+    mix_changed: bool,
+
+    /// Pitting, or a safety car, make a lap's burn unrepresentative
+    pitted: bool,
+    neutralised: bool,
+    laps: Vec<FuelLap>,
+}
+
+impl CarFuel {
+    fn update_status(&mut self, status: CarStatus) {
+        if self.lap_mix.is_some_and(|mix| mix != status.fuel_mix) {
+            self.mix_changed = true;
+        }
+
+        self.status = Some(status);
+    }
+
+    fn update_lap(&mut self, lap_number: u8, lap_distance_m: f32, pit_status: PitStatus) {
+        self.lap_distance_m = lap_distance_m;
+        self.pitted |= pit_status != PitStatus::None;
+        if lap_number == self.lap_number {
+            return;
+        }
+
+        let completed = lap_number == self.lap_number + 1;
+        let start_fuel = self.lap_start_fuel_kg.take();
+        let mix = (!self.mix_changed).then_some(self.lap_mix).flatten();
+        let representative = completed && !self.pitted && !self.neutralised;
+
+        let fuel = self.status.as_ref().map(|status| status.fuel_in_tank_kg);
+        self.lap_number = lap_number;
+        self.lap_start_fuel_kg = fuel;
+        self.lap_mix = self.status.as_ref().map(|status| status.fuel_mix);
+        self.mix_changed = false;
+        self.pitted = false;
+        self.neutralised = false;
+
+        if let (Some(start_fuel), Some(fuel)) = (start_fuel, fuel) {
+            if representative && start_fuel > fuel {
+                self.laps.push(FuelLap {
+                    lap: lap_number - 1,
+                    burn_kg: start_fuel - fuel,
+                    mix,
+                });
+            }
+        }
+    }
+
+    /// Average of the last few laps of `mix`, or of any mix if None
+    fn burn_per_lap(&self, mix: Option<FuelMix>) -> Option<f32> {
+        let recent: Vec<f32> = self
+            .laps
+            .iter()
+            .rev()
+            .filter(|lap| mix.is_none() || lap.mix == mix)
+            .take(RECENT_LAPS)
+            .map(|lap| lap.burn_kg)
+            .collect();
+
+        (!recent.is_empty()).then(|| recent.iter().sum::<f32>() / recent.len() as f32)
+    }
+}
+
+/// Measures how much fuel every car burns per lap on each fuel mix from the
+/// Car Status and Lap Data packets, and projects whether it will make the
+/// end of the race.
+#[derive(Debug, Clone)]
+pub struct FuelModel {
+    total_laps: u8,
+    track_length_m: f32,
+    target_margin_laps: f32,
+    cars: Vec<CarFuel>,
+}
+
+impl Default for FuelModel {
+    fn default() -> Self {
+        Self {
+            total_laps: 0,
+            track_length_m: 0.0,
+            target_margin_laps: DEFAULT_FUEL_MARGIN_LAPS,
+            cars: vec![CarFuel::default(); MAX_CARS],
+        }
+    }
+}
+
+impl FuelModel {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Forget every car's laps, e.g. when a new session starts.
+    /// The target margin is kept.
+    pub fn reset(&mut self) {
+        *self = Self {
+            target_margin_laps: self.target_margin_laps,
+            ..Self::default()
+        };
+    }
+
+    pub fn target_margin_laps(&self) -> f32 {
+        self.target_margin_laps
+    }
+
+    /// Fuel to have left at the end of the race, in laps of fuel
+    pub fn set_target_margin_laps(&mut self, margin_laps: f32) {
+        self.target_margin_laps = margin_laps;
+    }
+
+    /// Apply a Session, Lap Data or Car Status packet, other packets are ignored
+    pub fn ingest(&mut self, packet: &TelemetryPacket) {
+        match packet {
+            TelemetryPacket::Session(session) => {
+                self.total_laps = session.total_laps;
+                self.track_length_m = session.track_length as f32;
+
+                if matches!(
+                    SafetyCarStatus::from(session.safety_car_status),
+                    SafetyCarStatus::Full
+                        | SafetyCarStatus::Virtual
+                        | SafetyCarStatus::FormationLap
+                ) {
+                    self.cars.iter_mut().for_each(|car| car.neutralised = true);
+                }
+            }
+            TelemetryPacket::LapData(lap_data) => {
+                for (idx, car) in self.cars.iter_mut().enumerate() {
+                    if let Some(lap) = lap_data.lap(idx as u8) {
+                        car.update_lap(lap.lap_number, lap.lap_distance_m, lap.pit_status);
+                    }
+                }
+            }
+            TelemetryPacket::CarStatus(car_status) => {
+                for (idx, car) in self.cars.iter_mut().enumerate() {
+                    if let Some(status) = car_status.status(idx as u8) {
+                        car.update_status(status);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Laps left in the race for the car at `vehicle_idx`,
+    /// including the rest of the lap it's on
+    pub fn laps_remaining(&self, vehicle_idx: u8) -> Option<f32> {
+        let car = self.cars.get(vehicle_idx as usize)?;
+        if self.total_laps == 0 || car.lap_number == 0 {
+            return None;
+        }
+
+        let lap_progress = if self.track_length_m > 0.0 {
+            (car.lap_distance_m / self.track_length_m).clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+
+        Some((self.total_laps as f32 - car.lap_number as f32 + 1.0 - lap_progress).max(0.0))
+    }
+
+    /// Fuel state and projection of the car at `vehicle_idx`
+    pub fn analysis(&self, vehicle_idx: u8) -> Option<FuelAnalysis> {
+        let car = self.cars.get(vehicle_idx as usize)?;
+        let status = car.status.as_ref()?;
+        let fuel_kg = status.fuel_in_tank_kg;
+        let laps_remaining = self.laps_remaining(vehicle_idx).unwrap_or_default();
+
+        let burn_per_lap_kg = car
+            .burn_per_lap(Some(status.fuel_mix))
+            .or_else(|| car.burn_per_lap(None));
+        let burn_per_mix_kg: Vec<(FuelMix, f32)> = MIXES
+            .into_iter()
+            .filter_map(|mix| Some((mix, car.burn_per_lap(Some(mix))?)))
+            .collect();
+
+        // Fuel left at the end of the race, in laps, if `burn_kg` is used every lap
+        let margin_laps = |burn_kg: f32| (fuel_kg - laps_remaining * burn_kg) / burn_kg;
+        let margin_kg = burn_per_lap_kg.map(|burn_kg| fuel_kg - laps_remaining * burn_kg);
+
+        let advice = burn_per_lap_kg
+            .filter(|burn_kg| *burn_kg > 0.0)
+            .map(|burn_kg| {
+                let target = self.target_margin_laps;
+                let fits = |mix: FuelMix| {
+                    burn_per_mix_kg
+                        .iter()
+                        .find(|(measured, _)| *measured == mix)
+                        .is_some_and(|&(_, burn_kg)| margin_laps(burn_kg) >= target)
+                };
+                let position = MIXES.iter().position(|&mix| mix == status.fuel_mix);
+
+                if margin_laps(burn_kg) >= target {
+                    // Use spare fuel for pace, one mix at a time
+                    match position.and_then(|position| MIXES.get(position + 1)) {
+                        Some(&richer) if fits(richer) => FuelAdvice::SwitchMix { mix: richer },
+                        _ => FuelAdvice::Hold,
+                    }
+                } else {
+                    // The richest leaner mix that makes the end
+                    let leaner = position.and_then(|position| {
+                        MIXES[..position].iter().rev().find(|&&mix| fits(mix))
+                    });
+                    match leaner {
+                        Some(&mix) => FuelAdvice::SwitchMix { mix },
+                        None if laps_remaining > 0.0 => FuelAdvice::LiftAndCoast {
+                            save_per_lap_kg: (laps_remaining * burn_kg + target * burn_kg
+                                - fuel_kg)
+                                / laps_remaining,
+                        },
+                        None => FuelAdvice::Hold,
+                    }
+                }
+            });
+
+        let mut analysis = FuelAnalysis {
+            vehicle_idx,
+            fuel_in_tank_kg: fuel_kg,
+            mix: status.fuel_mix,
+            burn_per_lap_kg,
+            burn_per_mix_kg,
+            laps_remaining,
+            margin_kg,
+            margin_laps: burn_per_lap_kg
+                .filter(|burn_kg| *burn_kg > 0.0)
+                .map(margin_laps),
+            target_margin_laps: self.target_margin_laps,
+            advice,
+            message: None,
+            laps: car.laps.clone(),
+        };
+        analysis.message = analysis.radio_message();
+
+        Some(analysis)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::parse_packet;
+
+    /// A 2023 Car Status packet with the first car on `mix` with `fuel_kg` in the tank
+    fn car_status(mix: FuelMix, fuel_kg: f32) -> TelemetryPacket {
+        let mut buffer = vec![0u8; 1239];
+        buffer[0..2].copy_from_slice(&2023u16.to_le_bytes());
+        buffer[6] = 7;
+        buffer[29 + 2] = mix as u8;
+        buffer[29 + 5..29 + 9].copy_from_slice(&fuel_kg.to_le_bytes());
+        parse_packet(&buffer).unwrap()
+    }
+
+    fn fuel_lap(lap: u8, mix: FuelMix, burn_kg: f32) -> FuelLap {
+        FuelLap {
+            lap,
+            burn_kg,
+            mix: Some(mix),
+        }
+    }
+
+    /// Advice for the first car on lap 6 of 10, with 5 laps remaining.
+    /// Standard burns 2 kg a lap, Rich 2.5 kg and Lean 1.5 kg.
+    fn advice(mix: FuelMix, fuel_kg: f32) -> Option<FuelAdvice> {
+        let mut model = FuelModel::new();
+        model.total_laps = 10;
+        model.cars[0].lap_number = 6;
+        model.cars[0].laps = vec![
+            fuel_lap(1, FuelMix::Standard, 2.0),
+            fuel_lap(2, FuelMix::Standard, 2.0),
+            fuel_lap(3, FuelMix::Standard, 2.0),
+            fuel_lap(4, FuelMix::Rich, 2.5),
+            fuel_lap(5, FuelMix::Lean, 1.5),
+        ];
+        model.ingest(&car_status(mix, fuel_kg));
+
+        model.analysis(0)?.advice
+    }
+
+    #[test]
+    fn spare_fuel_switches_to_a_richer_mix() {
+        // 2 laps spare on Standard, 0.6 on Rich
+        assert_eq!(
+            advice(FuelMix::Standard, 14.0),
+            Some(FuelAdvice::SwitchMix { mix: FuelMix::Rich })
+        );
+    }
+
+    #[test]
+    fn short_on_fuel_switches_to_the_richest_leaner_mix_that_fits() {
+        // Short on Rich, 0.5 laps spare on Standard and more on Lean
+        assert_eq!(
+            advice(FuelMix::Rich, 11.0),
+            Some(FuelAdvice::SwitchMix {
+                mix: FuelMix::Standard
+            })
+        );
+    }
+
+    #[test]
+    fn lifts_and_coasts_when_no_leaner_mix_fits() {
+        // 10 kg to finish on Standard plus 0.4 kg of margin, with 7 kg in the tank
+        let Some(FuelAdvice::LiftAndCoast { save_per_lap_kg }) = advice(FuelMix::Standard, 7.0)
+        else {
+            panic!("expected lift and coast");
+        };
+        assert!((save_per_lap_kg - 0.68).abs() < 1e-4);
+    }
+}
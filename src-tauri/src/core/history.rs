@@ -0,0 +1,140 @@
+use serde::{Deserialize, Serialize};
+
+use crate::core::{LapTime, RaceStateChange, TelemetryPacket, TyreStint, MAX_CARS};
+
+/// Every lap and tyre stint of a car, from its latest Session History packet
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct LapHistory {
+    pub vehicle_idx: u8,
+
+    /// Completed laps, oldest first
+    pub laps: Vec<LapTime>,
+
+    /// Tyre sets used, oldest first
+    pub stints: Vec<TyreStint>,
+    pub best_lap_num: Option<u8>,
+    pub best_sector_lap_nums: [Option<u8>; 3],
+
+    /// Session time of the packet the history is from
+    pub session_time: f32,
+}
+
+impl LapHistory {
+    /// Lap number `lap`, if completed
+    pub fn lap(&self, lap: u8) -> Option<&LapTime> {
+        self.laps.iter().find(|completed| completed.lap == lap)
+    }
+
+    /// Fastest lap, as decided by the game
+    pub fn best_lap(&self) -> Option<&LapTime> {
+        self.lap(self.best_lap_num?)
+    }
+
+    /// Tyres used on `lap`
+    pub fn stint_at(&self, lap: u8) -> Option<&TyreStint> {
+        self.stints.iter().find(|stint| stint.covers(lap))
+    }
+}
+
+/// Merges the Session History packets, which the game sends for one car
+/// at a time in rotation, into the lap history of every car.
+#[derive(Debug, Clone)]
+pub struct HistoryAssembler {
+    num_active_cars: u8,
+
+    /// Indexed by vehicle index, None until the car's history is received
+    cars: Vec<Option<LapHistory>>,
+    complete: bool,
+}
+
+impl Default for HistoryAssembler {
+    fn default() -> Self {
+        Self {
+            num_active_cars: 0,
+            cars: vec![None; MAX_CARS],
+            complete: false,
+        }
+    }
+}
+
+impl HistoryAssembler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Forget every car's history, e.g. when a new session starts
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    /// History of the car at `vehicle_idx`, if received
+    pub fn car(&self, vehicle_idx: u8) -> Option<&LapHistory> {
+        self.cars.get(vehicle_idx as usize)?.as_ref()
+    }
+
+    /// Every history received, in vehicle index order
+    pub fn cars(&self) -> impl Iterator<Item = &LapHistory> {
+        self.cars.iter().flatten()
+    }
+
+    /// Lap number `lap` of the car at `vehicle_idx`
+    pub fn lap(&self, vehicle_idx: u8, lap: u8) -> Option<&LapTime> {
+        self.car(vehicle_idx)?.lap(lap)
+    }
+
+    /// Whether the history of every active car has been received
+    pub fn is_complete(&self) -> bool {
+        self.complete
+    }
+
+    /// Active cars whose history hasn't been received yet
+    pub fn missing(&self) -> Vec<u8> {
+        (0..self.num_active_cars)
+            .filter(|&vehicle_idx| self.car(vehicle_idx).is_none())
+            .collect()
+    }
+
+    /// Apply a Participants or Session History packet.
+    ///
+    /// Returns what changed, other packets are ignored.
+    pub fn ingest(&mut self, packet: &TelemetryPacket) -> Vec<RaceStateChange> {
+        let mut changes = Vec::new();
+
+        match packet {
+            TelemetryPacket::Participants(participants) => {
+                self.num_active_cars = participants.num_active_cars;
+
+                // Cars that left the session no longer have a history
+                for car in self.cars.iter_mut().skip(self.num_active_cars as usize) {
+                    *car = None;
+                }
+            }
+            TelemetryPacket::SessionHistory(history) => {
+                let vehicle_idx = history.car_idx;
+                let Some(car) = self.cars.get_mut(vehicle_idx as usize) else {
+                    return changes;
+                };
+
+                let (best_lap_num, best_sector_lap_nums) = history.best_lap_nums();
+                *car = Some(LapHistory {
+                    vehicle_idx,
+                    laps: history.laps(),
+                    stints: history.stints(),
+                    best_lap_num,
+                    best_sector_lap_nums,
+                    session_time: history.header.session_time,
+                });
+                changes.push(RaceStateChange::History { vehicle_idx });
+            }
+            _ => return changes,
+        }
+
+        // Only sent once, the grid stays complete as histories are updated
+        if !self.complete && self.num_active_cars > 0 && self.missing().is_empty() {
+            self.complete = true;
+            changes.push(RaceStateChange::HistoryComplete);
+        }
+
+        changes
+    }
+}
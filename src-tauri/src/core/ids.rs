@@ -1,27 +1,70 @@
-/// F1 22 ID and Type enum Definitions
+/// F1 22 - F1 25 ID and Type enum Definitions
 /// Provided by CodeMaster
 use serde::{Deserialize, Serialize};
 
-/// Enum representing the packet type
-/// from `packet_id` field in the PacketHeader struct
-#[repr(u8)]
-#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
-pub enum PacketType {
-    Motion = 0,
-    Session = 1,
-    LapData = 2,
-    Event = 3,
-    Participants = 4,
-    CarSetups = 5,
-    CarTelemetry = 6,
-    CarStatus = 7,
-    FinalClassification = 8,
-    LobbyInfo = 9,
-    CarDamage = 10,
-    SessionHistory = 11,
+/// Define an id enum alongside a `TryFrom` conversion from its
+/// underlying representation.
+///
+/// Values received over UDP are only ever turned into one of these enums
+/// through `try_from`, so a byte with no matching variant is returned as an
+/// error rather than being materialized as an invalid enum.
+macro_rules! id_enum {
+    (
+        $(#[$meta:meta])*
+        pub enum $name:ident: $repr:ty {
+            $(
+                $(#[$variant_meta:meta])*
+                $variant:ident = $value:expr
+            ),* $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        #[repr($repr)]
+        pub enum $name {
+            $(
+                $(#[$variant_meta])*
+                $variant = $value,
+            )*
+        }
 
-    #[default]
-    None = 255,
+        impl TryFrom<$repr> for $name {
+            type Error = $repr;
+
+            fn try_from(value: $repr) -> Result<Self, Self::Error> {
+                match value {
+                    $(v if v == $value => Ok($name::$variant),)*
+                    _ => Err(value),
+                }
+            }
+        }
+    };
+}
+
+id_enum! {
+    /// Enum representing the packet type
+    /// from `packet_id` field in the PacketHeader struct
+    #[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+    pub enum PacketType: u8 {
+        Motion = 0,
+        Session = 1,
+        LapData = 2,
+        Event = 3,
+        Participants = 4,
+        CarSetups = 5,
+        CarTelemetry = 6,
+        CarStatus = 7,
+        FinalClassification = 8,
+        LobbyInfo = 9,
+        CarDamage = 10,
+        SessionHistory = 11,
+        TyreSets = 12,
+        MotionEx = 13,
+        TimeTrial = 14,
+        LapPositions = 15,
+
+        #[default]
+        None = 255,
+    }
 }
 
 impl PacketType {
@@ -41,6 +84,10 @@ impl PacketType {
             PacketType::LobbyInfo => 9,
             PacketType::CarDamage => 10,
             PacketType::SessionHistory => 11,
+            PacketType::TyreSets => 12,
+            PacketType::MotionEx => 13,
+            PacketType::TimeTrial => 14,
+            PacketType::LapPositions => 15,
             PacketType::None => 255,
         }
     }
@@ -48,393 +95,1162 @@ impl PacketType {
 
 /// Enum representing a team based on a u8
 /// from `team_id` field in a packet
-#[repr(u8)]
-#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default)]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
 pub enum TeamId {
-    Mercedes = 0,
-    Ferrari = 1,
-    RedBullRacing = 2,
-    Williams = 3,
-    AstonMartin = 4,
-    Alpine = 5,
-    AlphaTauri = 6,
-    Haas = 7,
-    McLaren = 8,
-    AlfaRomeo = 9,
-    Mercedes2020 = 85,
-    Ferrari2020 = 86,
-    RedBull2020 = 87,
-    Williams2020 = 88,
-    RacingPoint2020 = 89,
-    Renault2020 = 90,
-    AlphaTauri2020 = 91,
-    Haas2020 = 92,
-    McLaren2020 = 93,
-    AlfaRomeo2020 = 94,
-    AstonMartinDB11V12 = 95,
-    AstonMartinVantageF1Edition = 96,
-    AstonMartinVantageSafetyCar = 97,
-    FerrariF8Tributo = 98,
-    FerrariRoma = 99,
-    McLaren720S = 100,
-    McLarenArtura = 101,
-    MercedesAMGGTBlackSeriesSafetyCar = 102,
-    MercedesAMGGTRPro = 103,
-    F1CustomTeam = 104,
-    Prema21 = 106,
-    UniVirtuosi21 = 107,
-    Carlin21 = 108,
-    Hitech21 = 109,
-    ArtGP21 = 110,
-    MPMotorsport21 = 111,
-    Charouz21 = 112,
-    Dams21 = 113,
-    Campos21 = 114,
-    BWT21 = 115,
-    Trident21 = 116,
-    MercedesAMGGTBlackSeries = 117,
-    Prema22 = 118,
-    Virtuosi22 = 119,
-    Carlin22 = 120,
-    Hitech22 = 121,
-    ArtGP22 = 122,
-    MPMotorsport22 = 123,
-    Charouz22 = 124,
-    Dams22 = 125,
-    Campos22 = 126,
-    VanAmersfoortRacing22 = 127,
-    Trident22 = 128,
+    Mercedes,
+    Ferrari,
+    RedBullRacing,
+    Williams,
+    AstonMartin,
+    Alpine,
+    AlphaTauri,
+    Haas,
+    McLaren,
+    AlfaRomeo,
+    Mercedes2020,
+    Ferrari2020,
+    RedBull2020,
+    Williams2020,
+    RacingPoint2020,
+    Renault2020,
+    AlphaTauri2020,
+    Haas2020,
+    McLaren2020,
+    AlfaRomeo2020,
+    AstonMartinDB11V12,
+    AstonMartinVantageF1Edition,
+    AstonMartinVantageSafetyCar,
+    FerrariF8Tributo,
+    FerrariRoma,
+    McLaren720S,
+    McLarenArtura,
+    MercedesAMGGTBlackSeriesSafetyCar,
+    MercedesAMGGTRPro,
+    F1CustomTeam,
+    Prema21,
+    UniVirtuosi21,
+    Carlin21,
+    Hitech21,
+    ArtGP21,
+    MPMotorsport21,
+    Charouz21,
+    Dams21,
+    Campos21,
+    BWT21,
+    Trident21,
+    MercedesAMGGTBlackSeries,
+    Prema22,
+    Virtuosi22,
+    Carlin22,
+    Hitech22,
+    ArtGP22,
+    MPMotorsport22,
+    Charouz22,
+    Dams22,
+    Campos22,
+    VanAmersfoortRacing22,
+    Trident22,
+    Mercedes22,
+    Ferrari22,
+    RedBullRacing22,
+    Williams22,
+    AstonMartin22,
+    Alpine22,
+    AlphaTauri22,
+    Haas22,
+    McLaren22,
+    AlfaRomeo22,
+    Konnersport22,
+    Konnersport,
+    RB,
+    Sauber,
+    F1Generic,
+    ArtGP23,
+    Campos23,
+    Carlin23,
+    PHM23,
+    Dams23,
+    Hitech23,
+    MPMotorsport23,
+    Prema23,
+    Trident23,
+    VanAmersfoortRacing23,
+    Virtuosi23,
+    APXGP24,
+    APXGP25,
+    Konnersport24,
+    ArtGP24,
+    Campos24,
+    RodinMotorsport24,
+    AIXRacing24,
+    Dams24,
+    Hitech24,
+    MPMotorsport24,
+    Prema24,
+    Trident24,
+    VanAmersfoortRacing24,
+    Invicta24,
+    Mercedes24,
+    Ferrari24,
+    RedBullRacing24,
+    Williams24,
+    AstonMartin24,
+    Alpine24,
+    RB24,
+    Haas24,
+    McLaren24,
+    Sauber24,
 
     #[default]
-    None = 255,
+    None,
+}
+
+impl TeamId {
+    /// Look up a team from the raw `team_id` of a packet.
+    ///
+    /// Team ids are reassigned between games (e.g. 118 is Prema '22 in F1 22
+    /// but Mercedes '22 in F1 23), so the table used depends on the packet
+    /// format. The raw id is returned as the error if it is not in the table.
+    pub fn from_format(packet_format: u16, raw: u8) -> Result<Self, u8> {
+        let value = match packet_format {
+            ..=2022 => Self::from_f22(raw),
+            2023 => Self::from_f23(raw),
+            2024 => Self::from_f24(raw),
+            _ => Self::from_f25(raw),
+        };
+
+        value.ok_or(raw)
+    }
+
+    /// Team ids used by F1 22
+    fn from_f22(raw: u8) -> Option<Self> {
+        let value = match raw {
+            0 => TeamId::Mercedes,
+            1 => TeamId::Ferrari,
+            2 => TeamId::RedBullRacing,
+            3 => TeamId::Williams,
+            4 => TeamId::AstonMartin,
+            5 => TeamId::Alpine,
+            6 => TeamId::AlphaTauri,
+            7 => TeamId::Haas,
+            8 => TeamId::McLaren,
+            9 => TeamId::AlfaRomeo,
+            85 => TeamId::Mercedes2020,
+            86 => TeamId::Ferrari2020,
+            87 => TeamId::RedBull2020,
+            88 => TeamId::Williams2020,
+            89 => TeamId::RacingPoint2020,
+            90 => TeamId::Renault2020,
+            91 => TeamId::AlphaTauri2020,
+            92 => TeamId::Haas2020,
+            93 => TeamId::McLaren2020,
+            94 => TeamId::AlfaRomeo2020,
+            95 => TeamId::AstonMartinDB11V12,
+            96 => TeamId::AstonMartinVantageF1Edition,
+            97 => TeamId::AstonMartinVantageSafetyCar,
+            98 => TeamId::FerrariF8Tributo,
+            99 => TeamId::FerrariRoma,
+            100 => TeamId::McLaren720S,
+            101 => TeamId::McLarenArtura,
+            102 => TeamId::MercedesAMGGTBlackSeriesSafetyCar,
+            103 => TeamId::MercedesAMGGTRPro,
+            104 => TeamId::F1CustomTeam,
+            106 => TeamId::Prema21,
+            107 => TeamId::UniVirtuosi21,
+            108 => TeamId::Carlin21,
+            109 => TeamId::Hitech21,
+            110 => TeamId::ArtGP21,
+            111 => TeamId::MPMotorsport21,
+            112 => TeamId::Charouz21,
+            113 => TeamId::Dams21,
+            114 => TeamId::Campos21,
+            115 => TeamId::BWT21,
+            116 => TeamId::Trident21,
+            117 => TeamId::MercedesAMGGTBlackSeries,
+            118 => TeamId::Prema22,
+            119 => TeamId::Virtuosi22,
+            120 => TeamId::Carlin22,
+            121 => TeamId::Hitech22,
+            122 => TeamId::ArtGP22,
+            123 => TeamId::MPMotorsport22,
+            124 => TeamId::Charouz22,
+            125 => TeamId::Dams22,
+            126 => TeamId::Campos22,
+            127 => TeamId::VanAmersfoortRacing22,
+            128 => TeamId::Trident22,
+            255 => TeamId::None,
+            _ => return None,
+        };
+
+        Some(value)
+    }
+
+    /// Team ids used by F1 23
+    fn from_f23(raw: u8) -> Option<Self> {
+        let value = match raw {
+            0 => TeamId::Mercedes,
+            1 => TeamId::Ferrari,
+            2 => TeamId::RedBullRacing,
+            3 => TeamId::Williams,
+            4 => TeamId::AstonMartin,
+            5 => TeamId::Alpine,
+            6 => TeamId::AlphaTauri,
+            7 => TeamId::Haas,
+            8 => TeamId::McLaren,
+            9 => TeamId::AlfaRomeo,
+            85 => TeamId::Mercedes2020,
+            86 => TeamId::Ferrari2020,
+            87 => TeamId::RedBull2020,
+            88 => TeamId::Williams2020,
+            89 => TeamId::RacingPoint2020,
+            90 => TeamId::Renault2020,
+            91 => TeamId::AlphaTauri2020,
+            92 => TeamId::Haas2020,
+            93 => TeamId::McLaren2020,
+            94 => TeamId::AlfaRomeo2020,
+            95 => TeamId::AstonMartinDB11V12,
+            96 => TeamId::AstonMartinVantageF1Edition,
+            97 => TeamId::AstonMartinVantageSafetyCar,
+            98 => TeamId::FerrariF8Tributo,
+            99 => TeamId::FerrariRoma,
+            100 => TeamId::McLaren720S,
+            101 => TeamId::McLarenArtura,
+            102 => TeamId::MercedesAMGGTBlackSeriesSafetyCar,
+            103 => TeamId::MercedesAMGGTRPro,
+            104 => TeamId::F1CustomTeam,
+            106 => TeamId::Prema21,
+            107 => TeamId::UniVirtuosi21,
+            108 => TeamId::Carlin21,
+            109 => TeamId::Hitech21,
+            110 => TeamId::ArtGP21,
+            111 => TeamId::MPMotorsport21,
+            112 => TeamId::Charouz21,
+            113 => TeamId::Dams21,
+            114 => TeamId::Campos21,
+            115 => TeamId::BWT21,
+            116 => TeamId::Trident21,
+            117 => TeamId::MercedesAMGGTBlackSeries,
+            118 => TeamId::Mercedes22,
+            119 => TeamId::Ferrari22,
+            120 => TeamId::RedBullRacing22,
+            121 => TeamId::Williams22,
+            122 => TeamId::AstonMartin22,
+            123 => TeamId::Alpine22,
+            124 => TeamId::AlphaTauri22,
+            125 => TeamId::Haas22,
+            126 => TeamId::McLaren22,
+            127 => TeamId::AlfaRomeo22,
+            128 => TeamId::Konnersport22,
+            129 => TeamId::Konnersport,
+            130 => TeamId::Prema22,
+            131 => TeamId::Virtuosi22,
+            132 => TeamId::Carlin22,
+            133 => TeamId::MPMotorsport22,
+            134 => TeamId::Charouz22,
+            135 => TeamId::Dams22,
+            136 => TeamId::Campos22,
+            137 => TeamId::VanAmersfoortRacing22,
+            138 => TeamId::Trident22,
+            139 => TeamId::Hitech22,
+            140 => TeamId::ArtGP22,
+            255 => TeamId::None,
+            _ => return None,
+        };
+
+        Some(value)
+    }
+
+    /// Team ids used by F1 24
+    fn from_f24(raw: u8) -> Option<Self> {
+        let value = match raw {
+            0 => TeamId::Mercedes,
+            1 => TeamId::Ferrari,
+            2 => TeamId::RedBullRacing,
+            3 => TeamId::Williams,
+            4 => TeamId::AstonMartin,
+            5 => TeamId::Alpine,
+            6 => TeamId::RB,
+            7 => TeamId::Haas,
+            8 => TeamId::McLaren,
+            9 => TeamId::Sauber,
+            41 => TeamId::F1Generic,
+            104 => TeamId::F1CustomTeam,
+            143 => TeamId::ArtGP23,
+            144 => TeamId::Campos23,
+            145 => TeamId::Carlin23,
+            146 => TeamId::PHM23,
+            147 => TeamId::Dams23,
+            148 => TeamId::Hitech23,
+            149 => TeamId::MPMotorsport23,
+            150 => TeamId::Prema23,
+            151 => TeamId::Trident23,
+            152 => TeamId::VanAmersfoortRacing23,
+            153 => TeamId::Virtuosi23,
+            255 => TeamId::None,
+            _ => return None,
+        };
+
+        Some(value)
+    }
+
+    /// Team ids used by F1 25
+    fn from_f25(raw: u8) -> Option<Self> {
+        let value = match raw {
+            0 => TeamId::Mercedes,
+            1 => TeamId::Ferrari,
+            2 => TeamId::RedBullRacing,
+            3 => TeamId::Williams,
+            4 => TeamId::AstonMartin,
+            5 => TeamId::Alpine,
+            6 => TeamId::RB,
+            7 => TeamId::Haas,
+            8 => TeamId::McLaren,
+            9 => TeamId::Sauber,
+            41 => TeamId::F1Generic,
+            104 => TeamId::F1CustomTeam,
+            129 => TeamId::Konnersport,
+            142 => TeamId::APXGP24,
+            154 => TeamId::APXGP25,
+            155 => TeamId::Konnersport24,
+            158 => TeamId::ArtGP24,
+            159 => TeamId::Campos24,
+            160 => TeamId::RodinMotorsport24,
+            161 => TeamId::AIXRacing24,
+            162 => TeamId::Dams24,
+            163 => TeamId::Hitech24,
+            164 => TeamId::MPMotorsport24,
+            165 => TeamId::Prema24,
+            166 => TeamId::Trident24,
+            167 => TeamId::VanAmersfoortRacing24,
+            168 => TeamId::Invicta24,
+            185 => TeamId::Mercedes24,
+            186 => TeamId::Ferrari24,
+            187 => TeamId::RedBullRacing24,
+            188 => TeamId::Williams24,
+            189 => TeamId::AstonMartin24,
+            190 => TeamId::Alpine24,
+            191 => TeamId::RB24,
+            192 => TeamId::Haas24,
+            193 => TeamId::McLaren24,
+            194 => TeamId::Sauber24,
+            255 => TeamId::None,
+            _ => return None,
+        };
+
+        Some(value)
+    }
 }
 
 /// Enum representing a driver based on
 /// the `driver_id` field in a packet
-#[repr(u8)]
-#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default)]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
 pub enum DriverId {
-    CarlosSainz = 0,
-    DaniilKvyat = 1,
-    DanielRicciardo = 2,
-    FernandoAlonso = 3,
-    FelipeMassa = 4,
-    KimiRaikkonen = 6,
-    LewisHamilton = 7,
-    MaxVerstappen = 9,
-    NicoHulkenburg = 10,
-    KevinMagnussen = 11,
-    RomainGrosjean = 12,
-    SebastianVettel = 13,
-    SergioPerez = 14,
-    ValtteriBottas = 15,
-    EstebanOcon = 17,
-    LanceStroll = 19,
-    ArronBarnes = 20,
-    MartinGiles = 21,
-    AlexMurray = 22,
-    LucasRoth = 23,
-    IgorCorreia = 24,
-    SophieLevasseur = 25,
-    JonasSchiffer = 26,
-    AlainForest = 27,
-    JayLetourneau = 28,
-    EstoSaari = 29,
-    YasarAtiyeh = 30,
-    CallistoCalabresi = 31,
-    NaotaIzum = 32,
-    HowardClarke = 33,
-    WilheimKaufmann = 34,
-    MarieLaursen = 35,
-    FlavioNieves = 36,
-    PeterBelousov = 37,
-    KlimekMichalski = 38,
-    SantiagoMoreno = 39,
-    BenjaminCoppens = 40,
-    NoahVisser = 41,
-    GertWaldmuller = 42,
-    JulianQuesada = 43,
-    DanielJones = 44,
-    ArtemMarkelov = 45,
-    TadasukeMakino = 46,
-    SeanGelael = 47,
-    NyckDeVries = 48,
-    JackAitken = 49,
-    GeorgeRussell = 50,
-    MaximilianGunther = 51,
-    NireiFukuzumi = 52,
-    LucaGhiotto = 53,
-    LandoNorris = 54,
-    SergioSetteCamara = 55,
-    LouisDeletraz = 56,
-    AntonioFuoco = 57,
-    CharlesLeclerc = 58,
-    PierreGasly = 59,
-    AlexanderAlbon = 62,
-    NicholasLatifi = 63,
-    DorianBoccolacci = 64,
-    NikoKari = 65,
-    RobertoMerhi = 66,
-    ArjunMaini = 67,
-    AlessioLorandi = 68,
-    RubenMeijer = 69,
-    RashidNair = 70,
-    JackTremblay = 71,
-    DevonButler = 72,
-    LukasWeber = 73,
-    AntonioGiovinazzi = 74,
-    RobertKubica = 75,
-    AlainProst = 76,
-    AyrtonSenna = 77,
-    NobuharuMatsushita = 78,
-    NikitaMazepin = 79,
-    GuanyaZhou = 80,
-    MickSchumacher = 81,
-    CallumIlott = 82,
-    JuanManuelCorrea = 83,
-    JordanKing = 84,
-    MahaveerRaghunathan = 85,
-    TatianaCalderon = 86,
-    AnthoineHubert = 87,
-    GuilianoAlesi = 88,
-    RalphBoschung = 89,
-    MichaelSchumacher = 90,
-    DanTicktum = 91,
-    MarcusArmstrong = 92,
-    ChristianLundgaard = 93,
-    YukiTsunoda = 94,
-    JehanDaruvala = 95,
-    GulhermeSamaia = 96,
-    PedroPiquet = 97,
-    FelipeDrugovich = 98,
-    RobertSchwartzman = 99,
-    RoyNissany = 100,
-    MarinoSato = 101,
-    AidanJackson = 102,
-    CasperAkkerman = 103,
-    JensonButton = 109,
-    DavidCoulthard = 110,
-    NicoRosberg = 111,
-    OscarPiastri = 112,
-    LiamLawson = 113,
-    JuriVips = 114,
-    TheoPourchaire = 115,
-    RichardVerschoor = 116,
-    LirimZendeli = 117,
-    DavidBeckmann = 118,
-    AlessioDeledda = 121,
-    BentViscaal = 122,
-    EnzoFittipaldi = 123,
-    MarkWebber = 125,
-    JacquesVilleneuve = 126,
-    JakeHughes = 127,
-    FrederikVesti = 128,
-    OlliCaldwell = 129,
-    LoganSargeant = 130,
-    CemBolukbasi = 131,
-    AyumaIwasa = 132,
-    ClementNovolak = 133,
-    DennisHauger = 134,
-    CalanWilliams = 135,
-    JackDoohan = 136,
-    AmauryCordeel = 137,
-    MikaHakkinen = 138,
+    CarlosSainz,
+    DaniilKvyat,
+    DanielRicciardo,
+    FernandoAlonso,
+    FelipeMassa,
+    KimiRaikkonen,
+    LewisHamilton,
+    MaxVerstappen,
+    NicoHulkenburg,
+    KevinMagnussen,
+    RomainGrosjean,
+    SebastianVettel,
+    SergioPerez,
+    ValtteriBottas,
+    EstebanOcon,
+    LanceStroll,
+    ArronBarnes,
+    MartinGiles,
+    AlexMurray,
+    LucasRoth,
+    IgorCorreia,
+    SophieLevasseur,
+    JonasSchiffer,
+    AlainForest,
+    JayLetourneau,
+    EstoSaari,
+    YasarAtiyeh,
+    CallistoCalabresi,
+    NaotaIzum,
+    HowardClarke,
+    WilheimKaufmann,
+    MarieLaursen,
+    FlavioNieves,
+    PeterBelousov,
+    KlimekMichalski,
+    SantiagoMoreno,
+    BenjaminCoppens,
+    NoahVisser,
+    GertWaldmuller,
+    JulianQuesada,
+    DanielJones,
+    ArtemMarkelov,
+    TadasukeMakino,
+    SeanGelael,
+    NyckDeVries,
+    JackAitken,
+    GeorgeRussell,
+    MaximilianGunther,
+    NireiFukuzumi,
+    LucaGhiotto,
+    LandoNorris,
+    SergioSetteCamara,
+    LouisDeletraz,
+    AntonioFuoco,
+    CharlesLeclerc,
+    PierreGasly,
+    AlexanderAlbon,
+    NicholasLatifi,
+    DorianBoccolacci,
+    NikoKari,
+    RobertoMerhi,
+    ArjunMaini,
+    AlessioLorandi,
+    RubenMeijer,
+    RashidNair,
+    JackTremblay,
+    DevonButler,
+    LukasWeber,
+    AntonioGiovinazzi,
+    RobertKubica,
+    AlainProst,
+    AyrtonSenna,
+    NobuharuMatsushita,
+    NikitaMazepin,
+    GuanyaZhou,
+    MickSchumacher,
+    CallumIlott,
+    JuanManuelCorrea,
+    JordanKing,
+    MahaveerRaghunathan,
+    TatianaCalderon,
+    AnthoineHubert,
+    GuilianoAlesi,
+    RalphBoschung,
+    MichaelSchumacher,
+    DanTicktum,
+    MarcusArmstrong,
+    ChristianLundgaard,
+    YukiTsunoda,
+    JehanDaruvala,
+    GulhermeSamaia,
+    PedroPiquet,
+    FelipeDrugovich,
+    RobertSchwartzman,
+    RoyNissany,
+    MarinoSato,
+    AidanJackson,
+    CasperAkkerman,
+    JensonButton,
+    DavidCoulthard,
+    NicoRosberg,
+    OscarPiastri,
+    LiamLawson,
+    JuriVips,
+    TheoPourchaire,
+    RichardVerschoor,
+    LirimZendeli,
+    DavidBeckmann,
+    AlessioDeledda,
+    BentViscaal,
+    EnzoFittipaldi,
+    MarkWebber,
+    JacquesVilleneuve,
+    JakeHughes,
+    FrederikVesti,
+    OlliCaldwell,
+    LoganSargeant,
+    CemBolukbasi,
+    AyumaIwasa,
+    ClementNovolak,
+    DennisHauger,
+    CalanWilliams,
+    JackDoohan,
+    AmauryCordeel,
+    MikaHakkinen,
+    CallieMayer,
+    NoahBell,
+    JamieChadwick,
+    KamuiKobayashi,
+    PastorMaldonado,
+    NigelMansell,
+    ZaneMaloney,
+    VictorMartins,
+    OliverBearman,
+    JakCrawford,
+    IsackHadjar,
+    ArthurLeclerc,
+    BradBenavides,
+    RomanStanek,
+    KushMaini,
+    JamesHunt,
+    JuanPabloMontoya,
+    BrianLee,
 
     #[default]
-    NetworkHuman = 255,
+    NetworkHuman,
 }
 
-/// Enum representing an F1 track based on
-/// the `track_id` field in a packet
-#[repr(i8)]
-#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default)]
-pub enum TrackId {
-    #[default]
-    Unknown = -1,
-
-    Melbourne = 0,
-    PaulRicard = 1,
-    Shanghai = 2,
-    SakhirBahrain = 3,
-    Catalunya = 4,
-    Monaco = 5,
-    Montreal = 6,
-    Silverstone = 7,
-    Hockenheim = 8,
-    Hungaroring = 9,
-    Spa = 10,
-    Monza = 11,
-    Singapore = 12,
-    Suzuka = 13,
-    AbuDhabi = 14,
-    Texas = 15,
-    Brazil = 16,
-    Austria = 17,
-    Sochi = 18,
-    Mexico = 19,
-    BakuAzerbaijan = 20,
-    SakhirShort = 21,
-    SilverstoneShort = 22,
-    TexasShort = 23,
-    SuzukaShort = 24,
-    Hanoi = 25,
-    Zandvoort = 26,
-    Imola = 27,
-    Portimao = 28,
-    Jeddah = 29,
-    Miami = 30,
+impl DriverId {
+    /// Look up a driver from the raw `driver_id` of a packet.
+    ///
+    /// Driver ids from 127 onwards are reassigned between games, so the
+    /// table used depends on the packet format. The raw id is returned as
+    /// the error if it is not in the table.
+    pub fn from_format(packet_format: u16, raw: u8) -> Result<Self, u8> {
+        let value = match packet_format {
+            ..=2022 => Self::from_f22(raw),
+            2023 => Self::from_f23(raw),
+            _ => Self::from_f24(raw),
+        };
+
+        value.ok_or(raw)
+    }
+
+    /// Driver ids used by F1 22
+    fn from_f22(raw: u8) -> Option<Self> {
+        let value = match raw {
+            0 => DriverId::CarlosSainz,
+            1 => DriverId::DaniilKvyat,
+            2 => DriverId::DanielRicciardo,
+            3 => DriverId::FernandoAlonso,
+            4 => DriverId::FelipeMassa,
+            6 => DriverId::KimiRaikkonen,
+            7 => DriverId::LewisHamilton,
+            9 => DriverId::MaxVerstappen,
+            10 => DriverId::NicoHulkenburg,
+            11 => DriverId::KevinMagnussen,
+            12 => DriverId::RomainGrosjean,
+            13 => DriverId::SebastianVettel,
+            14 => DriverId::SergioPerez,
+            15 => DriverId::ValtteriBottas,
+            17 => DriverId::EstebanOcon,
+            19 => DriverId::LanceStroll,
+            20 => DriverId::ArronBarnes,
+            21 => DriverId::MartinGiles,
+            22 => DriverId::AlexMurray,
+            23 => DriverId::LucasRoth,
+            24 => DriverId::IgorCorreia,
+            25 => DriverId::SophieLevasseur,
+            26 => DriverId::JonasSchiffer,
+            27 => DriverId::AlainForest,
+            28 => DriverId::JayLetourneau,
+            29 => DriverId::EstoSaari,
+            30 => DriverId::YasarAtiyeh,
+            31 => DriverId::CallistoCalabresi,
+            32 => DriverId::NaotaIzum,
+            33 => DriverId::HowardClarke,
+            34 => DriverId::WilheimKaufmann,
+            35 => DriverId::MarieLaursen,
+            36 => DriverId::FlavioNieves,
+            37 => DriverId::PeterBelousov,
+            38 => DriverId::KlimekMichalski,
+            39 => DriverId::SantiagoMoreno,
+            40 => DriverId::BenjaminCoppens,
+            41 => DriverId::NoahVisser,
+            42 => DriverId::GertWaldmuller,
+            43 => DriverId::JulianQuesada,
+            44 => DriverId::DanielJones,
+            45 => DriverId::ArtemMarkelov,
+            46 => DriverId::TadasukeMakino,
+            47 => DriverId::SeanGelael,
+            48 => DriverId::NyckDeVries,
+            49 => DriverId::JackAitken,
+            50 => DriverId::GeorgeRussell,
+            51 => DriverId::MaximilianGunther,
+            52 => DriverId::NireiFukuzumi,
+            53 => DriverId::LucaGhiotto,
+            54 => DriverId::LandoNorris,
+            55 => DriverId::SergioSetteCamara,
+            56 => DriverId::LouisDeletraz,
+            57 => DriverId::AntonioFuoco,
+            58 => DriverId::CharlesLeclerc,
+            59 => DriverId::PierreGasly,
+            62 => DriverId::AlexanderAlbon,
+            63 => DriverId::NicholasLatifi,
+            64 => DriverId::DorianBoccolacci,
+            65 => DriverId::NikoKari,
+            66 => DriverId::RobertoMerhi,
+            67 => DriverId::ArjunMaini,
+            68 => DriverId::AlessioLorandi,
+            69 => DriverId::RubenMeijer,
+            70 => DriverId::RashidNair,
+            71 => DriverId::JackTremblay,
+            72 => DriverId::DevonButler,
+            73 => DriverId::LukasWeber,
+            74 => DriverId::AntonioGiovinazzi,
+            75 => DriverId::RobertKubica,
+            76 => DriverId::AlainProst,
+            77 => DriverId::AyrtonSenna,
+            78 => DriverId::NobuharuMatsushita,
+            79 => DriverId::NikitaMazepin,
+            80 => DriverId::GuanyaZhou,
+            81 => DriverId::MickSchumacher,
+            82 => DriverId::CallumIlott,
+            83 => DriverId::JuanManuelCorrea,
+            84 => DriverId::JordanKing,
+            85 => DriverId::MahaveerRaghunathan,
+            86 => DriverId::TatianaCalderon,
+            87 => DriverId::AnthoineHubert,
+            88 => DriverId::GuilianoAlesi,
+            89 => DriverId::RalphBoschung,
+            90 => DriverId::MichaelSchumacher,
+            91 => DriverId::DanTicktum,
+            92 => DriverId::MarcusArmstrong,
+            93 => DriverId::ChristianLundgaard,
+            94 => DriverId::YukiTsunoda,
+            95 => DriverId::JehanDaruvala,
+            96 => DriverId::GulhermeSamaia,
+            97 => DriverId::PedroPiquet,
+            98 => DriverId::FelipeDrugovich,
+            99 => DriverId::RobertSchwartzman,
+            100 => DriverId::RoyNissany,
+            101 => DriverId::MarinoSato,
+            102 => DriverId::AidanJackson,
+            103 => DriverId::CasperAkkerman,
+            109 => DriverId::JensonButton,
+            110 => DriverId::DavidCoulthard,
+            111 => DriverId::NicoRosberg,
+            112 => DriverId::OscarPiastri,
+            113 => DriverId::LiamLawson,
+            114 => DriverId::JuriVips,
+            115 => DriverId::TheoPourchaire,
+            116 => DriverId::RichardVerschoor,
+            117 => DriverId::LirimZendeli,
+            118 => DriverId::DavidBeckmann,
+            121 => DriverId::AlessioDeledda,
+            122 => DriverId::BentViscaal,
+            123 => DriverId::EnzoFittipaldi,
+            125 => DriverId::MarkWebber,
+            126 => DriverId::JacquesVilleneuve,
+            127 => DriverId::JakeHughes,
+            128 => DriverId::FrederikVesti,
+            129 => DriverId::OlliCaldwell,
+            130 => DriverId::LoganSargeant,
+            131 => DriverId::CemBolukbasi,
+            132 => DriverId::AyumaIwasa,
+            133 => DriverId::ClementNovolak,
+            134 => DriverId::DennisHauger,
+            135 => DriverId::CalanWilliams,
+            136 => DriverId::JackDoohan,
+            137 => DriverId::AmauryCordeel,
+            138 => DriverId::MikaHakkinen,
+            255 => DriverId::NetworkHuman,
+            _ => return None,
+        };
+
+        Some(value)
+    }
+
+    /// Driver ids used by F1 23
+    fn from_f23(raw: u8) -> Option<Self> {
+        let value = match raw {
+            0 => DriverId::CarlosSainz,
+            1 => DriverId::DaniilKvyat,
+            2 => DriverId::DanielRicciardo,
+            3 => DriverId::FernandoAlonso,
+            4 => DriverId::FelipeMassa,
+            6 => DriverId::KimiRaikkonen,
+            7 => DriverId::LewisHamilton,
+            9 => DriverId::MaxVerstappen,
+            10 => DriverId::NicoHulkenburg,
+            11 => DriverId::KevinMagnussen,
+            12 => DriverId::RomainGrosjean,
+            13 => DriverId::SebastianVettel,
+            14 => DriverId::SergioPerez,
+            15 => DriverId::ValtteriBottas,
+            17 => DriverId::EstebanOcon,
+            19 => DriverId::LanceStroll,
+            20 => DriverId::ArronBarnes,
+            21 => DriverId::MartinGiles,
+            22 => DriverId::AlexMurray,
+            23 => DriverId::LucasRoth,
+            24 => DriverId::IgorCorreia,
+            25 => DriverId::SophieLevasseur,
+            26 => DriverId::JonasSchiffer,
+            27 => DriverId::AlainForest,
+            28 => DriverId::JayLetourneau,
+            29 => DriverId::EstoSaari,
+            30 => DriverId::YasarAtiyeh,
+            31 => DriverId::CallistoCalabresi,
+            32 => DriverId::NaotaIzum,
+            33 => DriverId::HowardClarke,
+            34 => DriverId::WilheimKaufmann,
+            35 => DriverId::MarieLaursen,
+            36 => DriverId::FlavioNieves,
+            37 => DriverId::PeterBelousov,
+            38 => DriverId::KlimekMichalski,
+            39 => DriverId::SantiagoMoreno,
+            40 => DriverId::BenjaminCoppens,
+            41 => DriverId::NoahVisser,
+            42 => DriverId::GertWaldmuller,
+            43 => DriverId::JulianQuesada,
+            44 => DriverId::DanielJones,
+            45 => DriverId::ArtemMarkelov,
+            46 => DriverId::TadasukeMakino,
+            47 => DriverId::SeanGelael,
+            48 => DriverId::NyckDeVries,
+            49 => DriverId::JackAitken,
+            50 => DriverId::GeorgeRussell,
+            51 => DriverId::MaximilianGunther,
+            52 => DriverId::NireiFukuzumi,
+            53 => DriverId::LucaGhiotto,
+            54 => DriverId::LandoNorris,
+            55 => DriverId::SergioSetteCamara,
+            56 => DriverId::LouisDeletraz,
+            57 => DriverId::AntonioFuoco,
+            58 => DriverId::CharlesLeclerc,
+            59 => DriverId::PierreGasly,
+            62 => DriverId::AlexanderAlbon,
+            63 => DriverId::NicholasLatifi,
+            64 => DriverId::DorianBoccolacci,
+            65 => DriverId::NikoKari,
+            66 => DriverId::RobertoMerhi,
+            67 => DriverId::ArjunMaini,
+            68 => DriverId::AlessioLorandi,
+            69 => DriverId::RubenMeijer,
+            70 => DriverId::RashidNair,
+            71 => DriverId::JackTremblay,
+            72 => DriverId::DevonButler,
+            73 => DriverId::LukasWeber,
+            74 => DriverId::AntonioGiovinazzi,
+            75 => DriverId::RobertKubica,
+            76 => DriverId::AlainProst,
+            77 => DriverId::AyrtonSenna,
+            78 => DriverId::NobuharuMatsushita,
+            79 => DriverId::NikitaMazepin,
+            80 => DriverId::GuanyaZhou,
+            81 => DriverId::MickSchumacher,
+            82 => DriverId::CallumIlott,
+            83 => DriverId::JuanManuelCorrea,
+            84 => DriverId::JordanKing,
+            85 => DriverId::MahaveerRaghunathan,
+            86 => DriverId::TatianaCalderon,
+            87 => DriverId::AnthoineHubert,
+            88 => DriverId::GuilianoAlesi,
+            89 => DriverId::RalphBoschung,
+            90 => DriverId::MichaelSchumacher,
+            91 => DriverId::DanTicktum,
+            92 => DriverId::MarcusArmstrong,
+            93 => DriverId::ChristianLundgaard,
+            94 => DriverId::YukiTsunoda,
+            95 => DriverId::JehanDaruvala,
+            96 => DriverId::GulhermeSamaia,
+            97 => DriverId::PedroPiquet,
+            98 => DriverId::FelipeDrugovich,
+            99 => DriverId::RobertSchwartzman,
+            100 => DriverId::RoyNissany,
+            101 => DriverId::MarinoSato,
+            102 => DriverId::AidanJackson,
+            103 => DriverId::CasperAkkerman,
+            109 => DriverId::JensonButton,
+            110 => DriverId::DavidCoulthard,
+            111 => DriverId::NicoRosberg,
+            112 => DriverId::OscarPiastri,
+            113 => DriverId::LiamLawson,
+            114 => DriverId::JuriVips,
+            115 => DriverId::TheoPourchaire,
+            116 => DriverId::RichardVerschoor,
+            117 => DriverId::LirimZendeli,
+            118 => DriverId::DavidBeckmann,
+            121 => DriverId::AlessioDeledda,
+            122 => DriverId::BentViscaal,
+            123 => DriverId::EnzoFittipaldi,
+            125 => DriverId::MarkWebber,
+            126 => DriverId::JacquesVilleneuve,
+            127 => DriverId::CallieMayer,
+            128 => DriverId::NoahBell,
+            129 => DriverId::JakeHughes,
+            130 => DriverId::FrederikVesti,
+            131 => DriverId::OlliCaldwell,
+            132 => DriverId::LoganSargeant,
+            133 => DriverId::CemBolukbasi,
+            134 => DriverId::AyumaIwasa,
+            135 => DriverId::ClementNovolak,
+            136 => DriverId::JackDoohan,
+            137 => DriverId::AmauryCordeel,
+            138 => DriverId::DennisHauger,
+            139 => DriverId::CalanWilliams,
+            140 => DriverId::JamieChadwick,
+            141 => DriverId::KamuiKobayashi,
+            142 => DriverId::PastorMaldonado,
+            143 => DriverId::MikaHakkinen,
+            144 => DriverId::NigelMansell,
+            255 => DriverId::NetworkHuman,
+            _ => return None,
+        };
+
+        Some(value)
+    }
+
+    /// Driver ids used by F1 24 and later
+    fn from_f24(raw: u8) -> Option<Self> {
+        let value = match raw {
+            0 => DriverId::CarlosSainz,
+            1 => DriverId::DaniilKvyat,
+            2 => DriverId::DanielRicciardo,
+            3 => DriverId::FernandoAlonso,
+            4 => DriverId::FelipeMassa,
+            6 => DriverId::KimiRaikkonen,
+            7 => DriverId::LewisHamilton,
+            9 => DriverId::MaxVerstappen,
+            10 => DriverId::NicoHulkenburg,
+            11 => DriverId::KevinMagnussen,
+            12 => DriverId::RomainGrosjean,
+            13 => DriverId::SebastianVettel,
+            14 => DriverId::SergioPerez,
+            15 => DriverId::ValtteriBottas,
+            17 => DriverId::EstebanOcon,
+            19 => DriverId::LanceStroll,
+            20 => DriverId::ArronBarnes,
+            21 => DriverId::MartinGiles,
+            22 => DriverId::AlexMurray,
+            23 => DriverId::LucasRoth,
+            24 => DriverId::IgorCorreia,
+            25 => DriverId::SophieLevasseur,
+            26 => DriverId::JonasSchiffer,
+            27 => DriverId::AlainForest,
+            28 => DriverId::JayLetourneau,
+            29 => DriverId::EstoSaari,
+            30 => DriverId::YasarAtiyeh,
+            31 => DriverId::CallistoCalabresi,
+            32 => DriverId::NaotaIzum,
+            33 => DriverId::HowardClarke,
+            34 => DriverId::WilheimKaufmann,
+            35 => DriverId::MarieLaursen,
+            36 => DriverId::FlavioNieves,
+            37 => DriverId::PeterBelousov,
+            38 => DriverId::KlimekMichalski,
+            39 => DriverId::SantiagoMoreno,
+            40 => DriverId::BenjaminCoppens,
+            41 => DriverId::NoahVisser,
+            42 => DriverId::GertWaldmuller,
+            43 => DriverId::JulianQuesada,
+            44 => DriverId::DanielJones,
+            45 => DriverId::ArtemMarkelov,
+            46 => DriverId::TadasukeMakino,
+            47 => DriverId::SeanGelael,
+            48 => DriverId::NyckDeVries,
+            49 => DriverId::JackAitken,
+            50 => DriverId::GeorgeRussell,
+            51 => DriverId::MaximilianGunther,
+            52 => DriverId::NireiFukuzumi,
+            53 => DriverId::LucaGhiotto,
+            54 => DriverId::LandoNorris,
+            55 => DriverId::SergioSetteCamara,
+            56 => DriverId::LouisDeletraz,
+            57 => DriverId::AntonioFuoco,
+            58 => DriverId::CharlesLeclerc,
+            59 => DriverId::PierreGasly,
+            62 => DriverId::AlexanderAlbon,
+            63 => DriverId::NicholasLatifi,
+            64 => DriverId::DorianBoccolacci,
+            65 => DriverId::NikoKari,
+            66 => DriverId::RobertoMerhi,
+            67 => DriverId::ArjunMaini,
+            68 => DriverId::AlessioLorandi,
+            69 => DriverId::RubenMeijer,
+            70 => DriverId::RashidNair,
+            71 => DriverId::JackTremblay,
+            72 => DriverId::DevonButler,
+            73 => DriverId::LukasWeber,
+            74 => DriverId::AntonioGiovinazzi,
+            75 => DriverId::RobertKubica,
+            76 => DriverId::AlainProst,
+            77 => DriverId::AyrtonSenna,
+            78 => DriverId::NobuharuMatsushita,
+            79 => DriverId::NikitaMazepin,
+            80 => DriverId::GuanyaZhou,
+            81 => DriverId::MickSchumacher,
+            82 => DriverId::CallumIlott,
+            83 => DriverId::JuanManuelCorrea,
+            84 => DriverId::JordanKing,
+            85 => DriverId::MahaveerRaghunathan,
+            86 => DriverId::TatianaCalderon,
+            87 => DriverId::AnthoineHubert,
+            88 => DriverId::GuilianoAlesi,
+            89 => DriverId::RalphBoschung,
+            90 => DriverId::MichaelSchumacher,
+            91 => DriverId::DanTicktum,
+            92 => DriverId::MarcusArmstrong,
+            93 => DriverId::ChristianLundgaard,
+            94 => DriverId::YukiTsunoda,
+            95 => DriverId::JehanDaruvala,
+            96 => DriverId::GulhermeSamaia,
+            97 => DriverId::PedroPiquet,
+            98 => DriverId::FelipeDrugovich,
+            99 => DriverId::RobertSchwartzman,
+            100 => DriverId::RoyNissany,
+            101 => DriverId::MarinoSato,
+            102 => DriverId::AidanJackson,
+            103 => DriverId::CasperAkkerman,
+            109 => DriverId::JensonButton,
+            110 => DriverId::DavidCoulthard,
+            111 => DriverId::NicoRosberg,
+            112 => DriverId::OscarPiastri,
+            113 => DriverId::LiamLawson,
+            114 => DriverId::JuriVips,
+            115 => DriverId::TheoPourchaire,
+            116 => DriverId::RichardVerschoor,
+            117 => DriverId::LirimZendeli,
+            118 => DriverId::DavidBeckmann,
+            121 => DriverId::AlessioDeledda,
+            122 => DriverId::BentViscaal,
+            123 => DriverId::EnzoFittipaldi,
+            125 => DriverId::MarkWebber,
+            126 => DriverId::JacquesVilleneuve,
+            127 => DriverId::CallieMayer,
+            128 => DriverId::NoahBell,
+            129 => DriverId::JakeHughes,
+            130 => DriverId::FrederikVesti,
+            131 => DriverId::OlliCaldwell,
+            132 => DriverId::LoganSargeant,
+            133 => DriverId::CemBolukbasi,
+            134 => DriverId::AyumaIwasa,
+            135 => DriverId::ClementNovolak,
+            136 => DriverId::JackDoohan,
+            137 => DriverId::AmauryCordeel,
+            138 => DriverId::DennisHauger,
+            139 => DriverId::CalanWilliams,
+            140 => DriverId::JamieChadwick,
+            141 => DriverId::KamuiKobayashi,
+            142 => DriverId::PastorMaldonado,
+            143 => DriverId::MikaHakkinen,
+            144 => DriverId::NigelMansell,
+            145 => DriverId::ZaneMaloney,
+            146 => DriverId::VictorMartins,
+            147 => DriverId::OliverBearman,
+            148 => DriverId::JakCrawford,
+            149 => DriverId::IsackHadjar,
+            150 => DriverId::ArthurLeclerc,
+            151 => DriverId::BradBenavides,
+            152 => DriverId::RomanStanek,
+            153 => DriverId::KushMaini,
+            154 => DriverId::JamesHunt,
+            155 => DriverId::JuanPabloMontoya,
+            156 => DriverId::BrianLee,
+            255 => DriverId::NetworkHuman,
+            _ => return None,
+        };
+
+        Some(value)
+    }
 }
 
-/// Enum representing a nationality based on
-/// the `nationality` field in a packet
-#[repr(u8)]
-#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default)]
-pub enum NationalityId {
-    American = 1,
-    Argentinean = 2,
-    Australian = 3,
-    Austrian = 4,
-    Azerbaijani = 5,
-    Bahraini = 6,
-    Belgian = 7,
-    Bolivian = 8,
-    Brazilian = 9,
-    British = 10,
-    Bulgarian = 11,
-    Cameroonian = 12,
-    Canadian = 13,
-    Chilean = 14,
-    Chinese = 15,
-    Colombian = 16,
-    CostaRican = 17,
-    Croatian = 18,
-    Cypriot = 19,
-    Czech = 20,
-    Danish = 21,
-    Dutch = 22,
-    Ecuadorian = 23,
-    English = 24,
-    Emirian = 25,
-    Estonian = 26,
-    Finnish = 27,
-    French = 28,
-    German = 29,
-    Ghanaian = 30,
-    Greek = 31,
-    Guatemalan = 32,
-    Honduran = 33,
-    HongKonger = 34,
-    Hungarian = 35,
-    Icelander = 36,
-    Indian = 37,
-    Indonesian = 38,
-    Irish = 39,
-    Israeli = 40,
-    Italian = 41,
-    Jamaican = 42,
-    Japanese = 43,
-    Jordanian = 44,
-    Kuwaiti = 45,
-    Latvian = 46,
-    Lebanese = 47,
-    Lithuanian = 48,
-    Luxembourger = 49,
-    Malaysian = 50,
-    Maltese = 51,
-    Mexican = 52,
-    Monegasque = 53,
-    NewZealander = 54,
-    Nicaraguan = 55,
-    NorthernIrish = 56,
-    Norwegian = 57,
-    Omani = 58,
-    Pakistani = 59,
-    Panamanian = 60,
-    Paraguayan = 61,
-    Peruvian = 62,
-    Polish = 63,
-    Portuguese = 64,
-    Qatari = 65,
-    Romanian = 66,
-    Russian = 67,
-    Salvadoran = 68,
-    Saudi = 69,
-    Scottish = 70,
-    Serbian = 71,
-    Singaporean = 72,
-    Slovakian = 73,
-    Slovenian = 74,
-    SouthKorean = 75,
-    SouthAfrican = 76,
-    Spanish = 77,
-    Swedish = 78,
-    Swiss = 79,
-    Thai = 80,
-    Turkish = 81,
-    Uruguayan = 82,
-    Ukrainian = 83,
-    Venezuelan = 84,
-    Barbadian = 85,
-    Welsh = 86,
-    Vietnamese = 87,
+id_enum! {
+    /// Enum representing an F1 track based on
+    /// the `track_id` field in a packet
+    #[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+    pub enum TrackId: i8 {
+        #[default]
+        Unknown = -1,
 
-    #[default]
-    None = 255,
+        Melbourne = 0,
+        PaulRicard = 1,
+        Shanghai = 2,
+        SakhirBahrain = 3,
+        Catalunya = 4,
+        Monaco = 5,
+        Montreal = 6,
+        Silverstone = 7,
+        Hockenheim = 8,
+        Hungaroring = 9,
+        Spa = 10,
+        Monza = 11,
+        Singapore = 12,
+        Suzuka = 13,
+        AbuDhabi = 14,
+        Texas = 15,
+        Brazil = 16,
+        Austria = 17,
+        Sochi = 18,
+        Mexico = 19,
+        BakuAzerbaijan = 20,
+        SakhirShort = 21,
+        SilverstoneShort = 22,
+        TexasShort = 23,
+        SuzukaShort = 24,
+        Hanoi = 25,
+        Zandvoort = 26,
+        Imola = 27,
+        Portimao = 28,
+        Jeddah = 29,
+        Miami = 30,
+        LasVegas = 31,
+        Losail = 32,
+        SilverstoneReverse = 39,
+        AustriaReverse = 40,
+        ZandvoortReverse = 41,
+    }
 }
 
-/// Enum representing a game mode based on
-/// the `gameMode` field in a packet
-#[repr(u8)]
-#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
-pub enum GameModeId {
-    EventMode = 0,
-    GrandPrix = 3,
-    TimeTrial = 5,
-    Splitscreen = 6,
-    OnlineCustom = 7,
-    OnlineLeague = 8,
-    CareerInvitational = 11,
-    ChampionshipInvitational = 12,
-    Championship = 13,
-    OnlineChampionship = 14,
-    OnlineWeeklyEvent = 15,
-    Career = 19,
-    CareerOnline = 20,
-    Benchmark = 127,
+id_enum! {
+    /// Enum representing a nationality based on
+    /// the `nationality` field in a packet
+    #[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+    pub enum NationalityId: u8 {
+        American = 1,
+        Argentinean = 2,
+        Australian = 3,
+        Austrian = 4,
+        Azerbaijani = 5,
+        Bahraini = 6,
+        Belgian = 7,
+        Bolivian = 8,
+        Brazilian = 9,
+        British = 10,
+        Bulgarian = 11,
+        Cameroonian = 12,
+        Canadian = 13,
+        Chilean = 14,
+        Chinese = 15,
+        Colombian = 16,
+        CostaRican = 17,
+        Croatian = 18,
+        Cypriot = 19,
+        Czech = 20,
+        Danish = 21,
+        Dutch = 22,
+        Ecuadorian = 23,
+        English = 24,
+        Emirian = 25,
+        Estonian = 26,
+        Finnish = 27,
+        French = 28,
+        German = 29,
+        Ghanaian = 30,
+        Greek = 31,
+        Guatemalan = 32,
+        Honduran = 33,
+        HongKonger = 34,
+        Hungarian = 35,
+        Icelander = 36,
+        Indian = 37,
+        Indonesian = 38,
+        Irish = 39,
+        Israeli = 40,
+        Italian = 41,
+        Jamaican = 42,
+        Japanese = 43,
+        Jordanian = 44,
+        Kuwaiti = 45,
+        Latvian = 46,
+        Lebanese = 47,
+        Lithuanian = 48,
+        Luxembourger = 49,
+        Malaysian = 50,
+        Maltese = 51,
+        Mexican = 52,
+        Monegasque = 53,
+        NewZealander = 54,
+        Nicaraguan = 55,
+        NorthernIrish = 56,
+        Norwegian = 57,
+        Omani = 58,
+        Pakistani = 59,
+        Panamanian = 60,
+        Paraguayan = 61,
+        Peruvian = 62,
+        Polish = 63,
+        Portuguese = 64,
+        Qatari = 65,
+        Romanian = 66,
+        Russian = 67,
+        Salvadoran = 68,
+        Saudi = 69,
+        Scottish = 70,
+        Serbian = 71,
+        Singaporean = 72,
+        Slovakian = 73,
+        Slovenian = 74,
+        SouthKorean = 75,
+        SouthAfrican = 76,
+        Spanish = 77,
+        Swedish = 78,
+        Swiss = 79,
+        Thai = 80,
+        Turkish = 81,
+        Uruguayan = 82,
+        Ukrainian = 83,
+        Venezuelan = 84,
+        Barbadian = 85,
+        Welsh = 86,
+        Vietnamese = 87,
+
+        #[default]
+        None = 255,
+    }
 }
 
-/// Enum representing a ruleset based on
-/// the `rule_set` field in a packet
-#[repr(u8)]
-#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
-pub enum RulesetId {
-    PracticeAndQualifying = 0,
-    Race = 1,
-    TimeTrial = 2,
-    TimeAttack = 4,
-    CheckpointChallenge = 6,
-    Autocross = 8,
-    Drift = 9,
-    AverageSpeedZone = 10,
-    RivalDuel = 11,
+id_enum! {
+    /// Enum representing a game mode based on
+    /// the `gameMode` field in a packet
+    #[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum GameModeId: u8 {
+        EventMode = 0,
+        GrandPrix = 3,
+        TimeTrial = 5,
+        Splitscreen = 6,
+        OnlineCustom = 7,
+        OnlineLeague = 8,
+        CareerInvitational = 11,
+        ChampionshipInvitational = 12,
+        Championship = 13,
+        OnlineChampionship = 14,
+        OnlineWeeklyEvent = 15,
+        Career = 19,
+        CareerOnline = 20,
+        GrandPrix23 = 4,
+        StoryMode = 17,
+        Career23 = 21,
+        CareerOnline23 = 22,
+        Career24 = 23,
+        CareerOnline24 = 24,
+        ChallengeCareer24 = 25,
+        StoryModeApxgp = 26,
+        Career25 = 27,
+        CareerOnline25 = 28,
+        ChallengeCareer25 = 29,
+        Benchmark = 127,
+    }
 }
 
-/// Type of contact a wheel is experiencing
-#[repr(u8)]
-#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
-pub enum SurfaceType {
-    Tarmac = 0,
-    RumbleStrip = 1,
-    Concrete = 2,
-    Rock = 3,
-    Gravel = 4,
-    Mud = 5,
-    Sand = 6,
-    Grass = 7,
-    Water = 8,
-    Cobblestone = 9,
-    Metal = 10,
-    Ridged = 11,
+id_enum! {
+    /// Enum representing a ruleset based on
+    /// the `rule_set` field in a packet
+    #[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum RulesetId: u8 {
+        PracticeAndQualifying = 0,
+        Race = 1,
+        TimeTrial = 2,
+        TimeAttack = 4,
+        CheckpointChallenge = 6,
+        Autocross = 8,
+        Drift = 9,
+        AverageSpeedZone = 10,
+        RivalDuel = 11,
+    }
+}
+
+id_enum! {
+    /// Type of contact a wheel is experiencing
+    #[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum SurfaceType: u8 {
+        Tarmac = 0,
+        RumbleStrip = 1,
+        Concrete = 2,
+        Rock = 3,
+        Gravel = 4,
+        Mud = 5,
+        Sand = 6,
+        Grass = 7,
+        Water = 8,
+        Cobblestone = 9,
+        Metal = 10,
+        Ridged = 11,
+    }
 }
 
 /// Used in the telemetry packet to determine
@@ -510,29 +1326,30 @@ impl ButtonFlag {
     }
 }
 
-/// Enum representing a penalty based on
-/// the `penalty_type` field in a packet
-#[repr(u8)]
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
-pub enum PenaltyType {
-    DriveThrough = 0,
-    StopGo = 1,
-    GridPenalty = 2,
-    PenaltyReminder = 3,
-    TimePenalty = 4,
-    Warning = 5,
-    Disqualified = 6,
-    RemovedFromFormationLap = 7,
-    ParkedTooLongTimer = 8,
-    TyreRegulations = 9,
-    ThisLapInvalidated = 10,
-    ThisAndNextLapInvalidated = 11,
-    ThisLapInvalidatedWithoutReason = 12,
-    ThisAndNextLapInvalidatedWithoutReason = 13,
-    ThisAndPreviousLapInvalidated = 14,
-    ThisAndPreviousLapInvalidatedWithoutReason = 15,
-    Retired = 16,
-    BlackFlagTimer = 17,
+id_enum! {
+    /// Enum representing a penalty based on
+    /// the `penalty_type` field in a packet
+    #[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+    pub enum PenaltyType: u8 {
+        DriveThrough = 0,
+        StopGo = 1,
+        GridPenalty = 2,
+        PenaltyReminder = 3,
+        TimePenalty = 4,
+        Warning = 5,
+        Disqualified = 6,
+        RemovedFromFormationLap = 7,
+        ParkedTooLongTimer = 8,
+        TyreRegulations = 9,
+        ThisLapInvalidated = 10,
+        ThisAndNextLapInvalidated = 11,
+        ThisLapInvalidatedWithoutReason = 12,
+        ThisAndNextLapInvalidatedWithoutReason = 13,
+        ThisAndPreviousLapInvalidated = 14,
+        ThisAndPreviousLapInvalidatedWithoutReason = 15,
+        Retired = 16,
+        BlackFlagTimer = 17,
+    }
 }
 
 impl PenaltyType {
@@ -568,137 +1385,218 @@ impl PenaltyType {
     }
 }
 
-/// Enum representing an infringement type based on
-/// the `infringement_type` field in a packet
-#[repr(u8)]
-#[derive(Debug, Clone, Copy)]
-pub enum InfringementType {
-    BlockingBySlowDriving = 0,
-    BlockingByWrongWayDriving = 1,
-    ReversingOffTheStartLine = 2,
-    BigCollision = 3,
-    SmallCollision = 4,
-    CollisionFailedToHandBackPositionSingle = 5,
-    CollisionFailedToHandBackPositionMultiple = 6,
-    CornerCuttingGainedTime = 7,
-    CornerCuttingOvertakeSingle = 8,
-    CornerCuttingOvertakeMultiple = 9,
-    CrossedPitExitLane = 10,
-    IgnoringBlueFlags = 11,
-    IgnoringYellowFlags = 12,
-    IgnoringDriveThrough = 13,
-    TooManyDriveThroughs = 14,
-    DriveThroughReminderServeWithinNLaps = 15,
-    DriveThroughReminderServeThisLap = 16,
-    PitLaneSpeeding = 17,
-    ParkedForTooLong = 18,
-    IgnoringTyreRegulations = 19,
-    TooManyPenalties = 20,
-    MultipleWarnings = 21,
-    ApproachingDisqualification = 22,
-    TyreRegulationsSelectSingle = 23,
-    TyreRegulationsSelectMultiple = 24,
-    LapInvalidatedCornerCutting = 25,
-    LapInvalidatedRunningWide = 26,
-    CornerCuttingRanWideGainedTimeMinor = 27,
-    CornerCuttingRanWideGainedTimeSignificant = 28,
-    CornerCuttingRanWideGainedTimeExtreme = 29,
-    LapInvalidatedWallRiding = 30,
-    LapInvalidatedFlashbackUsed = 31,
-    LapInvalidatedResetToTrack = 32,
-    BlockingThePitlane = 33,
-    JumpStart = 34,
-    SafetyCarToCarCollision = 35,
-    SafetyCarIllegalOvertake = 36,
-    SafetyCarExceedingAllowedPace = 37,
-    VirtualSafetyCarExceedingAllowedPace = 38,
-    FormationLapBelowAllowedSpeed = 39,
-    FormationLapParking = 40,
-    RetiredMechanicalFailure = 41,
-    RetiredTerminallyDamaged = 42,
-    SafetyCarFallingTooFarBack = 43,
-    BlackFlagTimer = 44,
-    UnservedStopGoPenalty = 45,
-    UnservedDriveThroughPenalty = 46,
-    EngineComponentChange = 47,
-    GearboxChange = 48,
-    ParcFermeChange = 49,
-    LeagueGridPenalty = 50,
-    RetryPenalty = 51,
-    IllegalTimeGain = 52,
-    MandatoryPitstop = 53,
-    AttributeAssigned = 54,
+id_enum! {
+    /// Enum representing an infringement type based on
+    /// the `infringement_type` field in a packet
+    #[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub enum InfringementType: u8 {
+        BlockingBySlowDriving = 0,
+        BlockingByWrongWayDriving = 1,
+        ReversingOffTheStartLine = 2,
+        BigCollision = 3,
+        SmallCollision = 4,
+        CollisionFailedToHandBackPositionSingle = 5,
+        CollisionFailedToHandBackPositionMultiple = 6,
+        CornerCuttingGainedTime = 7,
+        CornerCuttingOvertakeSingle = 8,
+        CornerCuttingOvertakeMultiple = 9,
+        CrossedPitExitLane = 10,
+        IgnoringBlueFlags = 11,
+        IgnoringYellowFlags = 12,
+        IgnoringDriveThrough = 13,
+        TooManyDriveThroughs = 14,
+        DriveThroughReminderServeWithinNLaps = 15,
+        DriveThroughReminderServeThisLap = 16,
+        PitLaneSpeeding = 17,
+        ParkedForTooLong = 18,
+        IgnoringTyreRegulations = 19,
+        TooManyPenalties = 20,
+        MultipleWarnings = 21,
+        ApproachingDisqualification = 22,
+        TyreRegulationsSelectSingle = 23,
+        TyreRegulationsSelectMultiple = 24,
+        LapInvalidatedCornerCutting = 25,
+        LapInvalidatedRunningWide = 26,
+        CornerCuttingRanWideGainedTimeMinor = 27,
+        CornerCuttingRanWideGainedTimeSignificant = 28,
+        CornerCuttingRanWideGainedTimeExtreme = 29,
+        LapInvalidatedWallRiding = 30,
+        LapInvalidatedFlashbackUsed = 31,
+        LapInvalidatedResetToTrack = 32,
+        BlockingThePitlane = 33,
+        JumpStart = 34,
+        SafetyCarToCarCollision = 35,
+        SafetyCarIllegalOvertake = 36,
+        SafetyCarExceedingAllowedPace = 37,
+        VirtualSafetyCarExceedingAllowedPace = 38,
+        FormationLapBelowAllowedSpeed = 39,
+        FormationLapParking = 40,
+        RetiredMechanicalFailure = 41,
+        RetiredTerminallyDamaged = 42,
+        SafetyCarFallingTooFarBack = 43,
+        BlackFlagTimer = 44,
+        UnservedStopGoPenalty = 45,
+        UnservedDriveThroughPenalty = 46,
+        EngineComponentChange = 47,
+        GearboxChange = 48,
+        ParcFermeChange = 49,
+        LeagueGridPenalty = 50,
+        RetryPenalty = 51,
+        IllegalTimeGain = 52,
+        MandatoryPitstop = 53,
+        AttributeAssigned = 54,
+    }
 }
 
 /// Enum representing a session type based on
 /// the `session_type` field in a packet
-#[repr(u8)]
-#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy)]
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
 pub enum SessionType {
     #[default]
-    Unknown = 0,
-
-    P1 = 1,
-    P2 = 2,
-    P3 = 3,
-    ShortP = 4,
-    Q1 = 5,
-    Q2 = 6,
-    Q3 = 7,
-    ShortQ = 8,
-    OSQ = 9,
-    R = 10,
-    R2 = 11,
-    R3 = 12,
-    TimeTrial = 13,
+    Unknown,
+    P1,
+    P2,
+    P3,
+    ShortP,
+    Q1,
+    Q2,
+    Q3,
+    ShortQ,
+    OSQ,
+    SQ1,
+    SQ2,
+    SQ3,
+    ShortSQ,
+    OSSQ,
+    R,
+    R2,
+    R3,
+    TimeTrial,
 }
 
-/// Enum representing a state of weather based on
-/// the `weather` field in a packet
-#[repr(u8)]
-#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy)]
-pub enum WeatherType {
-    #[default]
-    Clear = 0,
+impl SessionType {
+    /// Whether cars are classified by distance covered rather than best lap
+    pub fn is_race(&self) -> bool {
+        matches!(self, SessionType::R | SessionType::R2 | SessionType::R3)
+    }
 
-    LightCloud = 1,
-    Overcast = 2,
-    LightRain = 3,
-    HeavyRain = 4,
-    Storm = 5,
+    /// Look up a session type from the raw `session_type` of a packet.
+    ///
+    /// F1 24 inserted the sprint qualifying sessions before the race
+    /// sessions, so the table used depends on the packet format. The raw
+    /// value is returned as the error if it is not in the table.
+    pub fn from_format(packet_format: u16, raw: u8) -> Result<Self, u8> {
+        let value = match packet_format {
+            ..=2023 => Self::from_f22(raw),
+            _ => Self::from_f24(raw),
+        };
+
+        value.ok_or(raw)
+    }
+
+    /// Session types used by F1 22 and F1 23
+    fn from_f22(raw: u8) -> Option<Self> {
+        let value = match raw {
+            0 => SessionType::Unknown,
+            1 => SessionType::P1,
+            2 => SessionType::P2,
+            3 => SessionType::P3,
+            4 => SessionType::ShortP,
+            5 => SessionType::Q1,
+            6 => SessionType::Q2,
+            7 => SessionType::Q3,
+            8 => SessionType::ShortQ,
+            9 => SessionType::OSQ,
+            10 => SessionType::R,
+            11 => SessionType::R2,
+            12 => SessionType::R3,
+            13 => SessionType::TimeTrial,
+            _ => return None,
+        };
+
+        Some(value)
+    }
+
+    /// Session types used by F1 24 and later, which added sprint qualifying
+    fn from_f24(raw: u8) -> Option<Self> {
+        let value = match raw {
+            0 => SessionType::Unknown,
+            1 => SessionType::P1,
+            2 => SessionType::P2,
+            3 => SessionType::P3,
+            4 => SessionType::ShortP,
+            5 => SessionType::Q1,
+            6 => SessionType::Q2,
+            7 => SessionType::Q3,
+            8 => SessionType::ShortQ,
+            9 => SessionType::OSQ,
+            10 => SessionType::SQ1,
+            11 => SessionType::SQ2,
+            12 => SessionType::SQ3,
+            13 => SessionType::ShortSQ,
+            14 => SessionType::OSSQ,
+            15 => SessionType::R,
+            16 => SessionType::R2,
+            17 => SessionType::R3,
+            18 => SessionType::TimeTrial,
+            _ => return None,
+        };
+
+        Some(value)
+    }
 }
 
-/// Enum representing the formula mode based on
-/// the `formula` field in a packet. e.g: f1, f2, etc
-#[repr(u8)]
-#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy)]
-pub enum FormulaType {
-    #[default]
-    F1Modern = 0,
-
-    F1Classic = 1,
-    F2 = 2,
-    F1Generic = 3,
-    Beta = 4,
-    Supercards = 5,
-    Esports = 6,
-    F22021 = 7,
+id_enum! {
+    /// Enum representing a state of weather based on
+    /// the `weather` field in a packet
+    #[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+    pub enum WeatherType: u8 {
+        #[default]
+        Clear = 0,
+
+        LightCloud = 1,
+        Overcast = 2,
+        LightRain = 3,
+        HeavyRain = 4,
+        Storm = 5,
+    }
 }
 
-/// Enum representing the length of a session based on
-/// the `session_length` field in a packet
-#[repr(u8)]
-#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy)]
-pub enum SessionLength {
-    #[default]
-    None = 0,
-
-    VeryShort = 2,
-    Short = 3,
-    Medium = 4,
-    MediumLong = 5,
-    Long = 6,
-    Full = 7,
+id_enum! {
+    /// Enum representing the formula mode based on
+    /// the `formula` field in a packet. e.g: f1, f2, etc
+    #[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+    pub enum FormulaType: u8 {
+        #[default]
+        F1Modern = 0,
+
+        F1Classic = 1,
+        F2 = 2,
+        F1Generic = 3,
+        Beta = 4,
+        Supercards = 5,
+        Esports = 6,
+        F22021 = 7,
+        F1World = 8,
+        F1Elimination = 9,
+    }
+}
+
+id_enum! {
+    /// Enum representing the length of a session based on
+    /// the `session_length` field in a packet
+    #[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+    pub enum SessionLength: u8 {
+        #[default]
+        None = 0,
+
+        VeryShort = 2,
+        Short = 3,
+        Medium = 4,
+        MediumLong = 5,
+        Long = 6,
+        Full = 7,
+    }
 }
 
 /// Enum representing an event based on
@@ -723,4 +1621,8 @@ pub enum EventId {
     StopGoServed,
     Flashback,
     ButtonStatus,
+    RedFlag,
+    Overtake,
+    SafetyCar,
+    Collision,
 }
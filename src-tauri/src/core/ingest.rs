@@ -0,0 +1,205 @@
+use std::{
+    io,
+    mem::MaybeUninit,
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        mpsc::{sync_channel, Receiver, SyncSender, TrySendError},
+        Arc,
+    },
+    thread::{self, JoinHandle},
+    time::{Duration, Instant},
+};
+
+use serde::{Deserialize, Serialize};
+use socket2::Socket;
+
+use crate::core::{parse_packet, Relay, TelemetryPacket};
+
+/// Number of datagrams that can be waiting to be handled before
+/// new ones are dropped. A few seconds of every packet at 60 Hz
+/// with 22 cars.
+pub const DEFAULT_QUEUE_LEN: usize = 4096;
+
+/// Size of the socket's receive buffer to ask the OS for, so bursts
+/// of datagrams aren't dropped by the OS before they're received.
+/// The OS may give a smaller buffer.
+const SOCKET_RECV_BUFFER_SIZE: usize = 4 * 1024 * 1024;
+
+/// How often the receiver thread checks if it should stop
+/// when no datagrams are arriving.
+const STOP_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// A datagram received by a [`PacketReceiver`], decoded if possible.
+#[derive(Clone)]
+pub struct ReceivedDatagram {
+    /// The datagram as received, for recording
+    pub data: Vec<u8>,
+
+    /// None if the datagram could not be decoded
+    pub packet: Option<TelemetryPacket>,
+    pub received_at: Instant,
+}
+
+impl ReceivedDatagram {
+    pub fn decode(data: Vec<u8>) -> Self {
+        let packet = match parse_packet(&data) {
+            Ok(packet) => Some(packet),
+            Err(e) => {
+                println!("[backend] Dropped packet: {e}");
+                None
+            }
+        };
+
+        Self {
+            data,
+            packet,
+            received_at: Instant::now(),
+        }
+    }
+}
+
+/// Counters for a [`PacketReceiver`] since it was started
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct IngestStats {
+    pub received: u64,
+    pub decoded: u64,
+    pub undecodable: u64,
+
+    /// Datagrams dropped because the queue was full
+    pub dropped: u64,
+}
+
+#[derive(Debug, Default)]
+struct Counters {
+    received: AtomicU64,
+    decoded: AtomicU64,
+    undecodable: AtomicU64,
+    dropped: AtomicU64,
+}
+
+/// Receives datagrams on a dedicated thread as fast as they arrive,
+/// decodes them and queues them for whoever owns the other end of the
+/// channel returned by [`PacketReceiver::spawn`].
+///
+/// Nothing is locked on the receiving side, so a slow consumer can't
+/// hold up the socket. If the queue is full, new datagrams are dropped
+/// and counted instead of blocking.
+#[derive(Debug)]
+pub struct PacketReceiver {
+    stop: Arc<AtomicBool>,
+    counters: Arc<Counters>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl PacketReceiver {
+    /// Start receiving on `socket`, queueing up to `queue_len` datagrams.
+    /// Every datagram is forwarded by `relay` as soon as it's received.
+    ///
+    /// The receiver stops when stopped or dropped, or when the returned
+    /// channel is dropped.
+    pub fn spawn(
+        socket: Socket,
+        queue_len: usize,
+        relay: Relay,
+    ) -> io::Result<(Self, Receiver<ReceivedDatagram>)> {
+        // Wake up regularly to check if the receiver was stopped
+        socket.set_read_timeout(Some(STOP_POLL_INTERVAL))?;
+        let _ = socket.set_recv_buffer_size(SOCKET_RECV_BUFFER_SIZE);
+
+        let (sender, receiver) = sync_channel(queue_len);
+        let stop = Arc::new(AtomicBool::new(false));
+        let counters = Arc::new(Counters::default());
+
+        let thread = {
+            let stop = Arc::clone(&stop);
+            let counters = Arc::clone(&counters);
+            thread::spawn(move || Self::receive(socket, sender, &relay, &stop, &counters))
+        };
+
+        Ok((
+            Self {
+                stop,
+                counters,
+                thread: Some(thread),
+            },
+            receiver,
+        ))
+    }
+
+    fn receive(
+        socket: Socket,
+        sender: SyncSender<ReceivedDatagram>,
+        relay: &Relay,
+        stop: &AtomicBool,
+        counters: &Counters,
+    ) {
+        let mut buf = [MaybeUninit::<u8>::uninit(); 2048];
+
+        while !stop.load(Ordering::Relaxed) {
+            let bytes_received = match socket.recv(&mut buf) {
+                Ok(bytes_received) => bytes_received,
+                Err(e)
+                    if matches!(
+                        e.kind(),
+                        io::ErrorKind::WouldBlock
+                            | io::ErrorKind::TimedOut
+                            | io::ErrorKind::Interrupted
+                            | io::ErrorKind::ConnectionReset
+                    ) =>
+                {
+                    continue
+                }
+                Err(e) => {
+                    println!("[backend] Stopped receiving packets: {e}");
+                    break;
+                }
+            };
+
+            let initialized =
+                unsafe { std::slice::from_raw_parts(buf.as_ptr() as *const u8, bytes_received) };
+            relay.forward(initialized);
+
+            let datagram = ReceivedDatagram::decode(initialized.to_vec());
+
+            counters.received.fetch_add(1, Ordering::Relaxed);
+            if datagram.packet.is_some() {
+                counters.decoded.fetch_add(1, Ordering::Relaxed);
+            } else {
+                counters.undecodable.fetch_add(1, Ordering::Relaxed);
+            }
+
+            match sender.try_send(datagram) {
+                Ok(()) => {}
+                Err(TrySendError::Full(_)) => {
+                    counters.dropped.fetch_add(1, Ordering::Relaxed);
+                }
+                Err(TrySendError::Disconnected(_)) => break,
+            }
+        }
+    }
+
+    pub fn stats(&self) -> IngestStats {
+        IngestStats {
+            received: self.counters.received.load(Ordering::Relaxed),
+            decoded: self.counters.decoded.load(Ordering::Relaxed),
+            undecodable: self.counters.undecodable.load(Ordering::Relaxed),
+            dropped: self.counters.dropped.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Stop receiving and wait for the thread to finish.
+    ///
+    /// Datagrams already queued can still be read from the channel.
+    pub fn stop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+impl Drop for PacketReceiver {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
@@ -0,0 +1,146 @@
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use crate::core::{RaceEvent, RaceSnapshot, TelemetryPacket};
+
+/// How long without any packet before the player is
+/// assumed to have gone back to the menus
+pub const SESSION_IDLE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Max number of finished sessions kept by a [`Session`](crate::core::Session)
+pub const MAX_ARCHIVED_SESSIONS: usize = 16;
+
+/// Why a session was considered finished
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SessionEndReason {
+    /// The game sent a Session Ended event
+    Ended,
+
+    /// Packets from a different session arrived
+    /// before the previous session ended
+    Replaced,
+
+    /// The game sent packets without a session, or stopped
+    /// sending packets for [`SESSION_IDLE_TIMEOUT`]
+    ReturnedToMenu,
+}
+
+/// A session boundary, detected from the session uid of
+/// every packet and the Session Started/Ended events.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LifecycleEvent {
+    /// Packets for a new session arrived, or the game
+    /// sent Session Started after the session ended
+    SessionStarted { session_uid: u64 },
+    SessionEnded {
+        session_uid: u64,
+        reason: SessionEndReason,
+    },
+
+    /// Packets arrived again for a session that ended because the
+    /// game went quiet, e.g. the player left and came back.
+    /// The session's state is kept.
+    SessionResumed { session_uid: u64 },
+}
+
+/// The state of a session when it ended
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ArchivedSession {
+    pub session_uid: u64,
+    pub reason: SessionEndReason,
+    pub state: RaceSnapshot,
+}
+
+/// Tracks which session packets are being received for.
+#[derive(Debug, Default)]
+pub struct SessionLifecycle {
+    /// Session being received, None between sessions
+    current: Option<u64>,
+
+    /// The last session that ended, and why
+    ended: Option<(u64, SessionEndReason)>,
+    last_packet: Option<Instant>,
+}
+
+impl SessionLifecycle {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Uid of the session being received, if any
+    pub fn current(&self) -> Option<u64> {
+        self.current
+    }
+
+    /// Check a packet for session boundaries.
+    ///
+    /// Returns the boundaries crossed, in order, e.g. the end of the
+    /// previous session and then the start of the packet's session.
+    pub fn ingest(
+        &mut self,
+        packet: &TelemetryPacket,
+        received_at: Instant,
+    ) -> Vec<LifecycleEvent> {
+        self.last_packet = Some(received_at);
+
+        let session_uid = packet.header().session_uid;
+        let event = packet.as_event().map(|event| event.event);
+        let mut events = Vec::new();
+
+        // Packets sent from the menus aren't part of a session
+        if session_uid == 0 {
+            events.extend(self.end(SessionEndReason::ReturnedToMenu));
+            return events;
+        }
+
+        if self.current.is_some_and(|current| current != session_uid) {
+            events.extend(self.end(SessionEndReason::Replaced));
+        }
+
+        if self.current.is_none() {
+            match self.ended {
+                // Only Session Started restarts a session the game ended
+                Some((ended, SessionEndReason::Ended))
+                    if ended == session_uid && event != Some(RaceEvent::SessionStarted) => {}
+                Some((ended, SessionEndReason::ReturnedToMenu)) if ended == session_uid => {
+                    self.current = Some(session_uid);
+                    self.ended = None;
+                    events.push(LifecycleEvent::SessionResumed { session_uid });
+                }
+                _ => {
+                    self.current = Some(session_uid);
+                    self.ended = None;
+                    events.push(LifecycleEvent::SessionStarted { session_uid });
+                }
+            }
+        }
+
+        if event == Some(RaceEvent::SessionEnded) {
+            events.extend(self.end(SessionEndReason::Ended));
+        }
+
+        events
+    }
+
+    /// End the current session if no packet was received for
+    /// [`SESSION_IDLE_TIMEOUT`]. Should be called regularly while
+    /// no packets are arriving.
+    pub fn check_idle(&mut self, now: Instant) -> Option<LifecycleEvent> {
+        let last_packet = self.last_packet?;
+        if now.saturating_duration_since(last_packet) < SESSION_IDLE_TIMEOUT {
+            return None;
+        }
+
+        self.end(SessionEndReason::ReturnedToMenu)
+    }
+
+    fn end(&mut self, reason: SessionEndReason) -> Option<LifecycleEvent> {
+        let session_uid = self.current.take()?;
+        self.ended = Some((session_uid, reason));
+        Some(LifecycleEvent::SessionEnded {
+            session_uid,
+            reason,
+        })
+    }
+}
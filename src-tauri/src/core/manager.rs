@@ -0,0 +1,203 @@
+use std::{
+    collections::BTreeMap,
+    io,
+    net::SocketAddr,
+    path::PathBuf,
+    sync::{mpsc::RecvTimeoutError, Arc, Mutex},
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::core::{IngestStats, RelayStats, RelayTarget, Session, DEFAULT_QUEUE_LEN};
+
+/// Id of the listener used when only one is needed
+pub const DEFAULT_LISTENER_ID: &str = "default";
+
+/// How often a listener checks if the game went quiet
+/// when no packets are arriving, see [`Session::check_idle`]
+const IDLE_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Description of a running listener, for the UI and CLI
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ListenerInfo {
+    pub id: String,
+    pub address: String,
+    pub port: String,
+    pub connected: bool,
+
+    /// Session of the last packet received, 0 if none yet
+    pub session_uid: u64,
+
+    /// Number of finished sessions archived
+    pub archived_sessions: usize,
+
+    /// File being recorded to, if recording
+    pub recording: Option<PathBuf>,
+    pub ingest: IngestStats,
+    pub relay: RelayStats,
+    pub relay_targets: Vec<RelayTarget>,
+}
+
+#[derive(Debug)]
+struct Listener {
+    session: Arc<Mutex<Session>>,
+
+    /// Applies every datagram received to the session
+    thread: Option<JoinHandle<()>>,
+}
+
+impl Listener {
+    fn stop(&mut self) {
+        // Dropping the connection stops the receiver, which closes
+        // the queue and ends the thread. The session must not be
+        // locked while waiting for the thread.
+        self.session.lock().unwrap().drop_connection();
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// Owns every UDP listener, e.g. one per sim rig, each with its own
+/// [`Session`], race state and statistics, addressed by a unique id.
+#[derive(Debug, Default)]
+pub struct SessionManager {
+    listeners: BTreeMap<String, Listener>,
+}
+
+impl SessionManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start listening for packets on `address`:`port` as `id`.
+    ///
+    /// Every datagram received is applied to the listener's session on a
+    /// dedicated thread; subscribe to the session's bus to consume them.
+    /// Fails if a listener with the same id is running or the socket
+    /// can't be bound.
+    pub fn start(
+        &mut self,
+        id: impl Into<String>,
+        address: String,
+        port: String,
+    ) -> io::Result<Arc<Mutex<Session>>> {
+        let id = id.into();
+        if self.listeners.contains_key(&id) {
+            return Err(io::Error::new(
+                io::ErrorKind::AlreadyExists,
+                format!("listener '{id}' is already running"),
+            ));
+        }
+
+        // Connection::new expects an address it can parse
+        if format!("{address}:{port}").parse::<SocketAddr>().is_err() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("invalid address {address}:{port}"),
+            ));
+        }
+
+        let session = Session::new(address.clone(), port.clone());
+        if !session.lock().unwrap().connected() {
+            return Err(io::Error::other(format!(
+                "could not listen on {address}:{port}"
+            )));
+        }
+
+        let datagrams = session.lock().unwrap().start_receiver(DEFAULT_QUEUE_LEN)?;
+        let ingest_session = Arc::clone(&session);
+        let thread = thread::spawn(move || loop {
+            match datagrams.recv_timeout(IDLE_CHECK_INTERVAL) {
+                Ok(datagram) => ingest_session.lock().unwrap().ingest(&datagram),
+                Err(RecvTimeoutError::Timeout) => ingest_session.lock().unwrap().check_idle(),
+                Err(RecvTimeoutError::Disconnected) => break,
+            }
+        });
+
+        println!("[backend] Started listener '{id}' on {address}:{port}");
+        self.listeners.insert(
+            id,
+            Listener {
+                session: Arc::clone(&session),
+                thread: Some(thread),
+            },
+        );
+
+        Ok(session)
+    }
+
+    /// Stop the listener `id`, finishing any recording.
+    ///
+    /// Returns false if there was no such listener.
+    pub fn stop(&mut self, id: &str) -> bool {
+        let Some(mut listener) = self.listeners.remove(id) else {
+            return false;
+        };
+
+        listener.stop();
+        println!("[backend] Stopped listener '{id}'");
+        true
+    }
+
+    pub fn stop_all(&mut self) {
+        let ids: Vec<String> = self.listeners.keys().cloned().collect();
+        for id in ids {
+            self.stop(&id);
+        }
+    }
+
+    pub fn session(&self, id: &str) -> Option<Arc<Mutex<Session>>> {
+        self.listeners
+            .get(id)
+            .map(|listener| Arc::clone(&listener.session))
+    }
+
+    /// Ids of every listener, in order
+    pub fn ids(&self) -> impl Iterator<Item = &str> {
+        self.listeners.keys().map(String::as_str)
+    }
+
+    pub fn len(&self) -> usize {
+        self.listeners.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.listeners.is_empty()
+    }
+
+    pub fn info(&self, id: &str) -> Option<ListenerInfo> {
+        let listener = self.listeners.get(id)?;
+        let session = listener.session.lock().unwrap();
+
+        Some(ListenerInfo {
+            id: id.to_string(),
+            address: session.connection.address.clone(),
+            port: session.connection.port.clone(),
+            connected: session.connected(),
+            session_uid: session.race_state.snapshot().session_uid,
+            archived_sessions: session.archive.len(),
+            recording: session
+                .recorder
+                .as_ref()
+                .and_then(|recorder| recorder.current_path())
+                .map(PathBuf::from),
+            ingest: session.ingest_stats().unwrap_or_default(),
+            relay: session.relay.stats(),
+            relay_targets: session.relay.targets(),
+        })
+    }
+
+    /// Every listener, in order of id
+    pub fn list(&self) -> Vec<ListenerInfo> {
+        self.ids().filter_map(|id| self.info(id)).collect()
+    }
+}
+
+impl Drop for SessionManager {
+    fn drop(&mut self) {
+        self.stop_all();
+    }
+}
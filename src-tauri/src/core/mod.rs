@@ -1,10 +1,40 @@
+mod bus;
 mod cm_events;
 mod decoder;
+mod detector;
+mod ers;
+mod fuel;
+mod history;
+mod ingest;
+mod lifecycle;
+mod manager;
+mod model;
 mod packets;
+mod participants;
+mod race_state;
+mod relay;
 mod session;
+mod timing;
+mod tyres;
+mod weather;
 
 pub mod ids;
 
+pub use bus::*;
 pub use decoder::*;
+pub use detector::*;
+pub use ers::*;
+pub use fuel::*;
+pub use history::*;
+pub use ingest::*;
+pub use lifecycle::*;
+pub use manager::*;
+pub use model::*;
 pub use packets::*;
+pub use participants::*;
+pub use race_state::*;
+pub use relay::*;
 pub use session::*;
+pub use timing::*;
+pub use tyres::*;
+pub use weather::*;
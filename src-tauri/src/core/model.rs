@@ -0,0 +1,1184 @@
+/// Season agnostic telemetry model.
+///
+/// The packet structs in [`packets`](crate::core::TelemetryPacket) mirror
+/// the CodeMasters wire layout of every supported season. The types in this
+/// module are built from those packets and are what the rest of Solis
+/// (bridge, strategy, storage) should program against, so they do not need
+/// to know which game sent the data or how it was laid out.
+///
+/// Raw codes are converted to enums, times to [`Duration`] and every other
+/// measured value carries its unit in the field name (e.g. `speed_kph`).
+///
+/// Note: All wheel arrays have the following order:
+/// Index - Name
+///   0   - Rear Left (RL)
+///   1   - Rear Right (RR)
+///   2   - Front Left (FL)
+///   3   - Front Right (FR)
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::core::{
+    ids::{
+        ButtonFlag, DriverId, FormulaType, GameModeId, InfringementType, NationalityId,
+        PenaltyType, RulesetId, SessionLength, SessionType, SurfaceType, TeamId, TrackId,
+        WeatherType,
+    },
+    Buttons, CarDamageData, CarMotionData, CarStatusData, CarTelemetryData, LapData,
+    LapHistoryData, LobbyInfoData, PacketCarDamageData, PacketCarStatusData,
+    PacketCarTelemetryData, PacketLapData, PacketLobbyInfoData, PacketMotionData,
+    PacketParticipantsData, PacketSessionData, PacketSessionHistoryData, PacketTyreSetsData,
+    ParticipantData, TelemetryPacket, TyreSetData, WeatherForecastSample,
+};
+
+/// Define an enum for a raw code sent in a packet.
+///
+/// Codes without a matching variant become `Unknown`
+/// instead of failing, as these are informational values.
+macro_rules! raw_enum {
+    (
+        $(#[$meta:meta])*
+        pub enum $name:ident: $repr:ty {
+            $($variant:ident = $value:literal,)*
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+        pub enum $name {
+            $($variant,)*
+
+            #[default]
+            Unknown,
+        }
+
+        impl From<$repr> for $name {
+            fn from(raw: $repr) -> Self {
+                match raw {
+                    $($value => $name::$variant,)*
+                    _ => $name::Unknown,
+                }
+            }
+        }
+    };
+}
+
+raw_enum! {
+    /// Actual tyre compound fitted to a car
+    pub enum TyreCompound: u8 {
+        Inter = 7,
+        Wet = 8,
+        ClassicDry = 9,
+        ClassicWet = 10,
+        F2SuperSoft = 11,
+        F2Soft = 12,
+        F2Medium = 13,
+        F2Hard = 14,
+        F2Wet = 15,
+        C5 = 16,
+        C4 = 17,
+        C3 = 18,
+        C2 = 19,
+        C1 = 20,
+        C0 = 21,
+        C6 = 22,
+    }
+}
+
+raw_enum! {
+    /// Visual tyre compound, as shown on the car and broadcast graphics.
+    /// Can differ from the [`TyreCompound`] actually fitted.
+    pub enum VisualTyreCompound: u8 {
+        Inter = 7,
+        Wet = 8,
+        ClassicDry = 9,
+        ClassicWet = 10,
+        F2Wet = 15,
+        Soft = 16,
+        Medium = 17,
+        Hard = 18,
+        F2SuperSoft = 19,
+        F2Soft = 20,
+        F2Medium = 21,
+        F2Hard = 22,
+    }
+}
+
+raw_enum! {
+    pub enum PitStatus: u8 {
+        None = 0,
+        Pitting = 1,
+        InPitArea = 2,
+    }
+}
+
+raw_enum! {
+    pub enum DriverStatus: u8 {
+        InGarage = 0,
+        FlyingLap = 1,
+        InLap = 2,
+        OutLap = 3,
+        OnTrack = 4,
+    }
+}
+
+raw_enum! {
+    pub enum ResultStatus: u8 {
+        Invalid = 0,
+        Inactive = 1,
+        Active = 2,
+        Finished = 3,
+        DidNotFinish = 4,
+        Disqualified = 5,
+        NotClassified = 6,
+        Retired = 7,
+    }
+}
+
+raw_enum! {
+    /// Flag shown to a car or in a marshal zone
+    pub enum FiaFlag: i8 {
+        None = 0,
+        Green = 1,
+        Blue = 2,
+        Yellow = 3,
+        Red = 4,
+    }
+}
+
+raw_enum! {
+    pub enum SafetyCarStatus: u8 {
+        None = 0,
+        Full = 1,
+        Virtual = 2,
+        FormationLap = 3,
+    }
+}
+
+raw_enum! {
+    /// Phase of a safety car period, from the Safety Car event
+    pub enum SafetyCarPhase: u8 {
+        Deployed = 0,
+        Returning = 1,
+        Returned = 2,
+        ResumeRace = 3,
+    }
+}
+
+raw_enum! {
+    pub enum ErsDeployMode: u8 {
+        None = 0,
+        Medium = 1,
+        Hotlap = 2,
+        Overtake = 3,
+    }
+}
+
+raw_enum! {
+    pub enum FuelMix: u8 {
+        Lean = 0,
+        Standard = 1,
+        Rich = 2,
+        Max = 3,
+    }
+}
+
+raw_enum! {
+    pub enum TractionControl: u8 {
+        Off = 0,
+        Medium = 1,
+        Full = 2,
+    }
+}
+
+raw_enum! {
+    pub enum TemperatureChange: i8 {
+        Up = 0,
+        Down = 1,
+        NoChange = 2,
+    }
+}
+
+raw_enum! {
+    pub enum Platform: u8 {
+        Steam = 1,
+        PlayStation = 3,
+        Xbox = 4,
+        Origin = 6,
+    }
+}
+
+raw_enum! {
+    /// Whether a player in a multiplayer lobby is ready to start
+    pub enum ReadyStatus: u8 {
+        NotReady = 0,
+        Ready = 1,
+        Spectating = 2,
+    }
+}
+
+/// A three dimensional vector in world or local space
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq)]
+pub struct Vector3 {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+}
+
+impl Vector3 {
+    pub fn new(x: f32, y: f32, z: f32) -> Self {
+        Self { x, y, z }
+    }
+}
+
+/// Convert a split minutes and milliseconds time to a Duration.
+///
+/// Older formats do not send the minutes part, so it is 0.
+fn split_time(minutes: u8, millis: u16) -> Duration {
+    Duration::from_secs(minutes as u64 * 60) + Duration::from_millis(millis as u64)
+}
+
+/// The person (or AI) driving a car.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Driver {
+    pub vehicle_idx: u8,
+    pub name: String,
+    pub driver_id: DriverId,
+
+    /// Unique identifier of a human player in a network game
+    pub network_id: u8,
+    pub team_id: TeamId,
+    pub nationality: NationalityId,
+    pub race_number: u8,
+    pub ai_controlled: bool,
+    pub my_team: bool,
+
+    /// Whether the player's UDP telemetry setting is public.
+    /// When false, most car data for this driver is zeroed by the game.
+    pub public_telemetry: bool,
+    pub platform: Platform,
+}
+
+impl Driver {
+    pub fn from_participant(vehicle_idx: u8, participant: &ParticipantData) -> Self {
+        Self {
+            vehicle_idx,
+            name: participant.get_player_name(),
+            driver_id: participant.driver_id,
+            network_id: participant.network_id,
+            team_id: participant.team_id,
+            nationality: participant.nationality,
+            race_number: participant.race_number,
+            ai_controlled: participant.ai_controlled == 1,
+            my_team: participant.my_team == 1,
+            public_telemetry: participant.your_telemetry == 1,
+            platform: participant.platform.into(),
+        }
+    }
+}
+
+/// Lap and race progress of a car.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Lap {
+    pub last_lap_time: Duration,
+    pub current_lap_time: Duration,
+    pub sector1_time: Duration,
+    pub sector2_time: Duration,
+    pub delta_to_car_in_front: Duration,
+    pub delta_to_race_leader: Duration,
+
+    /// Distance around the current lap, negative
+    /// if the line hasn't been crossed yet
+    pub lap_distance_m: f32,
+    pub total_distance_m: f32,
+    pub safety_car_delta_s: f32,
+
+    pub position: u8,
+    pub lap_number: u8,
+
+    /// Current sector, 0 indexed
+    pub sector: u8,
+    pub lap_invalid: bool,
+    pub penalties_s: u8,
+    pub warnings: u8,
+    pub corner_cutting_warnings: u8,
+    pub unserved_drive_through_pens: u8,
+    pub unserved_stop_go_pens: u8,
+    pub grid_position: u8,
+
+    pub pit_status: PitStatus,
+    pub num_pit_stops: u8,
+    pub pit_lane_time: Option<Duration>,
+    pub pit_stop_time: Duration,
+    pub pit_stop_should_serve_pen: bool,
+
+    pub driver_status: DriverStatus,
+    pub result_status: ResultStatus,
+
+    pub speed_trap_fastest_speed_kph: f32,
+
+    /// Lap the fastest speed trap speed was set on, None if not set
+    pub speed_trap_fastest_lap: Option<u8>,
+}
+
+impl From<&LapData> for Lap {
+    fn from(lap: &LapData) -> Self {
+        Self {
+            last_lap_time: Duration::from_millis(lap.last_lap_time_in_ms as u64),
+            current_lap_time: Duration::from_millis(lap.current_lap_time_in_ms as u64),
+            sector1_time: split_time(lap.sector1_time_minutes, lap.sector1_time_in_ms),
+            sector2_time: split_time(lap.sector2_time_minutes, lap.sector2_time_in_ms),
+            delta_to_car_in_front: split_time(
+                lap.delta_to_car_in_front_minutes,
+                lap.delta_to_car_in_front_in_ms,
+            ),
+            delta_to_race_leader: split_time(
+                lap.delta_to_race_leader_minutes,
+                lap.delta_to_race_leader_in_ms,
+            ),
+            lap_distance_m: lap.lap_distance,
+            total_distance_m: lap.total_distance,
+            safety_car_delta_s: lap.safety_car_delta,
+            position: lap.car_position,
+            lap_number: lap.current_lap_num,
+            sector: lap.sector,
+            lap_invalid: lap.current_lap_invalid == 1,
+            penalties_s: lap.penalties,
+            warnings: lap.warnings,
+            corner_cutting_warnings: lap.corner_cutting_warnings,
+            unserved_drive_through_pens: lap.num_unserved_drive_through_pens,
+            unserved_stop_go_pens: lap.num_unserved_stop_go_pens,
+            grid_position: lap.grid_position,
+            pit_status: lap.pit_status.into(),
+            num_pit_stops: lap.num_pit_stops,
+            pit_lane_time: (lap.pit_lane_timer_active == 1)
+                .then(|| Duration::from_millis(lap.pit_lane_time_in_lane_in_ms as u64)),
+            pit_stop_time: Duration::from_millis(lap.pit_stop_timer_in_ms as u64),
+            pit_stop_should_serve_pen: lap.pit_stop_should_serve_pen == 1,
+            driver_status: lap.driver_status.into(),
+            result_status: lap.result_status.into(),
+            speed_trap_fastest_speed_kph: lap.speed_trap_fastest_speed,
+            speed_trap_fastest_lap: match lap.speed_trap_fastest_lap {
+                0 | 255 => None,
+                lap => Some(lap),
+            },
+        }
+    }
+}
+
+/// Live inputs and sensor readings of a car.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CarTelemetry {
+    pub speed_kph: u16,
+
+    /// 0.0 to 1.0
+    pub throttle: f32,
+
+    /// -1.0 (full lock left) to 1.0 (full lock right)
+    pub steer: f32,
+
+    /// 0.0 to 1.0
+    pub brake: f32,
+
+    /// 0 to 100
+    pub clutch_percent: u8,
+
+    /// 1-8, 0 = neutral, -1 = reverse
+    pub gear: i8,
+    pub engine_rpm: u16,
+    pub drs_open: bool,
+    pub rev_lights_percent: u8,
+    pub brakes_temperature_c: [u16; 4],
+    pub tyres_surface_temperature_c: [u8; 4],
+    pub tyres_inner_temperature_c: [u8; 4],
+    pub engine_temperature_c: u16,
+    pub tyres_pressure_psi: [f32; 4],
+    pub surface_type: [SurfaceType; 4],
+}
+
+impl From<&CarTelemetryData> for CarTelemetry {
+    fn from(telemetry: &CarTelemetryData) -> Self {
+        Self {
+            speed_kph: telemetry.speed,
+            throttle: telemetry.throttle,
+            steer: telemetry.steer,
+            brake: telemetry.brake,
+            clutch_percent: telemetry.clutch,
+            gear: telemetry.gear,
+            engine_rpm: telemetry.engine_rpm,
+            drs_open: telemetry.drs == 1,
+            rev_lights_percent: telemetry.rev_lights_percent,
+            brakes_temperature_c: telemetry.brakes_temperature,
+            tyres_surface_temperature_c: telemetry.tyres_surface_temperature,
+            tyres_inner_temperature_c: telemetry.tyres_inner_temperature,
+            engine_temperature_c: telemetry.engine_temperature,
+            tyres_pressure_psi: telemetry.tyres_pressure,
+            surface_type: telemetry.surface_type,
+        }
+    }
+}
+
+/// A completed lap of a car, with its sector times.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct LapTime {
+    /// Lap number, starting at 1
+    pub lap: u8,
+    pub time: Duration,
+    pub sectors: [Duration; 3],
+    pub valid: bool,
+
+    /// Whether each sector was valid, a lap with
+    /// an invalid sector can still have valid ones
+    pub sectors_valid: [bool; 3],
+}
+
+impl LapTime {
+    fn from_history(lap: u8, history: &LapHistoryData) -> Self {
+        let flags = history.lap_valid_bit_flags;
+        Self {
+            lap,
+            time: Duration::from_millis(history.lap_time_in_ms as u64),
+            sectors: [
+                split_time(history.sector1_time_minutes, history.sector1_time_in_ms),
+                split_time(history.sector2_time_minutes, history.sector2_time_in_ms),
+                split_time(history.sector3_time_minutes, history.sector3_time_in_ms),
+            ],
+            valid: flags & 0x01 != 0,
+            sectors_valid: [flags & 0x02 != 0, flags & 0x04 != 0, flags & 0x08 != 0],
+        }
+    }
+}
+
+/// A set of tyres used by a car for a run of laps.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct TyreStint {
+    pub compound: TyreCompound,
+    pub visual_compound: VisualTyreCompound,
+    pub start_lap: u8,
+
+    /// Last lap on this set, None for the set currently fitted
+    pub end_lap: Option<u8>,
+}
+
+impl TyreStint {
+    /// Whether the set was used on `lap`
+    pub fn covers(&self, lap: u8) -> bool {
+        self.start_lap <= lap && self.end_lap.is_none_or(|end_lap| lap <= end_lap)
+    }
+}
+
+/// A set of tyres, either fitted to a car or allocated to it for the session.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TyreSet {
+    pub compound: TyreCompound,
+    pub visual_compound: VisualTyreCompound,
+
+    /// Laps driven on this set. Only known for the fitted set.
+    pub age_laps: Option<u8>,
+
+    /// Only known for sets from the Tyre Sets packet
+    pub wear_percent: Option<u8>,
+
+    /// Laps of life left in this set, see `wear_percent`
+    pub laps_left: Option<u8>,
+
+    /// Max number of laps recommended for this compound, see `wear_percent`
+    pub usable_life_laps: Option<u8>,
+
+    /// Lap time difference compared to the fitted set, see `wear_percent`
+    pub lap_delta_s: Option<f32>,
+
+    pub available: bool,
+    pub fitted: bool,
+}
+
+impl TyreSet {
+    /// The set currently fitted to a car, from the Car Status packet.
+    pub(crate) fn fitted(status: &CarStatusData) -> Self {
+        Self {
+            compound: status.actual_tyre_compound.into(),
+            visual_compound: status.visual_tyre_compound.into(),
+            age_laps: Some(status.tyres_age_laps),
+            wear_percent: None,
+            laps_left: None,
+            usable_life_laps: None,
+            lap_delta_s: None,
+            available: true,
+            fitted: true,
+        }
+    }
+}
+
+impl From<&TyreSetData> for TyreSet {
+    fn from(set: &TyreSetData) -> Self {
+        Self {
+            compound: set.actual_tyre_compound.into(),
+            visual_compound: set.visual_tyre_compound.into(),
+            age_laps: None,
+            wear_percent: Some(set.wear),
+            laps_left: Some(set.life_span),
+            usable_life_laps: Some(set.usable_life),
+            lap_delta_s: Some(set.lap_delta_time as f32 / 1000.0),
+            available: set.available == 1,
+            fitted: set.fitted == 1,
+        }
+    }
+}
+
+/// Car settings, fuel, tyres and energy store of a car.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CarStatus {
+    pub traction_control: TractionControl,
+    pub anti_lock_brakes: bool,
+    pub fuel_mix: FuelMix,
+    pub front_brake_bias_percent: u8,
+    pub pit_limiter: bool,
+    pub fuel_in_tank_kg: f32,
+    pub fuel_capacity_kg: f32,
+
+    /// Fuel remaining in terms of laps, as shown on the MFD
+    pub fuel_remaining_laps: f32,
+    pub max_rpm: u16,
+    pub idle_rpm: u16,
+    pub max_gears: u8,
+    pub drs_allowed: bool,
+
+    /// Distance until DRS will be available, None if DRS is not coming up
+    pub drs_activation_distance_m: Option<u16>,
+    pub tyres: TyreSet,
+    pub fia_flag: FiaFlag,
+    pub engine_power_ice_w: f32,
+    pub engine_power_mguk_w: f32,
+    pub ers_store_energy_j: f32,
+    pub ers_deploy_mode: ErsDeployMode,
+    pub ers_harvested_this_lap_mguk_j: f32,
+    pub ers_harvested_this_lap_mguh_j: f32,
+    pub ers_deployed_this_lap_j: f32,
+    pub network_paused: bool,
+}
+
+impl From<&CarStatusData> for CarStatus {
+    fn from(status: &CarStatusData) -> Self {
+        Self {
+            traction_control: status.traction_control.into(),
+            anti_lock_brakes: status.anti_lock_brakes == 1,
+            fuel_mix: status.fuel_mix.into(),
+            front_brake_bias_percent: status.front_brake_bias,
+            pit_limiter: status.pit_limiter_status == 1,
+            fuel_in_tank_kg: status.fuel_in_tank,
+            fuel_capacity_kg: status.fuel_capacity,
+            fuel_remaining_laps: status.fuel_remaining_laps,
+            max_rpm: status.max_rpm,
+            idle_rpm: status.idle_rpm,
+            max_gears: status.max_gears,
+            drs_allowed: status.drs_allowed == 1,
+            drs_activation_distance_m: match status.drs_activation_distance {
+                0 => None,
+                distance => Some(distance),
+            },
+            tyres: TyreSet::fitted(status),
+            fia_flag: status.vehicle_fia_flags.into(),
+            engine_power_ice_w: status.engine_power_ice,
+            engine_power_mguk_w: status.engine_power_mguk,
+            ers_store_energy_j: status.ers_store_energy,
+            ers_deploy_mode: status.ers_deploy_mode.into(),
+            ers_harvested_this_lap_mguk_j: status.ers_harvested_this_lap_mguk,
+            ers_harvested_this_lap_mguh_j: status.ers_harvested_this_lap_mguh,
+            ers_deployed_this_lap_j: status.ers_deployed_this_lap,
+            network_paused: status.network_paused == 1,
+        }
+    }
+}
+
+/// Wear and damage of a car. All values are percentages.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CarDamage {
+    pub tyres_wear: [f32; 4],
+    pub tyres_damage: [u8; 4],
+    pub tyres_blisters: [u8; 4],
+    pub brakes_damage: [u8; 4],
+    pub front_left_wing: u8,
+    pub front_right_wing: u8,
+    pub rear_wing: u8,
+    pub floor: u8,
+    pub diffuser: u8,
+    pub sidepod: u8,
+    pub drs_fault: bool,
+    pub ers_fault: bool,
+    pub gear_box: u8,
+    pub engine: u8,
+    pub engine_mguh_wear: u8,
+    pub engine_es_wear: u8,
+    pub engine_ce_wear: u8,
+    pub engine_ice_wear: u8,
+    pub engine_mguk_wear: u8,
+    pub engine_tc_wear: u8,
+    pub engine_blown: bool,
+    pub engine_seized: bool,
+}
+
+impl From<&CarDamageData> for CarDamage {
+    fn from(damage: &CarDamageData) -> Self {
+        Self {
+            tyres_wear: damage.tyres_wear,
+            tyres_damage: damage.tyres_damage,
+            tyres_blisters: damage.tyre_blisters,
+            brakes_damage: damage.brakes_damage,
+            front_left_wing: damage.front_left_wing_damage,
+            front_right_wing: damage.front_right_wing_damage,
+            rear_wing: damage.rear_wing_damage,
+            floor: damage.floor_damage,
+            diffuser: damage.diffuser_damage,
+            sidepod: damage.sidepod_damage,
+            drs_fault: damage.drs_fault == 1,
+            ers_fault: damage.ers_fault == 1,
+            gear_box: damage.gear_box_damage,
+            engine: damage.engine_damage,
+            engine_mguh_wear: damage.engine_mguh_wear,
+            engine_es_wear: damage.engine_es_wear,
+            engine_ce_wear: damage.engine_ce_wear,
+            engine_ice_wear: damage.engine_ice_wear,
+            engine_mguk_wear: damage.engine_mguk_wear,
+            engine_tc_wear: damage.engine_tc_wear,
+            engine_blown: damage.engine_blown == 1,
+            engine_seized: damage.engine_seized == 1,
+        }
+    }
+}
+
+/// Position and orientation of a car in world space.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CarMotion {
+    pub position_m: Vector3,
+    pub velocity_mps: Vector3,
+
+    /// Normalised forward direction
+    pub forward: Vector3,
+
+    /// Normalised right direction
+    pub right: Vector3,
+    pub g_force_lateral: f32,
+    pub g_force_longitudinal: f32,
+    pub g_force_vertical: f32,
+    pub yaw_rad: f32,
+    pub pitch_rad: f32,
+    pub roll_rad: f32,
+}
+
+impl From<&CarMotionData> for CarMotion {
+    fn from(motion: &CarMotionData) -> Self {
+        // Directions are sent as i16 normalised to -32767..32767
+        let normalise = |value: i16| value as f32 / 32767.0;
+
+        Self {
+            position_m: Vector3::new(
+                motion.world_position_x,
+                motion.world_position_y,
+                motion.world_position_z,
+            ),
+            velocity_mps: Vector3::new(
+                motion.world_velocity_x,
+                motion.world_velocity_y,
+                motion.world_velocity_z,
+            ),
+            forward: Vector3::new(
+                normalise(motion.world_forward_dir_x),
+                normalise(motion.world_forward_dir_y),
+                normalise(motion.world_forward_dir_z),
+            ),
+            right: Vector3::new(
+                normalise(motion.world_right_dir_x),
+                normalise(motion.world_right_dir_y),
+                normalise(motion.world_right_dir_z),
+            ),
+            g_force_lateral: motion.g_force_lateral,
+            g_force_longitudinal: motion.g_force_longitudinal,
+            g_force_vertical: motion.g_force_vertical,
+            yaw_rad: motion.yaw,
+            pitch_rad: motion.pitch,
+            roll_rad: motion.roll,
+        }
+    }
+}
+
+/// Everything known about a single car, built up from the
+/// per-car packets as they arrive.
+///
+/// A part is None until a packet containing it has been received.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct Car {
+    pub vehicle_idx: u8,
+    pub driver: Option<Driver>,
+    pub lap: Option<Lap>,
+    pub telemetry: Option<CarTelemetry>,
+    pub status: Option<CarStatus>,
+    pub damage: Option<CarDamage>,
+    pub motion: Option<CarMotion>,
+
+    /// Tyre sets allocated to this car, from the Tyre Sets packet (F1 23+)
+    pub tyre_sets: Vec<TyreSet>,
+}
+
+impl Car {
+    pub fn new(vehicle_idx: u8) -> Self {
+        Self {
+            vehicle_idx,
+            ..Default::default()
+        }
+    }
+
+    /// Update the parts of this car contained in `packet`.
+    ///
+    /// Packets that do not contain data for this car are ignored.
+    /// Returns true if anything was updated.
+    pub fn update(&mut self, packet: &TelemetryPacket) -> bool {
+        let idx = self.vehicle_idx;
+        match packet {
+            TelemetryPacket::Participants(p) => {
+                self.driver = p.driver(idx);
+                self.driver.is_some()
+            }
+            TelemetryPacket::LapData(p) => replace(&mut self.lap, p.lap(idx)),
+            TelemetryPacket::CarTelemetry(p) => replace(&mut self.telemetry, p.telemetry(idx)),
+            TelemetryPacket::CarStatus(p) => replace(&mut self.status, p.status(idx)),
+            TelemetryPacket::CarDamage(p) => replace(&mut self.damage, p.damage(idx)),
+            TelemetryPacket::Motion(p) => replace(&mut self.motion, p.motion(idx)),
+            TelemetryPacket::TyreSets(p) if p.car_idx == idx => {
+                self.tyre_sets = p.tyre_sets();
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Replace `part` if `value` is Some, returning whether it was replaced.
+fn replace<T>(part: &mut Option<T>, value: Option<T>) -> bool {
+    let updated = value.is_some();
+    if updated {
+        *part = value;
+    }
+
+    updated
+}
+
+/// A single weather forecast sample for a session
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct WeatherForecast {
+    pub session_type: SessionType,
+
+    /// How far into the session the forecast is for
+    pub time_offset: Duration,
+    pub weather: WeatherType,
+    pub track_temperature_c: i8,
+    pub track_temperature_change: TemperatureChange,
+    pub air_temperature_c: i8,
+    pub air_temperature_change: TemperatureChange,
+    pub rain_percentage: u8,
+}
+
+impl From<&WeatherForecastSample> for WeatherForecast {
+    fn from(sample: &WeatherForecastSample) -> Self {
+        Self {
+            session_type: sample.session_type,
+            time_offset: Duration::from_secs(sample.time_offset as u64 * 60),
+            weather: sample.weather,
+            track_temperature_c: sample.track_temperature,
+            track_temperature_change: sample.track_temperature_change.into(),
+            air_temperature_c: sample.air_temperature,
+            air_temperature_change: sample.air_temperature_change.into(),
+            rain_percentage: sample.rain_percentage,
+        }
+    }
+}
+
+/// Current weather and forecast for a session.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Weather {
+    pub weather: WeatherType,
+    pub track_temperature_c: i8,
+    pub air_temperature_c: i8,
+
+    /// Whether the forecast is perfect or approximate
+    pub forecast_accurate: bool,
+    pub forecast: Vec<WeatherForecast>,
+}
+
+impl PacketParticipantsData {
+    /// The driver of the car at `vehicle_idx`, if that car is active
+    pub fn driver(&self, vehicle_idx: u8) -> Option<Driver> {
+        if vehicle_idx >= self.num_active_cars {
+            return None;
+        }
+
+        self.get_participant(vehicle_idx)
+            .map(|participant| Driver::from_participant(vehicle_idx, participant))
+    }
+
+    /// Drivers of all active cars
+    pub fn drivers(&self) -> Vec<Driver> {
+        (0..self.num_active_cars)
+            .filter_map(|idx| self.driver(idx))
+            .collect()
+    }
+}
+
+/// A player in a multiplayer lobby, before the session starts.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct LobbyPlayer {
+    pub name: String,
+    pub team_id: TeamId,
+    pub nationality: NationalityId,
+    pub car_number: u8,
+    pub ai_controlled: bool,
+    pub public_telemetry: bool,
+    pub platform: Platform,
+    pub ready_status: ReadyStatus,
+}
+
+impl From<&LobbyInfoData> for LobbyPlayer {
+    fn from(data: &LobbyInfoData) -> Self {
+        Self {
+            name: String::from_utf8_lossy(data.name.split(|&b| b == b'\0').next().unwrap_or(&[]))
+                .to_string(),
+            team_id: data.team_id,
+            nationality: data.nationality,
+            car_number: data.car_number,
+            ai_controlled: data.ai_controlled == 1,
+            public_telemetry: data.your_telemetry == 1,
+            platform: data.platform.into(),
+            ready_status: data.ready_status.into(),
+        }
+    }
+}
+
+impl PacketLobbyInfoData {
+    /// Every player in the lobby
+    pub fn players(&self) -> Vec<LobbyPlayer> {
+        self.lobby_players
+            .iter()
+            .take(self.num_players as usize)
+            .map(LobbyPlayer::from)
+            .collect()
+    }
+}
+
+impl PacketLapData {
+    pub fn lap(&self, vehicle_idx: u8) -> Option<Lap> {
+        self.lap_data.get(vehicle_idx as usize).map(Lap::from)
+    }
+}
+
+impl PacketCarTelemetryData {
+    pub fn telemetry(&self, vehicle_idx: u8) -> Option<CarTelemetry> {
+        self.car_telemetry_data
+            .get(vehicle_idx as usize)
+            .map(CarTelemetry::from)
+    }
+}
+
+impl PacketCarStatusData {
+    pub fn status(&self, vehicle_idx: u8) -> Option<CarStatus> {
+        self.car_status_data
+            .get(vehicle_idx as usize)
+            .map(CarStatus::from)
+    }
+}
+
+impl PacketCarDamageData {
+    pub fn damage(&self, vehicle_idx: u8) -> Option<CarDamage> {
+        self.car_damage_data
+            .get(vehicle_idx as usize)
+            .map(CarDamage::from)
+    }
+}
+
+impl PacketMotionData {
+    pub fn motion(&self, vehicle_idx: u8) -> Option<CarMotion> {
+        self.car_motion_data
+            .get(vehicle_idx as usize)
+            .map(CarMotion::from)
+    }
+}
+
+impl PacketTyreSetsData {
+    /// All tyre sets allocated to the car this packet relates to
+    pub fn tyre_sets(&self) -> Vec<TyreSet> {
+        self.tyre_set_data.iter().map(TyreSet::from).collect()
+    }
+}
+
+impl PacketSessionHistoryData {
+    /// Every lap the car has completed this session, oldest first
+    pub fn laps(&self) -> Vec<LapTime> {
+        self.lap_history_data
+            .iter()
+            .take(self.num_laps as usize)
+            .enumerate()
+            .filter(|(_, lap)| lap.lap_time_in_ms > 0)
+            .map(|(idx, lap)| LapTime::from_history(idx as u8 + 1, lap))
+            .collect()
+    }
+
+    /// Every set of tyres the car has used this session, oldest first
+    pub fn stints(&self) -> Vec<TyreStint> {
+        let mut start_lap = 1;
+        self.tyre_stints_history_data
+            .iter()
+            .take(self.num_tyre_stints as usize)
+            .map(|stint| {
+                let end_lap = (stint.end_lap != 255).then_some(stint.end_lap);
+                let tyre_stint = TyreStint {
+                    compound: stint.tyre_actual_compound.into(),
+                    visual_compound: stint.tyre_visual_compound.into(),
+                    start_lap,
+                    end_lap,
+                };
+                start_lap = end_lap.map_or(start_lap, |end_lap| end_lap + 1);
+                tyre_stint
+            })
+            .collect()
+    }
+
+    /// Lap of the car's fastest lap and sectors, None if not set yet
+    pub fn best_lap_nums(&self) -> (Option<u8>, [Option<u8>; 3]) {
+        let lap_num = |lap: u8| (lap > 0).then_some(lap);
+        (
+            lap_num(self.best_lap_time_lap_num),
+            [
+                lap_num(self.best_sector1_lap_num),
+                lap_num(self.best_sector2_lap_num),
+                lap_num(self.best_sector3_lap_num),
+            ],
+        )
+    }
+}
+
+/// Settings and progress of the session being driven.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SessionInfo {
+    pub session_type: SessionType,
+    pub track_id: TrackId,
+    pub formula: FormulaType,
+    pub game_mode: GameModeId,
+    pub rule_set: RulesetId,
+    pub session_length: SessionLength,
+    pub total_laps: u8,
+    pub track_length_m: u16,
+    pub time_left: Duration,
+    pub duration: Duration,
+    pub pit_speed_limit_kph: u8,
+    pub game_paused: bool,
+    pub is_spectating: bool,
+    pub spectator_car_index: u8,
+    pub network_game: bool,
+    pub safety_car: SafetyCarStatus,
+    pub num_safety_car_periods: u8,
+    pub num_virtual_safety_car_periods: u8,
+    pub num_red_flag_periods: u8,
+
+    /// Flag shown in each marshal zone, in order around the lap
+    pub marshal_zone_flags: Vec<FiaFlag>,
+    pub pit_stop_window_ideal_lap: u8,
+    pub pit_stop_window_latest_lap: u8,
+    pub pit_stop_rejoin_position: u8,
+
+    /// Distance around the lap sectors 2 and 3 start at. F1 24+, 0 otherwise
+    pub sector2_lap_distance_start_m: f32,
+    pub sector3_lap_distance_start_m: f32,
+}
+
+impl PacketSessionData {
+    pub fn info(&self) -> SessionInfo {
+        SessionInfo {
+            session_type: self.session_type,
+            track_id: self.track_id,
+            formula: self.formula,
+            game_mode: self.game_mode,
+            rule_set: self.rule_set,
+            session_length: self.session_length,
+            total_laps: self.total_laps,
+            track_length_m: self.track_length,
+            time_left: Duration::from_secs(self.session_time_left as u64),
+            duration: Duration::from_secs(self.session_duration as u64),
+            pit_speed_limit_kph: self.pit_speed_limit,
+            game_paused: self.game_paused == 1,
+            is_spectating: self.is_spectating == 1,
+            spectator_car_index: self.spectator_car_index,
+            network_game: self.network_game == 1,
+            safety_car: self.safety_car(),
+            num_safety_car_periods: self.num_safety_car_periods,
+            num_virtual_safety_car_periods: self.num_virtual_safety_car_periods,
+            num_red_flag_periods: self.num_red_flag_periods,
+            marshal_zone_flags: self.marshal_zone_flags(),
+            pit_stop_window_ideal_lap: self.pit_stop_window_ideal_lap,
+            pit_stop_window_latest_lap: self.pit_stop_window_latest_lap,
+            pit_stop_rejoin_position: self.pit_stop_rejoin_position,
+            sector2_lap_distance_start_m: self.sector2_lap_distance_start,
+            sector3_lap_distance_start_m: self.sector3_lap_distance_start,
+        }
+    }
+
+    pub fn weather(&self) -> Weather {
+        let num_samples = self.num_weather_forecast_samples as usize;
+
+        Weather {
+            weather: self.weather,
+            track_temperature_c: self.track_temperature,
+            air_temperature_c: self.air_temperature,
+            forecast_accurate: self.forecast_accuracy == 0,
+            forecast: self
+                .weather_forecast_samples
+                .iter()
+                .take(num_samples)
+                .map(WeatherForecast::from)
+                .collect(),
+        }
+    }
+
+    pub fn safety_car(&self) -> SafetyCarStatus {
+        self.safety_car_status.into()
+    }
+
+    /// Flag shown in each active marshal zone
+    pub fn marshal_zone_flags(&self) -> Vec<FiaFlag> {
+        self.marshal_zones
+            .iter()
+            .take(self.num_marshal_zones as usize)
+            .map(|zone| zone.zone_flag.into())
+            .collect()
+    }
+}
+
+/// Something that happened in the session, from the Event packet.
+///
+/// Cars are referred to by vehicle index.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum RaceEvent {
+    SessionStarted,
+    SessionEnded,
+    FastestLap {
+        car: u8,
+        time: Duration,
+    },
+    Retirement {
+        car: u8,
+    },
+    DrsEnabled,
+    DrsDisabled,
+
+    /// The player's team mate has entered the pits
+    TeamMateInPits {
+        car: u8,
+    },
+    ChequeredFlag,
+    RaceWinner {
+        car: u8,
+    },
+    Penalty {
+        penalty: PenaltyType,
+        infringement: InfringementType,
+        car: u8,
+
+        /// The other car involved, if any
+        other_car: Option<u8>,
+
+        /// Time gained, or time spent doing the action
+        time: Duration,
+        lap: u8,
+        places_gained: u8,
+    },
+    SpeedTrap {
+        car: u8,
+        speed_kph: f32,
+
+        /// Fastest speed of anyone in the session
+        overall_fastest: bool,
+
+        /// Fastest speed of this driver in the session
+        driver_fastest: bool,
+        fastest_car: u8,
+        fastest_speed_kph: f32,
+    },
+    StartLights {
+        num_lights: u8,
+    },
+    LightsOut,
+    DriveThroughServed {
+        car: u8,
+    },
+    StopGoServed {
+        car: u8,
+    },
+    Flashback {
+        /// Frame flashed back to
+        frame_identifier: u32,
+        session_time: f32,
+    },
+
+    /// The buttons held changed, see [`RaceEvent::pressed_buttons`]
+    Buttons {
+        status: u32,
+    },
+
+    /// F1 23+
+    RedFlag,
+
+    /// F1 23+
+    Overtake {
+        overtaking_car: u8,
+        overtaken_car: u8,
+    },
+
+    /// F1 24+
+    SafetyCar {
+        kind: SafetyCarStatus,
+        phase: SafetyCarPhase,
+    },
+
+    /// F1 24+
+    Collision {
+        car: u8,
+        other_car: u8,
+    },
+
+    /// An event code this version of Solis doesn't know
+    Unknown {
+        code: [u8; 4],
+    },
+}
+
+impl RaceEvent {
+    /// Vehicle indexes of the cars the event is about, if any
+    pub fn cars(&self) -> Vec<u8> {
+        match *self {
+            RaceEvent::FastestLap { car, .. }
+            | RaceEvent::Retirement { car }
+            | RaceEvent::TeamMateInPits { car }
+            | RaceEvent::RaceWinner { car }
+            | RaceEvent::SpeedTrap { car, .. }
+            | RaceEvent::DriveThroughServed { car }
+            | RaceEvent::StopGoServed { car } => vec![car],
+            RaceEvent::Penalty { car, other_car, .. } => {
+                std::iter::once(car).chain(other_car).collect()
+            }
+            RaceEvent::Overtake {
+                overtaking_car,
+                overtaken_car,
+            } => vec![overtaking_car, overtaken_car],
+            RaceEvent::Collision { car, other_car } => vec![car, other_car],
+            _ => Vec::new(),
+        }
+    }
+
+    /// Buttons held, for a Buttons event
+    pub fn pressed_buttons(&self) -> Vec<ButtonFlag> {
+        match *self {
+            RaceEvent::Buttons { status } => Buttons {
+                button_status: status,
+            }
+            .get_pressed_buttons(),
+            _ => Vec::new(),
+        }
+    }
+}
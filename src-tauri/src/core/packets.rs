@@ -1,19 +1,21 @@
-use std::sync::MutexGuard;
-
 use crate::core::{
     cm_events::{Event, CM_EVENTS},
-    ids::{ButtonFlag, EventId, PacketType},
-    Session,
+    ids::{ButtonFlag, PacketType},
+    Participants, RaceEvent, SafetyCarPhase, SafetyCarStatus,
 };
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
 
-/// F1 22 Packet Definitions
+/// F1 22 - F1 25 Packet Definitions
 /// Provided by CodeMaster
 ///
-/// All structs are packed. Everything in little endian.
-/// Sent over UDP (telemetry) and then to be interpreted
-/// by Solis Core.
+/// Each struct is a superset of every supported season's layout.
+/// Fields that a season does not send are left as their default (zero)
+/// value, see the `F1 23+` style notes next to each field.
+///
+/// Packed and little endian on the wire. Sent over UDP (telemetry)
+/// and decoded field by field into these structs by the
+/// [`decoder`](crate::core::parse_packet) to be interpreted by Solis Core.
 ///
 /// Note: All wheel arrays have the following order:
 /// Index - Name
@@ -27,10 +29,10 @@ use crate::core::ids::{
 };
 
 /// Every packet will have the following header.
-#[repr(C, packed)]
 #[derive(Serialize, Deserialize, Debug, Clone, Copy, Default)]
 pub struct PacketHeader {
-    pub packet_format: u16,
+    pub packet_format: u16, // 2022, 2023, 2024 or 2025
+    pub game_year: u8,      // F1 23+, game year - last two digits e.g. 23
     pub game_major_version: u8,
     pub game_minor_version: u8,
     pub packet_version: u8,
@@ -51,10 +53,15 @@ pub struct PacketHeader {
     /// | Lobby Info            | 9   | Information about players in a multiplayer lobby                                             |
     /// | Car Damage            | 10  | Damage status for all cars                                                                   |
     /// | Session History       | 11  | Lap and tyre data for session                                                                |
+    /// | Tyre Sets             | 12  | F1 23+, extended tyre set data                                                               |
+    /// | Motion Ex             | 13  | F1 23+, extended motion data for player car                                                  |
+    /// | Time Trial            | 14  | F1 24+, time trial specific data                                                             |
+    /// | Lap Positions         | 15  | F1 25+, position of each car at the end of every lap                                         |
     pub packet_id: PacketType,
     pub session_uid: u64,
     pub session_time: f32,
     pub frame_identifier: u32,
+    pub overall_frame_identifier: u32, // F1 23+, overall frame identifier, doesn't go back after flashbacks
     pub player_car_index: u8,
 
     /// Index of secondary player's car in the array (splitscreen)
@@ -63,40 +70,39 @@ pub struct PacketHeader {
 }
 
 /// Physics data for a vehicle
-#[repr(C, packed)]
 #[derive(Serialize, Deserialize, Debug, Default, Clone, Copy)]
-struct CarMotionData {
-    world_position_x: f32,     // World space X position
-    world_position_y: f32,     // World space Y position
-    world_position_z: f32,     // World space Z position
-    world_velocity_x: f32,     // Velocity in world space X
-    world_velocity_y: f32,     // Velocity in world space Y
-    world_velocity_z: f32,     // Velocity in world space Z
-    world_forward_dir_x: i16,  // World space forward X direction (normalised)
-    world_forward_dir_y: i16,  // World space forward Y direction (normalised)
-    world_forward_dir_z: i16,  // World space forward Z direction (normalised)
-    world_right_dir_x: i16,    // World space right X direction (normalised)
-    world_right_dir_y: i16,    // World space right Y direction (normalised)
-    world_right_dir_z: i16,    // World space right Z direction (normalised)
-    g_force_lateral: f32,      // Lateral G-Force component
-    g_force_longitudinal: f32, // Longitudinal G-Force component
-    g_force_vertical: f32,     // Vertical G-Force component
-    yaw: f32,                  // Yaw angle in radians
-    pitch: f32,                // Pitch angle in radians
-    roll: f32,                 // Roll angle in radians
+pub(crate) struct CarMotionData {
+    pub(crate) world_position_x: f32,     // World space X position
+    pub(crate) world_position_y: f32,     // World space Y position
+    pub(crate) world_position_z: f32,     // World space Z position
+    pub(crate) world_velocity_x: f32,     // Velocity in world space X
+    pub(crate) world_velocity_y: f32,     // Velocity in world space Y
+    pub(crate) world_velocity_z: f32,     // Velocity in world space Z
+    pub(crate) world_forward_dir_x: i16,  // World space forward X direction (normalised)
+    pub(crate) world_forward_dir_y: i16,  // World space forward Y direction (normalised)
+    pub(crate) world_forward_dir_z: i16,  // World space forward Z direction (normalised)
+    pub(crate) world_right_dir_x: i16,    // World space right X direction (normalised)
+    pub(crate) world_right_dir_y: i16,    // World space right Y direction (normalised)
+    pub(crate) world_right_dir_z: i16,    // World space right Z direction (normalised)
+    pub(crate) g_force_lateral: f32,      // Lateral G-Force component
+    pub(crate) g_force_longitudinal: f32, // Longitudinal G-Force component
+    pub(crate) g_force_vertical: f32,     // Vertical G-Force component
+    pub(crate) yaw: f32,                  // Yaw angle in radians
+    pub(crate) pitch: f32,                // Pitch angle in radians
+    pub(crate) roll: f32,                 // Roll angle in radians
 }
 
 /// Physics data for all the cars being driven.
 ///
-/// Includes additional data for the car being driven
+/// In F1 22 this includes additional data for the car being driven
 /// with the goal of being able to drive a motion platform setup.
-#[repr(C, packed)]
+/// F1 23 and later moved that data to [`PacketMotionExData`].
 #[derive(Serialize, Deserialize, Debug, Clone, Copy)]
 pub struct PacketMotionData {
-    pub header: PacketHeader,             // Header
-    car_motion_data: [CarMotionData; 22], // Data for all cars on track
+    pub header: PacketHeader,                        // Header
+    pub(crate) car_motion_data: [CarMotionData; 22], // Data for all cars on track
 
-    // Extra player car ONLY data
+    // Extra player car ONLY data, F1 22 only
     pub suspension_velocity: [f32; 4],     // RL, RR, FL, FR
     pub suspension_acceleration: [f32; 4], // RL, RR, FL, FR
     pub suspension_position: [f32; 4],     // RL, RR, FL, FR
@@ -114,27 +120,62 @@ pub struct PacketMotionData {
     pub front_wheels_angle: f32,           // Current front wheels angle in radians
 }
 
-#[repr(C, packed)]
+/// Extended motion data for the player's car only. F1 23+
+///
+/// Replaces the extra player car data at the end of the F1 22
+/// [`PacketMotionData`].
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub struct PacketMotionExData {
+    pub header: PacketHeader, // Header
+
+    pub suspension_position: [f32; 4],     // RL, RR, FL, FR
+    pub suspension_velocity: [f32; 4],     // RL, RR, FL, FR
+    pub suspension_acceleration: [f32; 4], // RL, RR, FL, FR
+    pub wheel_speed: [f32; 4],             // Speed of each wheel
+    pub wheel_slip_ratio: [f32; 4],        // Slip ratio for each wheel
+    pub wheel_slip_angle: [f32; 4],        // Slip angles for each wheel
+    pub wheel_lat_force: [f32; 4],         // Lateral forces for each wheel
+    pub wheel_long_force: [f32; 4],        // Longitudinal forces for each wheel
+    pub height_of_cog_above_ground: f32,   // Height of centre of gravity above ground
+    pub local_velocity_x: f32,             // Velocity in local space – metres/s
+    pub local_velocity_y: f32,             // Velocity in local space
+    pub local_velocity_z: f32,             // Velocity in local space
+    pub angular_velocity_x: f32,           // Angular velocity x-component – radians/s
+    pub angular_velocity_y: f32,           // Angular velocity y-component
+    pub angular_velocity_z: f32,           // Angular velocity z-component
+    pub angular_acceleration_x: f32,       // Angular acceleration x-component – radians/s/s
+    pub angular_acceleration_y: f32,       // Angular acceleration y-component
+    pub angular_acceleration_z: f32,       // Angular acceleration z-component
+    pub front_wheels_angle: f32,           // Current front wheels angle in radians
+    pub wheel_vert_force: [f32; 4],        // Vertical forces for each wheel
+    pub front_aero_height: f32,            // F1 24+, front plank edge height above road surface
+    pub rear_aero_height: f32,             // F1 24+, rear plank edge height above road surface
+    pub front_roll_angle: f32,             // F1 24+, roll angle of the front suspension
+    pub rear_roll_angle: f32,              // F1 24+, roll angle of the rear suspension
+    pub chassis_yaw: f32, // F1 24+, yaw angle of the chassis relative to the direction of motion - radians
+    pub chassis_pitch: f32, // F1 25+, pitch angle of the chassis relative to the direction of motion - radians
+    pub wheel_camber: [f32; 4], // F1 25+, camber of each wheel in radians
+    pub wheel_camber_gain: [f32; 4], // F1 25+, camber gain for each wheel in radians
+}
+
 #[derive(Serialize, Deserialize, Debug, Default, Clone, Copy)]
 pub struct MarshalZone {
-    zone_start: f32, // Fraction (0..1) of way through the lap the marshal zone starts
-    zone_flag: i8,   // -1 = invalid/unknown, 0 = none, 1 = green, 2 = blue, 3 = yellow, 4 = red
+    pub(crate) zone_start: f32, // Fraction (0..1) of way through the lap the marshal zone starts
+    pub(crate) zone_flag: i8, // -1 = invalid/unknown, 0 = none, 1 = green, 2 = blue, 3 = yellow, 4 = red
 }
 
-#[repr(C, packed)]
 #[derive(Serialize, Deserialize, Debug, Default, Clone, Copy)]
 pub struct WeatherForecastSample {
-    session_type: SessionType,
-    time_offset: u8, // Time in minutes the forecast is for
-    weather: WeatherType,
-    track_temperature: i8,        // Track temp. in degrees Celsius
-    track_temperature_change: i8, // Track temp. change – 0 = up, 1 = down, 2 = no change
-    air_temperature: i8,          // Air temp. in degrees celsius
-    air_temperature_change: i8,   // Air temp. change – 0 = up, 1 = down, 2 = no change
-    rain_percentage: u8,          // Rain percentage (0-100)
+    pub(crate) session_type: SessionType,
+    pub(crate) time_offset: u8, // Time in minutes the forecast is for
+    pub(crate) weather: WeatherType,
+    pub(crate) track_temperature: i8, // Track temp. in degrees Celsius
+    pub(crate) track_temperature_change: i8, // Track temp. change – 0 = up, 1 = down, 2 = no change
+    pub(crate) air_temperature: i8,   // Air temp. in degrees celsius
+    pub(crate) air_temperature_change: i8, // Air temp. change – 0 = up, 1 = down, 2 = no change
+    pub(crate) rain_percentage: u8,   // Rain percentage (0-100)
 }
 
-#[repr(C, packed)]
 #[serde_as]
 #[derive(Serialize, Deserialize, Debug, Clone, Copy)]
 pub struct PacketSessionData {
@@ -156,101 +197,138 @@ pub struct PacketSessionData {
     pub sli_pro_native_support: u8, // SLI Pro support, 0 = inactive, 1 = active
     pub num_marshal_zones: u8,      // Number of marshal zones to follow
     #[serde_as(as = "[_; 21]")]
-    marshal_zones: [MarshalZone; 21], // List of marshal zones – max 21
+    pub(crate) marshal_zones: [MarshalZone; 21], // List of marshal zones – max 21
     pub safety_car_status: u8,      // 0 = no safety car, 1 = full
     // 2 = virtual, 3 = formation lap
     pub network_game: u8,                 // 0 = offline, 1 = online
     pub num_weather_forecast_samples: u8, // Number of weather samples to follow
-    #[serde_as(as = "[_; 56]")]
-    weather_forecast_samples: [WeatherForecastSample; 56], // Array of weather forecast samples
-    pub forecast_accuracy: u8,            // 0 = Perfect, 1 = Approximate
-    pub ai_difficulty: u8,                // AI Difficulty rating – 0-110
-    pub season_link_identifier: u32,      // Identifier for season - persists across saves
-    pub weekend_link_identifier: u32,     // Identifier for weekend - persists across saves
-    pub session_link_identifier: u32,     // Identifier for session - persists across saves
-    pub pit_stop_window_ideal_lap: u8,    // Ideal lap to pit on for current strategy (player)
-    pub pit_stop_window_latest_lap: u8,   // Latest lap to pit on for current strategy (player)
-    pub pit_stop_rejoin_position: u8,     // Predicted position to rejoin at (player)
-    pub steering_assist: u8,              // 0 = off, 1 = on
-    pub braking_assist: u8,               // 0 = off, 1 = low, 2 = medium, 3 = high
-    pub gearbox_assist: u8,               // 1 = manual, 2 = manual & suggested gear, 3 = auto
-    pub pit_assist: u8,                   // 0 = off, 1 = on
-    pub pit_release_assist: u8,           // 0 = off, 1 = on
-    pub ers_assist: u8,                   // 0 = off, 1 = on
-    pub drs_assist: u8,                   // 0 = off, 1 = on
-    pub dynamic_racing_line: u8,          // 0 = off, 1 = corners only, 2 = full
-    pub dynamic_racing_line_type: u8,     // 0 = 2D, 1 = 3D
-    pub game_mode: GameModeId,            // Game mode id - see appendix
-    pub rule_set: RulesetId,              // Ruleset - see appendix
-    pub time_of_day: u32,                 // Local time of day - minutes since midnight
+    #[serde_as(as = "[_; 64]")]
+    pub(crate) weather_forecast_samples: [WeatherForecastSample; 64], // Array of weather forecast samples, 56 max before F1 24
+    pub forecast_accuracy: u8,          // 0 = Perfect, 1 = Approximate
+    pub ai_difficulty: u8,              // AI Difficulty rating – 0-110
+    pub season_link_identifier: u32,    // Identifier for season - persists across saves
+    pub weekend_link_identifier: u32,   // Identifier for weekend - persists across saves
+    pub session_link_identifier: u32,   // Identifier for session - persists across saves
+    pub pit_stop_window_ideal_lap: u8,  // Ideal lap to pit on for current strategy (player)
+    pub pit_stop_window_latest_lap: u8, // Latest lap to pit on for current strategy (player)
+    pub pit_stop_rejoin_position: u8,   // Predicted position to rejoin at (player)
+    pub steering_assist: u8,            // 0 = off, 1 = on
+    pub braking_assist: u8,             // 0 = off, 1 = low, 2 = medium, 3 = high
+    pub gearbox_assist: u8,             // 1 = manual, 2 = manual & suggested gear, 3 = auto
+    pub pit_assist: u8,                 // 0 = off, 1 = on
+    pub pit_release_assist: u8,         // 0 = off, 1 = on
+    pub ers_assist: u8,                 // 0 = off, 1 = on
+    pub drs_assist: u8,                 // 0 = off, 1 = on
+    pub dynamic_racing_line: u8,        // 0 = off, 1 = corners only, 2 = full
+    pub dynamic_racing_line_type: u8,   // 0 = 2D, 1 = 3D
+    pub game_mode: GameModeId,          // Game mode id - see appendix
+    pub rule_set: RulesetId,            // Ruleset - see appendix
+    pub time_of_day: u32,               // Local time of day - minutes since midnight
     pub session_length: SessionLength,
+    pub speed_units_lead_player: u8,       // F1 23+, 0 = MPH, 1 = KPH
+    pub temperature_units_lead_player: u8, // F1 23+, 0 = Celsius, 1 = Fahrenheit
+    pub speed_units_secondary_player: u8,  // F1 23+, 0 = MPH, 1 = KPH
+    pub temperature_units_secondary_player: u8, // F1 23+, 0 = Celsius, 1 = Fahrenheit
+    pub num_safety_car_periods: u8,        // F1 23+, number of safety cars called during session
+    pub num_virtual_safety_car_periods: u8, // F1 23+, number of virtual safety cars called
+    pub num_red_flag_periods: u8,          // F1 23+, number of red flags called during session
+    pub equal_car_performance: u8,         // F1 24+, 0 = Off, 1 = On
+    pub recovery_mode: u8,                 // F1 24+, 0 = None, 1 = Flashbacks, 2 = Auto-recovery
+    pub flashback_limit: u8,               // F1 24+, 0 = Low, 1 = Medium, 2 = High, 3 = Unlimited
+    pub surface_type: u8,                  // F1 24+, 0 = Simplified, 1 = Realistic
+    pub low_fuel_mode: u8,                 // F1 24+, 0 = Easy, 1 = Hard
+    pub race_starts: u8,                   // F1 24+, 0 = Manual, 1 = Assisted
+    pub tyre_temperature: u8,              // F1 24+, 0 = Surface only, 1 = Surface & Carcass
+    pub pit_lane_tyre_sim: u8,             // F1 24+, 0 = On, 1 = Off
+    pub car_damage: u8, // F1 24+, 0 = Off, 1 = Reduced, 2 = Standard, 3 = Simulation
+    pub car_damage_rate: u8, // F1 24+, 0 = Reduced, 1 = Standard, 2 = Simulation
+    pub collisions: u8, // F1 24+, 0 = Off, 1 = Player-to-Player Off, 2 = On
+    pub collisions_off_for_first_lap_only: u8, // F1 24+, 0 = Disabled, 1 = Enabled
+    pub mp_unsafe_pit_release: u8, // F1 24+, 0 = On, 1 = Off (Multiplayer)
+    pub mp_off_for_griefing: u8, // F1 24+, 0 = Disabled, 1 = Enabled (Multiplayer)
+    pub corner_cutting_stringency: u8, // F1 24+, 0 = Regular, 1 = Strict
+    pub parc_ferme_rules: u8, // F1 24+, 0 = Off, 1 = On
+    pub pit_stop_experience: u8, // F1 24+, 0 = Automatic, 1 = Broadcast, 2 = Immersive
+    pub safety_car: u8, // F1 24+, 0 = Off, 1 = Reduced, 2 = Standard, 3 = Increased
+    pub safety_car_experience: u8, // F1 24+, 0 = Broadcast, 1 = Immersive
+    pub formation_lap: u8, // F1 24+, 0 = Off, 1 = On
+    pub formation_lap_experience: u8, // F1 24+, 0 = Broadcast, 1 = Immersive
+    pub red_flags: u8,  // F1 24+, 0 = Off, 1 = Reduced, 2 = Standard, 3 = Increased
+    pub affects_licence_level_solo: u8, // F1 24+, 0 = Off, 1 = On
+    pub affects_licence_level_mp: u8, // F1 24+, 0 = Off, 1 = On
+    pub num_sessions_in_weekend: u8, // F1 24+, number of session in following array
+    pub weekend_structure: [u8; 12], // F1 24+, list of session types to show weekend structure
+    pub sector2_lap_distance_start: f32, // F1 24+, distance in m around track where sector 2 starts
+    pub sector3_lap_distance_start: f32, // F1 24+, distance in m around track where sector 3 starts
 }
 
-#[repr(C, packed)]
 #[derive(Serialize, Deserialize, Debug, Clone, Copy)]
-struct LapData {
-    last_lap_time_in_ms: u32,            // Last lap time in milliseconds
-    current_lap_time_in_ms: u32,         // Current time around the lap in milliseconds
-    sector1_time_in_ms: u16,             // Sector 1 time in milliseconds
-    sector2_time_in_ms: u16,             // Sector 2 time in milliseconds
-    lap_distance: f32, // Distance vehicle is around current lap in metres – could be negative if line hasn’t been crossed yet
-    total_distance: f32, // Total distance travelled in session in metres – could be negative if line hasn’t been crossed yet
-    safety_car_delta: f32, // Delta in seconds for safety car
-    car_position: u8,    // Car race position
-    current_lap_num: u8, // Current lap number
-    pit_status: u8,      // 0 = none, 1 = pitting, 2 = in pit area
-    num_pit_stops: u8,   // Number of pit stops taken in this race
-    sector: u8,          // 0 = sector1, 1 = sector2, 2 = sector3
-    current_lap_invalid: u8, // Current lap invalid - 0 = valid, 1 = invalid
-    penalties: u8,       // Accumulated time penalties in seconds to be added
-    warnings: u8,        // Accumulated number of warnings issued
-    num_unserved_drive_through_pens: u8, // Num drive through pens left to serve
-    num_unserved_stop_go_pens: u8, // Num stop go pens left to serve
-    grid_position: u8,   // Grid position the vehicle started the race in
-    driver_status: u8, // Status of driver - 0 = in garage, 1 = flying lap, 2 = in lap, 3 = out lap, 4 = on track
-    result_status: u8, // Result status - 0 = invalid, 1 = inactive, 2 = active, 3 = finished, 4 = didnotfinish, 5 = disqualified, 6 = not classified, 7 = retired
-    pit_lane_timer_active: u8, // Pit lane timing, 0 = inactive, 1 = active
-    pit_lane_time_in_lane_in_ms: u16, // If active, the current time spent in the pit lane in ms
-    pit_stop_timer_in_ms: u16, // Time of the actual pit stop in ms
-    pit_stop_should_serve_pen: u8, // Whether the car should serve a penalty at this stop
+pub(crate) struct LapData {
+    pub(crate) last_lap_time_in_ms: u32, // Last lap time in milliseconds
+    pub(crate) current_lap_time_in_ms: u32, // Current time around the lap in milliseconds
+    pub(crate) sector1_time_in_ms: u16,  // Sector 1 time in milliseconds
+    pub(crate) sector1_time_minutes: u8, // F1 23+, sector 1 whole minute part
+    pub(crate) sector2_time_in_ms: u16,  // Sector 2 time in milliseconds
+    pub(crate) sector2_time_minutes: u8, // F1 23+, sector 2 whole minute part
+    pub(crate) delta_to_car_in_front_in_ms: u16, // F1 23+, time delta to car in front in milliseconds
+    pub(crate) delta_to_car_in_front_minutes: u8, // F1 24+, time delta to car in front whole minute part
+    pub(crate) delta_to_race_leader_in_ms: u16, // F1 23+, time delta to race leader in milliseconds
+    pub(crate) delta_to_race_leader_minutes: u8, // F1 24+, time delta to race leader whole minute part
+    pub(crate) lap_distance: f32, // Distance vehicle is around current lap in metres – could be negative if line hasn’t been crossed yet
+    pub(crate) total_distance: f32, // Total distance travelled in session in metres – could be negative if line hasn’t been crossed yet
+    pub(crate) safety_car_delta: f32, // Delta in seconds for safety car
+    pub(crate) car_position: u8,    // Car race position
+    pub(crate) current_lap_num: u8, // Current lap number
+    pub(crate) pit_status: u8,      // 0 = none, 1 = pitting, 2 = in pit area
+    pub(crate) num_pit_stops: u8,   // Number of pit stops taken in this race
+    pub(crate) sector: u8,          // 0 = sector1, 1 = sector2, 2 = sector3
+    pub(crate) current_lap_invalid: u8, // Current lap invalid - 0 = valid, 1 = invalid
+    pub(crate) penalties: u8,       // Accumulated time penalties in seconds to be added
+    pub(crate) warnings: u8,        // Accumulated number of warnings issued
+    pub(crate) corner_cutting_warnings: u8, // F1 23+, accumulated number of corner cutting warnings issued
+    pub(crate) num_unserved_drive_through_pens: u8, // Num drive through pens left to serve
+    pub(crate) num_unserved_stop_go_pens: u8, // Num stop go pens left to serve
+    pub(crate) grid_position: u8,           // Grid position the vehicle started the race in
+    pub(crate) driver_status: u8, // Status of driver - 0 = in garage, 1 = flying lap, 2 = in lap, 3 = out lap, 4 = on track
+    pub(crate) result_status: u8, // Result status - 0 = invalid, 1 = inactive, 2 = active, 3 = finished, 4 = didnotfinish, 5 = disqualified, 6 = not classified, 7 = retired
+    pub(crate) pit_lane_timer_active: u8, // Pit lane timing, 0 = inactive, 1 = active
+    pub(crate) pit_lane_time_in_lane_in_ms: u16, // If active, the current time spent in the pit lane in ms
+    pub(crate) pit_stop_timer_in_ms: u16,        // Time of the actual pit stop in ms
+    pub(crate) pit_stop_should_serve_pen: u8, // Whether the car should serve a penalty at this stop
+    pub(crate) speed_trap_fastest_speed: f32, // F1 24+, fastest speed through speed trap for this car in kmph
+    pub(crate) speed_trap_fastest_lap: u8, // F1 24+, lap no the fastest speed was achieved, 255 = not set
 }
 
 /// The lap data packet gives details of all the cars in the session.
-#[repr(C, packed)]
 #[derive(Serialize, Deserialize, Debug, Clone, Copy)]
 pub struct PacketLapData {
     pub header: PacketHeader,
-    lap_data: [LapData; 22],          // Lap data for all cars on track
-    pub time_trial_pb_car_idx: u8,    // Index of Personal Best car in time trial (255 if invalid)
-    pub time_trial_rival_car_idx: u8, // Index of Rival car in time trial (255 if invalid)
+    pub(crate) lap_data: [LapData; 22], // Lap data for all cars on track
+    pub time_trial_pb_car_idx: u8,      // Index of Personal Best car in time trial (255 if invalid)
+    pub time_trial_rival_car_idx: u8,   // Index of Rival car in time trial (255 if invalid)
 }
 
-#[repr(C, packed)]
 #[derive(Debug, Default, Clone, Copy)]
 pub struct FastestLap {
     pub vehicle_idx: u8, // Vehicle index of car achieving fastest lap
     pub lap_time: f32,   // Lap time is in seconds
 }
 
-#[repr(C, packed)]
 #[derive(Debug, Default, Clone, Copy)]
 pub struct Retirement {
     pub vehicle_idx: u8, // Vehicle index of car retiring
 }
 
-#[repr(C, packed)]
 #[derive(Debug, Default, Clone, Copy)]
 pub struct TeamMateInPits {
     pub vehicle_idx: u8, // Vehicle index of team mate
 }
 
-#[repr(C, packed)]
 #[derive(Debug, Default, Clone, Copy)]
 pub struct RaceWinner {
     pub vehicle_idx: u8, // Vehicle index of the race winner
 }
 
-#[repr(C, packed)]
 #[derive(Debug, Clone, Copy)]
 pub struct Penalty {
     pub penalty_type: PenaltyType, // Penalty type – see Appendices
@@ -262,7 +340,6 @@ pub struct Penalty {
     pub places_gained: u8,         // Number of places gained by this
 }
 
-#[repr(C, packed)]
 #[derive(Debug, Default, Clone, Copy)]
 pub struct SpeedTrap {
     pub vehicle_idx: u8, // Vehicle index of the vehicle triggering speed trap
@@ -274,32 +351,47 @@ pub struct SpeedTrap {
                                             // in this session
 }
 
-#[repr(C, packed)]
 #[derive(Debug, Default, Clone, Copy)]
 pub struct StartLights {
     pub num_lights: u8, // Number of lights showing
 }
 
-#[repr(C, packed)]
 #[derive(Debug, Default, Clone, Copy)]
 pub struct DriveThroughPenaltyServed {
     pub vehicle_idx: u8, // Vehicle index of the vehicle serving drive through
 }
 
-#[repr(C, packed)]
 #[derive(Debug, Default, Clone, Copy)]
 pub struct StopGoPenaltyServed {
     pub vehicle_idx: u8, // Vehicle index of the vehicle serving stop go
 }
 
-#[repr(C, packed)]
 #[derive(Debug, Default, Clone, Copy)]
 pub struct Flashback {
     pub flashback_frame_identifier: u32, // Frame identifier flashed back to
     pub flashback_session_time: f32,     // Session time flashed back to
 }
 
-#[repr(C, packed)]
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Overtake {
+    pub overtaking_vehicle_idx: u8, // Vehicle index of the vehicle overtaking
+    pub being_overtaken_vehicle_idx: u8, // Vehicle index of the vehicle being overtaken
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SafetyCar {
+    pub safety_car_type: u8, // 0 = No Safety Car, 1 = Full Safety Car
+    // 2 = Virtual Safety Car, 3 = Formation Lap Safety Car
+    pub event_type: u8, // 0 = Deployed, 1 = Returning, 2 = Returned
+                        // 3 = Resume Race
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Collision {
+    pub vehicle1_idx: u8, // Vehicle index of the first vehicle involved in the collision
+    pub vehicle2_idx: u8, // Vehicle index of the second vehicle involved in the collision
+}
+
 #[derive(Debug, Default, Clone, Copy)]
 pub struct Buttons {
     pub button_status: u32, // Bit flags specifying which buttons are being pressed
@@ -342,25 +434,8 @@ impl Buttons {
     }
 }
 
-#[repr(C, packed)]
-#[derive(Clone, Copy)]
-pub union EventDataDetails {
-    pub fastest_lap: FastestLap,
-    pub retirement: Retirement,
-    pub teammate_in_pits: TeamMateInPits,
-    pub race_winner: RaceWinner,
-    pub penalty: Penalty,
-    pub speed_trap: SpeedTrap,
-    pub start_lights: StartLights,
-    pub drive_through_penalty_served: DriveThroughPenaltyServed,
-    pub stop_go_penalty_served: StopGoPenaltyServed,
-    pub flashback: Flashback,
-    pub buttons: Buttons,
-}
-
 /// This packet gives details of events that happen during the course of a session.
-#[repr(C, packed)]
-#[derive(Clone, Copy)]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
 pub struct PacketEventData {
     pub header: PacketHeader,
 
@@ -383,10 +458,14 @@ pub struct PacketEventData {
     /// Stop go served       -   "SGSV"   -   Stop go penalty served
     /// Flashback            -   "FLBK"   -   Flashback activated
     /// Button status        -   "BUTN"   -   Button status changed
+    /// Red Flag             -   "RDFL"   -   F1 23+, red flag shown
+    /// Overtake             -   "OVTK"   -   F1 23+, overtake occurred
+    /// Safety Car           -   "SCAR"   -   F1 24+, safety car event - details in event
+    /// Collision            -   "COLL"   -   F1 24+, collision between two vehicles has occurred
     pub event_string_code: [u8; 4],
 
-    pub event_details: EventDataDetails, // Event details - should be interpreted differently
-                                         // for each type
+    /// The event and its details, decoded according to the code
+    pub event: RaceEvent,
 }
 
 impl PacketEventData {
@@ -412,149 +491,135 @@ impl PacketEventData {
         CM_EVENTS.get(self.code_as_string().as_str())
     }
 
-    pub fn event_message(&self, session: MutexGuard<'_, Session>) -> String {
-        let event_details = match self.event_reference() {
-            Some(e) => e,
-            None => return String::new(),
-        };
+    /// Vehicle indexes of the cars the event is about, if any
+    pub fn vehicle_indices(&self) -> Vec<u8> {
+        let mut indices = self.event.cars();
 
-        let data_details = self.event_details;
-        match event_details.id {
-            EventId::FastestLap => {
-                let fastest_lap = unsafe { data_details.fastest_lap };
-                let vehicle_idx = fastest_lap.vehicle_idx;
-                let lap_time = fastest_lap.lap_time;
+        // 255 is sent when there is no other car
+        indices.retain(|&idx| idx != 255);
+        indices
+    }
 
-                if let Some(player) = session.get_player(vehicle_idx) {
-                    format!(
-                        "Fastest lap of {} seconds achieved by {}",
-                        lap_time,
-                        player.get_player_name()
-                    )
+    /// Describe the event, naming the drivers
+    /// involved from the current `participants`
+    pub fn event_message(&self, participants: &Participants) -> String {
+        let description = self
+            .event_reference()
+            .map(|event| event.description.to_string())
+            .unwrap_or_default();
+
+        match self.event {
+            RaceEvent::FastestLap { car, time } => {
+                let lap_time = time.as_secs_f32();
+                if let Some(player) = participants.name(car) {
+                    format!("Fastest lap of {} seconds achieved by {}", lap_time, player)
                 } else {
                     format!("Fastest lap of {} seconds achieved", lap_time)
                 }
             }
-            EventId::Retirement => {
-                let retirement = unsafe { data_details.retirement };
-                let vehicle_idx = retirement.vehicle_idx;
-
-                if let Some(player) = session.get_player(vehicle_idx) {
-                    format!("{} retired from the session", player.get_player_name())
+            RaceEvent::Retirement { car } => {
+                if let Some(player) = participants.name(car) {
+                    format!("{} retired from the session", player)
                 } else {
                     "A participant retired from the session".to_string()
                 }
             }
-            EventId::TeamMateInPits => {
-                let pits = unsafe { data_details.teammate_in_pits };
-                let vehicle_idx = pits.vehicle_idx;
-
-                if let Some(player) = session.get_player(vehicle_idx) {
-                    format!(
-                        "Your teammate, {}, is in the pits.",
-                        player.get_player_name()
-                    )
+            RaceEvent::TeamMateInPits { car } => {
+                if let Some(player) = participants.name(car) {
+                    format!("Your teammate, {}, is in the pits.", player)
                 } else {
                     "Your teammate is in the pits".to_string()
                 }
             }
-            EventId::RaceWinner => {
-                let race_winner = unsafe { data_details.race_winner };
-                let vehicle_idx = race_winner.vehicle_idx;
-
-                if let Some(player) = session.get_player(vehicle_idx) {
-                    format!("{} is the Race Winner!", player.get_player_name())
+            RaceEvent::RaceWinner { car } => {
+                if let Some(player) = participants.name(car) {
+                    format!("{} is the Race Winner!", player)
                 } else {
-                    event_details.description.to_string()
+                    description
                 }
             }
-            EventId::PenaltyIssued => {
-                let penalty = unsafe { data_details.penalty };
-                let prim_vehicle_idx = penalty.vehicle_idx;
-                let lap = penalty.lap_num;
-                let time = penalty.time;
-                let penalty_type = penalty.penalty_type;
-                let infringement = penalty.infringement_type;
-
-                if let Some(player) = session.get_player(prim_vehicle_idx) {
-                    if penalty_type == PenaltyType::Retired {
+            RaceEvent::Penalty {
+                penalty,
+                infringement,
+                car,
+                time,
+                lap,
+                ..
+            } => {
+                if let Some(player) = participants.name(car) {
+                    if penalty == PenaltyType::Retired {
                         format!(
                             "{} has been retired from the session on lap {}.",
-                            player.get_player_name(),
-                            lap
+                            player, lap
                         )
                     } else {
-                        format!("{} has received a {} on lap {}. Infringment type: {:?}. Time: {} seconds", player.get_player_name(), penalty_type.as_str(),  lap, infringement, time)
+                        format!(
+                            "{} has received a {} on lap {}. Infringment type: {:?}. Time: {} seconds",
+                            player,
+                            penalty.as_str(),
+                            lap,
+                            infringement,
+                            time.as_secs()
+                        )
                     }
                 } else {
-                    event_details.description.to_string()
+                    description
                 }
             }
-            EventId::SpeedTrapTriggered => {
-                let speed_trap = unsafe { data_details.speed_trap };
-                let vehicle_idx = speed_trap.vehicle_idx;
-                let speed = speed_trap.speed;
-                let max_speed = speed_trap.fastest_speed_in_session;
-
-                if let Some(player) = session.get_player(vehicle_idx) {
-                    if speed_trap.is_overall_fastest_in_session == 1 {
+            RaceEvent::SpeedTrap {
+                car,
+                speed_kph,
+                overall_fastest,
+                driver_fastest,
+                fastest_speed_kph,
+                ..
+            } => {
+                if let Some(player) = participants.name(car) {
+                    if overall_fastest {
                         format!(
                             "Speed trap hit - {} hit the fastest speed in the session: {} kmh",
-                            player.get_player_name(),
-                            speed
+                            player, speed_kph
                         )
-                    } else if speed_trap.is_driver_fastest_in_session == 1 {
+                    } else if driver_fastest {
                         format!(
                             "Speed trap hit - {} hit their fastest speed in the session: {} kmh",
-                            player.get_player_name(),
-                            speed
+                            player, speed_kph
                         )
                     } else {
                         format!(
                             "Speed trap hit - {} hit {} kmh. Max: {} kmh",
-                            player.get_player_name(),
-                            speed,
-                            max_speed
+                            player, speed_kph, fastest_speed_kph
                         )
                     }
                 } else {
-                    format!("Speed trap hit at {} kmh. Max: {} kmh", speed, max_speed)
+                    format!(
+                        "Speed trap hit at {} kmh. Max: {} kmh",
+                        speed_kph, fastest_speed_kph
+                    )
                 }
             }
-            EventId::StartLights => {
-                let start_lights = unsafe { data_details.start_lights };
-                let num = start_lights.num_lights;
-                format!("{} start lights showing", num)
+            RaceEvent::StartLights { num_lights } => {
+                format!("{} start lights showing", num_lights)
             }
-            EventId::DriveThroughServed => {
-                let drive = unsafe { data_details.drive_through_penalty_served };
-                let vehicle_idx = drive.vehicle_idx;
-                if let Some(player) = session.get_player(vehicle_idx) {
-                    format!(
-                        "{} served their drive through penalty",
-                        player.get_player_name()
-                    )
+            RaceEvent::DriveThroughServed { car } => {
+                if let Some(player) = participants.name(car) {
+                    format!("{} served their drive through penalty", player)
                 } else {
-                    event_details.description.to_string()
+                    description
                 }
             }
-            EventId::StopGoServed => {
-                let stop_go = unsafe { data_details.stop_go_penalty_served };
-                let vehicle_idx = stop_go.vehicle_idx;
-                if let Some(player) = session.get_player(vehicle_idx) {
-                    format!("{} served their stop-go penalty", player.get_player_name())
+            RaceEvent::StopGoServed { car } => {
+                if let Some(player) = participants.name(car) {
+                    format!("{} served their stop-go penalty", player)
                 } else {
-                    event_details.description.to_string()
+                    description
                 }
             }
-            EventId::Flashback => {
-                let flashback = unsafe { data_details.flashback };
-                let session_time = flashback.flashback_session_time;
+            RaceEvent::Flashback { session_time, .. } => {
                 format!("Flashback at {} seconds (session time)", session_time)
             }
-            EventId::ButtonStatus => {
-                let buttons = unsafe { data_details.buttons };
-                let pressed = buttons.get_pressed_buttons();
+            RaceEvent::Buttons { .. } => {
+                let pressed = self.event.pressed_buttons();
                 if pressed.is_empty() {
                     return String::new();
                 }
@@ -568,12 +633,50 @@ impl PacketEventData {
                         .join(", ")
                 )
             }
-            _ => event_details.description.to_string(),
+            RaceEvent::Overtake {
+                overtaking_car,
+                overtaken_car,
+            } => {
+                let overtaking = participants.name(overtaking_car);
+                let overtaken = participants.name(overtaken_car);
+
+                if let (Some(overtaking), Some(overtaken)) = (overtaking, overtaken) {
+                    format!("{} overtook {}", overtaking, overtaken)
+                } else {
+                    description
+                }
+            }
+            RaceEvent::SafetyCar { kind, phase } => {
+                let kind = match kind {
+                    SafetyCarStatus::Full => "Safety car",
+                    SafetyCarStatus::Virtual => "Virtual safety car",
+                    SafetyCarStatus::FormationLap => "Formation lap safety car",
+                    _ => return description,
+                };
+
+                match phase {
+                    SafetyCarPhase::Deployed => format!("{kind} deployed"),
+                    SafetyCarPhase::Returning => format!("{kind} returning"),
+                    SafetyCarPhase::Returned => format!("{kind} returned"),
+                    SafetyCarPhase::ResumeRace => format!("{kind} period over, resume race"),
+                    SafetyCarPhase::Unknown => description,
+                }
+            }
+            RaceEvent::Collision { car, other_car } => {
+                let vehicle1 = participants.name(car);
+                let vehicle2 = participants.name(other_car);
+
+                if let (Some(vehicle1), Some(vehicle2)) = (vehicle1, vehicle2) {
+                    format!("Collision between {} and {}", vehicle1, vehicle2)
+                } else {
+                    description
+                }
+            }
+            _ => description,
         }
     }
 }
 
-#[repr(C, packed)]
 #[serde_as]
 #[derive(Serialize, Deserialize, Debug, Clone, Copy)]
 pub struct ParticipantData {
@@ -587,7 +690,21 @@ pub struct ParticipantData {
 
     #[serde_as(as = "[_; 48]")]
     pub name: [u8; 48], // Name of participant in UTF-8 format – null terminated. Truncated with … (U+2026) if too long
+    // 32 bytes max in F1 25+
     pub your_telemetry: u8, // The player's UDP setting, 0 = restricted, 1 = public
+    pub show_online_names: u8, // F1 23+, the player's show online names setting, 0 = off, 1 = on
+    pub tech_level: u16,    // F1 24+, F1 World tech level
+    pub platform: u8, // F1 23+, 1 = Steam, 3 = PlayStation, 4 = Xbox, 6 = Origin, 255 = unknown
+    pub num_colours: u8, // F1 25+, number of colours valid for this car
+    pub livery_colours: [LiveryColour; 4], // F1 25+, colours for the car
+}
+
+/// RGB colour of a car livery. F1 25+
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy)]
+pub struct LiveryColour {
+    pub red: u8,
+    pub green: u8,
+    pub blue: u8,
 }
 
 impl Default for ParticipantData {
@@ -602,6 +719,11 @@ impl Default for ParticipantData {
             race_number: 0,
             nationality: NationalityId::default(),
             your_telemetry: 0,
+            show_online_names: 0,
+            tech_level: 0,
+            platform: 0,
+            num_colours: 0,
+            livery_colours: [LiveryColour::default(); 4],
         }
     }
 }
@@ -620,7 +742,6 @@ impl ParticipantData {
 /// or the LAN name if appropriate.
 ///
 /// The array should be indexed by vehicle index.
-#[repr(C, packed)]
 #[derive(Serialize, Deserialize, Debug, Clone, Copy, Default)]
 pub struct PacketParticipantsData {
     pub header: PacketHeader,
@@ -634,63 +755,62 @@ impl PacketParticipantsData {
     }
 }
 
-#[repr(C, packed)]
 #[derive(Serialize, Deserialize, Debug, Clone, Copy)]
-struct CarSetupData {
-    front_wing: u8,                 // Front wing aero
-    rear_wing: u8,                  // Rear wing aero
-    on_throttle: u8,                // Differential adjustment on throttle (percentage)
-    off_throttle: u8,               // Differential adjustment off throttle (percentage)
-    front_camber: f32,              // Front camber angle (suspension geometry)
-    rear_camber: f32,               // Rear camber angle (suspension geometry)
-    front_toe: f32,                 // Front toe angle (suspension geometry)
-    rear_toe: f32,                  // Rear toe angle (suspension geometry)
-    front_suspension: u8,           // Front suspension
-    rear_suspension: u8,            // Rear suspension
-    front_anti_roll_bar: u8,        // Front anti-roll bar
-    rear_anti_roll_bar: u8,         // Front anti-roll bar
-    front_suspension_height: u8,    // Front ride height
-    rear_suspension_height: u8,     // Rear ride height
-    brake_pressure: u8,             // Brake pressure (percentage)
-    brake_bias: u8,                 // Brake bias (percentage)
-    rear_left_tyre_pressure: f32,   // Rear left tyre pressure (PSI)
-    rear_right_tyre_pressure: f32,  // Rear right tyre pressure (PSI)
-    front_left_tyre_pressure: f32,  // Front left tyre pressure (PSI)
-    front_right_tyre_pressure: f32, // Front right tyre pressure (PSI)
-    ballast: u8,                    // Ballast
-    fuel_load: f32,                 // Fuel load
+pub(crate) struct CarSetupData {
+    pub(crate) front_wing: u8,                 // Front wing aero
+    pub(crate) rear_wing: u8,                  // Rear wing aero
+    pub(crate) on_throttle: u8,                // Differential adjustment on throttle (percentage)
+    pub(crate) off_throttle: u8,               // Differential adjustment off throttle (percentage)
+    pub(crate) front_camber: f32,              // Front camber angle (suspension geometry)
+    pub(crate) rear_camber: f32,               // Rear camber angle (suspension geometry)
+    pub(crate) front_toe: f32,                 // Front toe angle (suspension geometry)
+    pub(crate) rear_toe: f32,                  // Rear toe angle (suspension geometry)
+    pub(crate) front_suspension: u8,           // Front suspension
+    pub(crate) rear_suspension: u8,            // Rear suspension
+    pub(crate) front_anti_roll_bar: u8,        // Front anti-roll bar
+    pub(crate) rear_anti_roll_bar: u8,         // Front anti-roll bar
+    pub(crate) front_suspension_height: u8,    // Front ride height
+    pub(crate) rear_suspension_height: u8,     // Rear ride height
+    pub(crate) brake_pressure: u8,             // Brake pressure (percentage)
+    pub(crate) brake_bias: u8,                 // Brake bias (percentage)
+    pub(crate) engine_braking: u8,             // F1 24+, engine braking (percentage)
+    pub(crate) rear_left_tyre_pressure: f32,   // Rear left tyre pressure (PSI)
+    pub(crate) rear_right_tyre_pressure: f32,  // Rear right tyre pressure (PSI)
+    pub(crate) front_left_tyre_pressure: f32,  // Front left tyre pressure (PSI)
+    pub(crate) front_right_tyre_pressure: f32, // Front right tyre pressure (PSI)
+    pub(crate) ballast: u8,                    // Ballast
+    pub(crate) fuel_load: f32,                 // Fuel load
 }
 
 /// This packet details the car setups for each vehicle in the session.
 ///
 /// Note that in multiplayer games, other player cars will appear as blank,
 /// you will only be able to see your car setup and AI cars.
-#[repr(C, packed)]
 #[derive(Serialize, Deserialize, Debug, Clone, Copy)]
 pub struct PacketCarSetupData {
     pub header: PacketHeader,
-    car_setups: [CarSetupData; 22],
+    pub(crate) car_setups: [CarSetupData; 22],
+    pub next_front_wing_value: f32, // F1 24+, value of front wing after next pit stop - player only
 }
 
-#[repr(C, packed)]
 #[derive(Serialize, Deserialize, Debug, Clone, Copy)]
-struct CarTelemetryData {
-    speed: u16,                         // Speed of car in kilometres per hour
-    throttle: f32,                      // Amount of throttle applied (0.0 to 1.0)
-    steer: f32,                         // Steering (-1.0 (full lock left) to 1.0 (full lock right))
-    brake: f32,                         // Amount of brake applied (0.0 to 1.0)
-    clutch: u8,                         // Amount of clutch applied (0 to 100)
-    gear: i8,                           // Gear selected (1-8, N=0, R=-1)
-    engine_rpm: u16,                    // Engine RPM
-    drs: u8,                            // 0 = off, 1 = on
-    rev_lights_percent: u8,             // Rev lights indicator (percentage)
-    rev_lights_bit_value: u16,          // Rev lights (bit 0 = leftmost LED, bit 14 = rightmost LED)
-    brakes_temperature: [u16; 4],       // Brakes temperature (celsius)
-    tyres_surface_temperature: [u8; 4], // Tyres surface temperature (celsius)
-    tyres_inner_temperature: [u8; 4],   // Tyres inner temperature (celsius)
-    engine_temperature: u16,            // Engine temperature (celsius)
-    tyres_pressure: [f32; 4],           // Tyres pressure (PSI)
-    surface_type: [SurfaceType; 4],     // Driving surface, see appendices
+pub(crate) struct CarTelemetryData {
+    pub(crate) speed: u16,                   // Speed of car in kilometres per hour
+    pub(crate) throttle: f32,                // Amount of throttle applied (0.0 to 1.0)
+    pub(crate) steer: f32, // Steering (-1.0 (full lock left) to 1.0 (full lock right))
+    pub(crate) brake: f32, // Amount of brake applied (0.0 to 1.0)
+    pub(crate) clutch: u8, // Amount of clutch applied (0 to 100)
+    pub(crate) gear: i8,   // Gear selected (1-8, N=0, R=-1)
+    pub(crate) engine_rpm: u16, // Engine RPM
+    pub(crate) drs: u8,    // 0 = off, 1 = on
+    pub(crate) rev_lights_percent: u8, // Rev lights indicator (percentage)
+    pub(crate) rev_lights_bit_value: u16, // Rev lights (bit 0 = leftmost LED, bit 14 = rightmost LED)
+    pub(crate) brakes_temperature: [u16; 4], // Brakes temperature (celsius)
+    pub(crate) tyres_surface_temperature: [u8; 4], // Tyres surface temperature (celsius)
+    pub(crate) tyres_inner_temperature: [u8; 4], // Tyres inner temperature (celsius)
+    pub(crate) engine_temperature: u16,   // Engine temperature (celsius)
+    pub(crate) tyres_pressure: [f32; 4],  // Tyres pressure (PSI)
+    pub(crate) surface_type: [SurfaceType; 4], // Driving surface, see appendices
 }
 
 /// Telemetry for all the cars in the race.
@@ -700,11 +820,10 @@ struct CarTelemetryData {
 ///
 /// Note that the rev light configurations are presented
 /// separately as well and will mimic real life driver preferences.
-#[repr(C, packed)]
 #[derive(Serialize, Deserialize, Debug, Clone, Copy)]
 pub struct PacketCarTelemetryData {
     pub header: PacketHeader, // Header
-    car_telemetry_data: [CarTelemetryData; 22],
+    pub(crate) car_telemetry_data: [CarTelemetryData; 22],
     pub mfd_panel_index: u8, // Index of MFD panel open - 255 = MFD closed
     // Single player, race – 0 = Car setup, 1 = Pits
     // 2 = Damage, 3 =  Engine, 4 = Temperatures
@@ -714,160 +833,182 @@ pub struct PacketCarTelemetryData {
                                               // 0 if no gear suggested
 }
 
-#[repr(C, packed)]
 #[derive(Serialize, Deserialize, Debug, Clone, Copy)]
-struct CarStatusData {
-    traction_control: u8,         // Traction control - 0 = off, 1 = medium, 2 = full
-    anti_lock_brakes: u8,         // 0 (off) - 1 (on)
-    fuel_mix: u8,                 // Fuel mix - 0 = lean, 1 = standard, 2 = rich, 3 = max
-    front_brake_bias: u8,         // Front brake bias (percentage)
-    pit_limiter_status: u8,       // Pit limiter status - 0 = off, 1 = on
-    fuel_in_tank: f32,            // Current fuel mass
-    fuel_capacity: f32,           // Fuel capacity
-    fuel_remaining_laps: f32,     // Fuel remaining in terms of laps (value on MFD)
-    max_rpm: u16,                 // Cars max RPM, point of rev limiter
-    idle_rpm: u16,                // Cars idle RPM
-    max_gears: u8,                // Maximum number of gears
-    drs_allowed: u8,              // 0 = not allowed, 1 = allowed
-    drs_activation_distance: u16, // 0 = DRS not available, non-zero - DRS will be available in [X] metres
-    actual_tyre_compound: u8,     // F1 Modern - 16 = C5, 17 = C4, 18 = C3, 19 = C2, 20 = C1
+pub(crate) struct CarStatusData {
+    pub(crate) traction_control: u8, // Traction control - 0 = off, 1 = medium, 2 = full
+    pub(crate) anti_lock_brakes: u8, // 0 (off) - 1 (on)
+    pub(crate) fuel_mix: u8,         // Fuel mix - 0 = lean, 1 = standard, 2 = rich, 3 = max
+    pub(crate) front_brake_bias: u8, // Front brake bias (percentage)
+    pub(crate) pit_limiter_status: u8, // Pit limiter status - 0 = off, 1 = on
+    pub(crate) fuel_in_tank: f32,    // Current fuel mass
+    pub(crate) fuel_capacity: f32,   // Fuel capacity
+    pub(crate) fuel_remaining_laps: f32, // Fuel remaining in terms of laps (value on MFD)
+    pub(crate) max_rpm: u16,         // Cars max RPM, point of rev limiter
+    pub(crate) idle_rpm: u16,        // Cars idle RPM
+    pub(crate) max_gears: u8,        // Maximum number of gears
+    pub(crate) drs_allowed: u8,      // 0 = not allowed, 1 = allowed
+    pub(crate) drs_activation_distance: u16, // 0 = DRS not available, non-zero - DRS will be available in [X] metres
+    pub(crate) actual_tyre_compound: u8, // F1 Modern - 16 = C5, 17 = C4, 18 = C3, 19 = C2, 20 = C1
     // 7 = inter, 8 = wet
     // F1 Classic - 9 = dry, 10 = wet
     // F2 – 11 = super soft, 12 = soft, 13 = medium, 14 = hard
     // 15 = wet
-    visual_tyre_compound: u8, // F1 visual (can be different from actual compound)
+    pub(crate) visual_tyre_compound: u8, // F1 visual (can be different from actual compound)
     // 16 = soft, 17 = medium, 18 = hard, 7 = inter, 8 = wet
     // F1 Classic – same as above
     // F2 ‘19, 15 = wet, 19 – super soft, 20 = soft
     // 21 = medium , 22 = hard
-    tyres_age_laps: u8,    // Age in laps of the current set of tyres
-    vehicle_fia_flags: i8, // -1 = invalid/unknown, 0 = none, 1 = green
+    pub(crate) tyres_age_laps: u8, // Age in laps of the current set of tyres
+    pub(crate) vehicle_fia_flags: i8, // -1 = invalid/unknown, 0 = none, 1 = green
     // 2 = blue, 3 = yellow, 4 = red
-    ers_store_energy: f32, // ERS energy store in Joules
-    ers_deploy_mode: u8,   // ERS deployment mode, 0 = none, 1 = medium
+    pub(crate) engine_power_ice: f32, // F1 23+, engine power output of ICE (W)
+    pub(crate) engine_power_mguk: f32, // F1 23+, engine power output of MGU-K (W)
+    pub(crate) ers_store_energy: f32, // ERS energy store in Joules
+    pub(crate) ers_deploy_mode: u8,   // ERS deployment mode, 0 = none, 1 = medium
     // 2 = hotlap, 3 = overtake
-    ers_harvested_this_lap_mguk: f32, // ERS energy harvested this lap by MGU-K
-    ers_harvested_this_lap_mguh: f32, // ERS energy harvested this lap by MGU-H
-    ers_deployed_this_lap: f32,       // ERS energy deployed this lap
-    network_paused: u8,               // Whether the car is paused in a network game
+    pub(crate) ers_harvested_this_lap_mguk: f32, // ERS energy harvested this lap by MGU-K
+    pub(crate) ers_harvested_this_lap_mguh: f32, // ERS energy harvested this lap by MGU-H
+    pub(crate) ers_deployed_this_lap: f32,       // ERS energy deployed this lap
+    pub(crate) network_paused: u8,               // Whether the car is paused in a network game
 }
 
-#[repr(C, packed)]
 #[derive(Serialize, Deserialize, Debug, Clone, Copy)]
 pub struct PacketCarStatusData {
     pub header: PacketHeader, // Header
-    car_status_data: [CarStatusData; 22],
+    pub(crate) car_status_data: [CarStatusData; 22],
 }
 
-#[repr(C, packed)]
 #[derive(Serialize, Deserialize, Debug, Clone, Copy)]
-struct FinalClassificationData {
-    position: u8,      // Finishing position
-    num_laps: u8,      // Number of laps completed
-    grid_position: u8, // Grid position of the car
-    points: u8,        // Number of points scored
-    num_pit_stops: u8, // Number of pit stops made
-    result_status: u8, // Result status - 0 = invalid, 1 = inactive, 2 = active
+pub(crate) struct FinalClassificationData {
+    pub(crate) position: u8,      // Finishing position
+    pub(crate) num_laps: u8,      // Number of laps completed
+    pub(crate) grid_position: u8, // Grid position of the car
+    pub(crate) points: u8,        // Number of points scored
+    pub(crate) num_pit_stops: u8, // Number of pit stops made
+    pub(crate) result_status: u8, // Result status - 0 = invalid, 1 = inactive, 2 = active
     // 3 = finished, 4 = didnotfinish, 5 = disqualified
     // 6 = not classified, 7 = retired
-    best_lap_time_in_ms: u32, // Best lap time of the session in milliseconds
-    total_race_time: f64,     // Total race time in seconds without penalties
-    penalties_time: u8,       // Total penalties accumulated in seconds
-    num_penalties: u8,        // Number of penalties applied to this driver
-    num_tyre_stints: u8,      // Number of tyres stints up to maximum
-    tyre_stints_actual: [u8; 8], // Actual tyres used by this driver
-    tyre_stints_visual: [u8; 8], // Visual tyres used by this driver
-    tyre_stints_end_laps: [u8; 8], // The lap number stints end on
+    pub(crate) result_reason: u8, // F1 25+, result reason - 0 = invalid, 1 = retired, 2 = finished
+    // 3 = terminal damage, 4 = inactive, 5 = not enough laps completed
+    // 6 = black flagged, 7 = red flagged, 8 = mechanical failure
+    // 9 = session skipped, 10 = session simulated
+    pub(crate) best_lap_time_in_ms: u32, // Best lap time of the session in milliseconds
+    pub(crate) total_race_time: f64,     // Total race time in seconds without penalties
+    pub(crate) penalties_time: u8,       // Total penalties accumulated in seconds
+    pub(crate) num_penalties: u8,        // Number of penalties applied to this driver
+    pub(crate) num_tyre_stints: u8,      // Number of tyres stints up to maximum
+    pub(crate) tyre_stints_actual: [u8; 8], // Actual tyres used by this driver
+    pub(crate) tyre_stints_visual: [u8; 8], // Visual tyres used by this driver
+    pub(crate) tyre_stints_end_laps: [u8; 8], // The lap number stints end on
 }
 
 /// This packet details the final classification at the end of the race,
 /// and the data will match with the post race results screen.
-#[repr(C, packed)]
 #[derive(Serialize, Deserialize, Debug, Clone, Copy)]
 pub struct PacketFinalClassificationData {
     pub header: PacketHeader, // Header
     pub num_cars: u8,         // Number of cars in the final classification
-    classification_data: [FinalClassificationData; 22],
+    pub(crate) classification_data: [FinalClassificationData; 22],
 }
 
-#[repr(C, packed)]
 #[serde_as]
 #[derive(Serialize, Deserialize, Debug, Clone, Copy)]
-struct LobbyInfoData {
-    ai_controlled: u8,          // Whether the vehicle is AI (1) or Human (0) controlled
-    team_id: TeamId,            // Team id - see appendix (255 if no team currently selected)
-    nationality: NationalityId, // Nationality of the driver
+pub(crate) struct LobbyInfoData {
+    pub(crate) ai_controlled: u8, // Whether the vehicle is AI (1) or Human (0) controlled
+    pub(crate) team_id: TeamId,   // Team id - see appendix (255 if no team currently selected)
+    pub(crate) nationality: NationalityId, // Nationality of the driver
+    pub(crate) platform: u8, // F1 23+, 1 = Steam, 3 = PlayStation, 4 = Xbox, 6 = Origin, 255 = unknown
 
     #[serde_as(as = "[_; 48]")]
-    name: [u8; 48], // Name of participant in UTF-8 format – null terminated Truncated with ... (U+2026) if too long
-    car_number: u8,   // Car number of the player
-    ready_status: u8, // 0 = not ready, 1 = ready, 2 = spectating
+    pub(crate) name: [u8; 48], // Name of participant in UTF-8 format – null terminated Truncated with ... (U+2026) if too long
+    // 32 bytes max in F1 25+
+    pub(crate) car_number: u8,        // Car number of the player
+    pub(crate) your_telemetry: u8, // F1 24+, the player's UDP setting, 0 = restricted, 1 = public
+    pub(crate) show_online_names: u8, // F1 24+, the player's show online names setting, 0 = off, 1 = on
+    pub(crate) tech_level: u16,       // F1 24+, F1 World tech level
+    pub(crate) ready_status: u8,      // 0 = not ready, 1 = ready, 2 = spectating
+}
+
+impl Default for LobbyInfoData {
+    fn default() -> Self {
+        Self {
+            ai_controlled: 0,
+            team_id: TeamId::default(),
+            nationality: NationalityId::default(),
+            platform: 0,
+            name: [0; 48],
+            car_number: 0,
+            your_telemetry: 0,
+            show_online_names: 0,
+            tech_level: 0,
+            ready_status: 0,
+        }
+    }
 }
 
 /// This packet details the players currently in a multiplayer lobby.
 /// It details each player’s selected car,
 /// any AI involved in the game and also the ready status of each of the participants.
-#[repr(C, packed)]
 #[derive(Serialize, Deserialize, Debug, Clone, Copy)]
 pub struct PacketLobbyInfoData {
     pub header: PacketHeader,
 
     // Packet specific data
     pub num_players: u8, // Number of players in the lobby data
-    lobby_players: [LobbyInfoData; 22],
+    pub(crate) lobby_players: [LobbyInfoData; 22],
 }
 
-#[repr(C, packed)]
 #[derive(Serialize, Deserialize, Debug, Clone, Copy)]
-struct CarDamageData {
-    tyres_wear: [f32; 4],        // Tyre wear (percentage)
-    tyres_damage: [u8; 4],       // Tyre damage (percentage)
-    brakes_damage: [u8; 4],      // Brakes damage (percentage)
-    front_left_wing_damage: u8,  // Front left wing damage (percentage)
-    front_right_wing_damage: u8, // Front right wing damage (percentage)
-    rear_wing_damage: u8,        // Rear wing damage (percentage)
-    floor_damage: u8,            // Floor damage (percentage)
-    diffuser_damage: u8,         // Diffuser damage (percentage)
-    sidepod_damage: u8,          // Sidepod damage (percentage)
-    drs_fault: u8,               // Indicator for DRS fault, 0 = OK, 1 = fault
-    ers_fault: u8,               // Indicator for ERS fault, 0 = OK, 1 = fault
-    gear_box_damage: u8,         // Gear box damage (percentage)
-    engine_damage: u8,           // Engine damage (percentage)
-    engine_mguh_wear: u8,        // Engine wear MGU-H (percentage)
-    engine_es_wear: u8,          // Engine wear ES (percentage)
-    engine_ce_wear: u8,          // Engine wear CE (percentage)
-    engine_ice_wear: u8,         // Engine wear ICE (percentage)
-    engine_mguk_wear: u8,        // Engine wear MGU-K (percentage)
-    engine_tc_wear: u8,          // Engine wear TC (percentage)
-    engine_blown: u8,            // Engine blown, 0 = OK, 1 = fault
-    engine_seized: u8,           // Engine seized, 0 = OK, 1 = fault
+pub(crate) struct CarDamageData {
+    pub(crate) tyres_wear: [f32; 4],        // Tyre wear (percentage)
+    pub(crate) tyres_damage: [u8; 4],       // Tyre damage (percentage)
+    pub(crate) brakes_damage: [u8; 4],      // Brakes damage (percentage)
+    pub(crate) tyre_blisters: [u8; 4],      // F1 25+, tyre blisters value (percentage)
+    pub(crate) front_left_wing_damage: u8,  // Front left wing damage (percentage)
+    pub(crate) front_right_wing_damage: u8, // Front right wing damage (percentage)
+    pub(crate) rear_wing_damage: u8,        // Rear wing damage (percentage)
+    pub(crate) floor_damage: u8,            // Floor damage (percentage)
+    pub(crate) diffuser_damage: u8,         // Diffuser damage (percentage)
+    pub(crate) sidepod_damage: u8,          // Sidepod damage (percentage)
+    pub(crate) drs_fault: u8,               // Indicator for DRS fault, 0 = OK, 1 = fault
+    pub(crate) ers_fault: u8,               // Indicator for ERS fault, 0 = OK, 1 = fault
+    pub(crate) gear_box_damage: u8,         // Gear box damage (percentage)
+    pub(crate) engine_damage: u8,           // Engine damage (percentage)
+    pub(crate) engine_mguh_wear: u8,        // Engine wear MGU-H (percentage)
+    pub(crate) engine_es_wear: u8,          // Engine wear ES (percentage)
+    pub(crate) engine_ce_wear: u8,          // Engine wear CE (percentage)
+    pub(crate) engine_ice_wear: u8,         // Engine wear ICE (percentage)
+    pub(crate) engine_mguk_wear: u8,        // Engine wear MGU-K (percentage)
+    pub(crate) engine_tc_wear: u8,          // Engine wear TC (percentage)
+    pub(crate) engine_blown: u8,            // Engine blown, 0 = OK, 1 = fault
+    pub(crate) engine_seized: u8,           // Engine seized, 0 = OK, 1 = fault
 }
 
 /// This packet details car damage parameters for all the cars in the race.
-#[repr(C, packed)]
 #[derive(Serialize, Deserialize, Debug, Clone, Copy)]
 pub struct PacketCarDamageData {
     pub header: PacketHeader,
-    car_damage_data: [CarDamageData; 22],
+    pub(crate) car_damage_data: [CarDamageData; 22],
 }
 
-#[repr(C, packed)]
 #[derive(Serialize, Deserialize, Debug, Clone, Copy)]
-struct LapHistoryData {
-    lap_time_in_ms: u32,     // Lap time in milliseconds
-    sector1_time_in_ms: u16, // Sector 1 time in milliseconds
-    sector2_time_in_ms: u16, // Sector 2 time in milliseconds
-    sector3_time_in_ms: u16, // Sector 3 time in milliseconds
-    lap_valid_bit_flags: u8, // 0x01 bit set-lap valid, 0x02 bit set-sector 1 valid
-                             // 0x04 bit set-sector 2 valid, 0x08 bit set-sector 3 valid
+pub(crate) struct LapHistoryData {
+    pub(crate) lap_time_in_ms: u32,      // Lap time in milliseconds
+    pub(crate) sector1_time_in_ms: u16,  // Sector 1 time in milliseconds
+    pub(crate) sector1_time_minutes: u8, // F1 23+, sector 1 whole minute part
+    pub(crate) sector2_time_in_ms: u16,  // Sector 2 time in milliseconds
+    pub(crate) sector2_time_minutes: u8, // F1 23+, sector 2 whole minute part
+    pub(crate) sector3_time_in_ms: u16,  // Sector 3 time in milliseconds
+    pub(crate) sector3_time_minutes: u8, // F1 23+, sector 3 whole minute part
+    pub(crate) lap_valid_bit_flags: u8,  // 0x01 bit set-lap valid, 0x02 bit set-sector 1 valid
+                                         // 0x04 bit set-sector 2 valid, 0x08 bit set-sector 3 valid
 }
 
-#[repr(C, packed)]
 #[derive(Serialize, Deserialize, Debug, Clone, Copy)]
-struct TyreStintHistoryData {
-    end_lap: u8,              // Lap the tyre usage ends on (255 of current tyre)
-    tyre_actual_compound: u8, // Actual tyres used by this driver
-    tyre_visual_compound: u8, // Visual tyres used by this driver
+pub(crate) struct TyreStintHistoryData {
+    pub(crate) end_lap: u8, // Lap the tyre usage ends on (255 of current tyre)
+    pub(crate) tyre_actual_compound: u8, // Actual tyres used by this driver
+    pub(crate) tyre_visual_compound: u8, // Visual tyres used by this driver
 }
 
 /// This packet contains lap times and tyre usage for the session.
@@ -877,7 +1018,6 @@ struct TyreStintHistoryData {
 /// and the vehicle being sent is cycled through.
 ///
 /// Therefore in a 20 car race you should receive an update for each vehicle at least once per second.
-#[repr(C, packed)]
 #[serde_as]
 #[derive(Serialize, Deserialize, Debug, Clone, Copy)]
 pub struct PacketSessionHistoryData {
@@ -893,8 +1033,78 @@ pub struct PacketSessionHistoryData {
     pub best_sector3_lap_num: u8,  // Lap the best Sector 3 time was achieved on
 
     #[serde_as(as = "[_; 100]")]
-    lap_history_data: [LapHistoryData; 100], // 100 laps of data max
-    tyre_stints_history_data: [TyreStintHistoryData; 8],
+    pub(crate) lap_history_data: [LapHistoryData; 100], // 100 laps of data max
+    pub(crate) tyre_stints_history_data: [TyreStintHistoryData; 8],
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy)]
+pub(crate) struct TyreSetData {
+    pub(crate) actual_tyre_compound: u8, // Actual tyre compound used
+    pub(crate) visual_tyre_compound: u8, // Visual tyre compound used
+    pub(crate) wear: u8,                 // Tyre wear (percentage)
+    pub(crate) available: u8,            // Whether this set is currently available
+    pub(crate) recommended_session: u8,  // Recommended session for tyre set, see appendix
+    pub(crate) life_span: u8,            // Laps left in this tyre set
+    pub(crate) usable_life: u8,          // Max number of laps recommended for this compound
+    pub(crate) lap_delta_time: i16,      // Lap delta time in milliseconds compared to fitted set
+    pub(crate) fitted: u8,               // Whether the set is fitted or not
+}
+
+/// This packet gives a more in-depth details about tyre sets assigned
+/// to a vehicle during the session. F1 23+
+///
+/// Each packet relates to a specific vehicle, the vehicle being sent
+/// is cycled through in the same way as [`PacketSessionHistoryData`].
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub struct PacketTyreSetsData {
+    pub header: PacketHeader, // Header
+
+    pub car_idx: u8, // Index of the car this data relates to
+    pub(crate) tyre_set_data: [TyreSetData; 20], // 13 (dry) + 7 (wet)
+    pub fitted_idx: u8, // Index into array of fitted tyre
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub(crate) struct TimeTrialDataSet {
+    pub(crate) car_idx: u8,               // Index of the car this data relates to
+    pub(crate) team_id: TeamId,           // Team id - see appendix
+    pub(crate) lap_time_in_ms: u32,       // Lap time in milliseconds
+    pub(crate) sector1_time_in_ms: u32,   // Sector 1 time in milliseconds
+    pub(crate) sector2_time_in_ms: u32,   // Sector 2 time in milliseconds
+    pub(crate) sector3_time_in_ms: u32,   // Sector 3 time in milliseconds
+    pub(crate) traction_control: u8,      // 0 = off, 1 = medium, 2 = full
+    pub(crate) gearbox_assist: u8,        // 1 = manual, 2 = manual & suggested gear, 3 = auto
+    pub(crate) anti_lock_brakes: u8,      // 0 (off) - 1 (on)
+    pub(crate) equal_car_performance: u8, // 0 = Realistic, 1 = Equal
+    pub(crate) custom_setup: u8,          // 0 = No, 1 = Yes
+    pub(crate) valid: u8,                 // 0 = invalid, 1 = valid
+}
+
+/// This packet details the time trial best laps. F1 24+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub struct PacketTimeTrialData {
+    pub header: PacketHeader, // Header
+
+    pub(crate) player_session_best_data_set: TimeTrialDataSet, // Player session best data set
+    pub(crate) personal_best_data_set: TimeTrialDataSet,       // Personal best data set
+    pub(crate) rival_data_set: TimeTrialDataSet,               // Rival data set
+}
+
+/// This packet details the position of every car at the end
+/// of each lap, to build a lap chart. F1 25+
+///
+/// Each packet holds up to 50 laps starting from `lap_start`.
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub struct PacketLapPositionsData {
+    pub header: PacketHeader, // Header
+
+    pub num_laps: u8,  // Number of laps in the data
+    pub lap_start: u8, // Index of the lap where the data starts, 0 indexed
+
+    // Array holding the position of the car in a given lap, 0 if no record
+    #[serde_as(as = "[[_; 22]; 50]")]
+    pub(crate) position_for_vehicle_idx: [[u8; 22]; 50],
 }
 
 /// A generic enum representing all possible F1 telemetry packet types
@@ -912,6 +1122,10 @@ pub enum TelemetryPacket {
     LobbyInfo(PacketLobbyInfoData),
     CarDamage(PacketCarDamageData),
     SessionHistory(PacketSessionHistoryData),
+    TyreSets(PacketTyreSetsData),
+    MotionEx(PacketMotionExData),
+    TimeTrial(PacketTimeTrialData),
+    LapPositions(PacketLapPositionsData),
 }
 
 impl TelemetryPacket {
@@ -930,6 +1144,10 @@ impl TelemetryPacket {
             TelemetryPacket::LobbyInfo(packet) => &packet.header,
             TelemetryPacket::CarDamage(packet) => &packet.header,
             TelemetryPacket::SessionHistory(packet) => &packet.header,
+            TelemetryPacket::TyreSets(packet) => &packet.header,
+            TelemetryPacket::MotionEx(packet) => &packet.header,
+            TelemetryPacket::TimeTrial(packet) => &packet.header,
+            TelemetryPacket::LapPositions(packet) => &packet.header,
         }
     }
 
@@ -948,6 +1166,10 @@ impl TelemetryPacket {
             TelemetryPacket::LobbyInfo(_) => "Lobby Info",
             TelemetryPacket::CarDamage(_) => "Car Damage",
             TelemetryPacket::SessionHistory(_) => "Session History",
+            TelemetryPacket::TyreSets(_) => "Tyre Sets",
+            TelemetryPacket::MotionEx(_) => "Motion Ex",
+            TelemetryPacket::TimeTrial(_) => "Time Trial",
+            TelemetryPacket::LapPositions(_) => "Lap Positions",
         }
     }
 
@@ -960,7 +1182,7 @@ impl TelemetryPacket {
 
     /// Get the F1 CodeMasters format of the packet
     ///
-    /// For example: 2022, 2021, 2020
+    /// For example: 2025, 2024, 2023, 2022
     pub fn format(&self) -> u16 {
         self.header().packet_format
     }
@@ -1075,4 +1297,35 @@ impl TelemetryPacket {
             None
         }
     }
+    pub fn as_tyre_sets(&self) -> Option<&PacketTyreSetsData> {
+        if let TelemetryPacket::TyreSets(p) = self {
+            Some(p)
+        } else {
+            None
+        }
+    }
+
+    pub fn as_motion_ex(&self) -> Option<&PacketMotionExData> {
+        if let TelemetryPacket::MotionEx(p) = self {
+            Some(p)
+        } else {
+            None
+        }
+    }
+
+    pub fn as_time_trial(&self) -> Option<&PacketTimeTrialData> {
+        if let TelemetryPacket::TimeTrial(p) = self {
+            Some(p)
+        } else {
+            None
+        }
+    }
+
+    pub fn as_lap_positions(&self) -> Option<&PacketLapPositionsData> {
+        if let TelemetryPacket::LapPositions(p) = self {
+            Some(p)
+        } else {
+            None
+        }
+    }
 }
@@ -0,0 +1,206 @@
+use serde::{Deserialize, Serialize};
+
+use crate::core::{Driver, LobbyPlayer, RaceStateChange, TelemetryPacket, MAX_CARS};
+
+/// How the driver of a car changed between two Participants packets
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ParticipantChange {
+    /// A driver appeared in a car that was empty
+    Joined,
+    Left,
+
+    /// A different driver, or the same driver with a new name
+    DriverChanged,
+
+    /// The AI took over from a human, e.g. after a disconnect
+    AiTakeover,
+
+    /// A human took over from the AI, e.g. joining mid-session
+    HumanTakeover,
+}
+
+/// A driver's time in a car
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DriverStint {
+    pub driver: Driver,
+
+    /// Session time the driver was first seen in the car
+    pub from: f32,
+
+    /// Session time the driver left the car, None while still in it
+    pub until: Option<f32>,
+}
+
+impl DriverStint {
+    fn covers(&self, session_time: f32) -> bool {
+        self.from <= session_time && self.until.is_none_or(|until| session_time < until)
+    }
+}
+
+/// Tracks who is driving every car, from every Participants and
+/// Lobby Info packet, along with who drove each car before.
+#[derive(Debug, Clone)]
+pub struct Participants {
+    num_active_cars: u8,
+
+    /// Current driver of every car slot, indexed by vehicle index
+    drivers: Vec<Option<Driver>>,
+
+    /// Players in the multiplayer lobby, empty outside of one
+    lobby: Vec<LobbyPlayer>,
+
+    /// Every driver seen in the session, in the order they got in a car
+    history: Vec<DriverStint>,
+}
+
+impl Default for Participants {
+    fn default() -> Self {
+        Self {
+            num_active_cars: 0,
+            drivers: vec![None; MAX_CARS],
+            lobby: Vec::new(),
+            history: Vec::new(),
+        }
+    }
+}
+
+impl Participants {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Forget every driver, e.g. when a new session starts
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    pub fn num_active_cars(&self) -> u8 {
+        self.num_active_cars
+    }
+
+    /// The current driver of the car at `vehicle_idx`, if any
+    pub fn driver(&self, vehicle_idx: u8) -> Option<&Driver> {
+        self.drivers.get(vehicle_idx as usize)?.as_ref()
+    }
+
+    /// Current drivers, in vehicle index order
+    pub fn drivers(&self) -> impl Iterator<Item = &Driver> {
+        self.drivers.iter().flatten()
+    }
+
+    /// Name of the current driver of the car at `vehicle_idx`
+    pub fn name(&self, vehicle_idx: u8) -> Option<&str> {
+        self.driver(vehicle_idx).map(|driver| driver.name.as_str())
+    }
+
+    pub fn lobby(&self) -> &[LobbyPlayer] {
+        &self.lobby
+    }
+
+    /// Every driver seen in the session, in the order they got in a car
+    pub fn history(&self) -> &[DriverStint] {
+        &self.history
+    }
+
+    /// Every driver of the car at `vehicle_idx`, oldest first
+    pub fn stints(&self, vehicle_idx: u8) -> impl Iterator<Item = &DriverStint> {
+        self.history
+            .iter()
+            .filter(move |stint| stint.driver.vehicle_idx == vehicle_idx)
+    }
+
+    /// Who was driving the car at `vehicle_idx` at `session_time`
+    pub fn driver_at(&self, vehicle_idx: u8, session_time: f32) -> Option<&Driver> {
+        self.stints(vehicle_idx)
+            .find(|stint| stint.covers(session_time))
+            .map(|stint| &stint.driver)
+    }
+
+    /// Apply a Participants or Lobby Info packet.
+    ///
+    /// Returns what changed, other packets are ignored.
+    pub fn ingest(&mut self, packet: &TelemetryPacket) -> Vec<RaceStateChange> {
+        let mut changes = Vec::new();
+
+        if let Some(lobby) = packet.as_lobby_info() {
+            let players = lobby.players();
+            if players != self.lobby {
+                self.lobby = players;
+                changes.push(RaceStateChange::Lobby);
+            }
+        }
+
+        let Some(participants) = packet.as_participants() else {
+            return changes;
+        };
+
+        let session_time = participants.header.session_time;
+        self.num_active_cars = participants.num_active_cars;
+
+        for vehicle_idx in 0..MAX_CARS as u8 {
+            let driver = participants
+                .driver(vehicle_idx)
+                .filter(|driver| !driver.name.is_empty());
+            let previous = self.drivers[vehicle_idx as usize].clone();
+
+            let change = match (&previous, &driver) {
+                (None, None) => continue,
+                (Some(previous), Some(driver)) if previous == driver => continue,
+                (None, Some(_)) => ParticipantChange::Joined,
+                (Some(_), None) => ParticipantChange::Left,
+                (Some(previous), Some(driver)) => {
+                    match (previous.ai_controlled, driver.ai_controlled) {
+                        (false, true) => ParticipantChange::AiTakeover,
+                        (true, false) => ParticipantChange::HumanTakeover,
+
+                        // Same driver, e.g. the team or telemetry setting
+                        // changed, so no new stint is started
+                        _ if previous.name == driver.name
+                            && previous.network_id == driver.network_id
+                            && previous.driver_id == driver.driver_id =>
+                        {
+                            self.update_driver(driver.clone());
+                            continue;
+                        }
+                        _ => ParticipantChange::DriverChanged,
+                    }
+                }
+            };
+
+            if let Some(stint) = self.current_stint(vehicle_idx) {
+                stint.until = Some(session_time);
+            }
+
+            if let Some(driver) = &driver {
+                self.history.push(DriverStint {
+                    driver: driver.clone(),
+                    from: session_time,
+                    until: None,
+                });
+            }
+
+            self.drivers[vehicle_idx as usize] = driver;
+            changes.push(RaceStateChange::Participant {
+                vehicle_idx,
+                change,
+            });
+        }
+
+        changes
+    }
+
+    fn current_stint(&mut self, vehicle_idx: u8) -> Option<&mut DriverStint> {
+        self.history
+            .iter_mut()
+            .rev()
+            .find(|stint| stint.driver.vehicle_idx == vehicle_idx && stint.until.is_none())
+    }
+
+    fn update_driver(&mut self, driver: Driver) {
+        let vehicle_idx = driver.vehicle_idx;
+        if let Some(stint) = self.current_stint(vehicle_idx) {
+            stint.driver = driver.clone();
+        }
+        self.drivers[vehicle_idx as usize] = Some(driver);
+    }
+}
@@ -0,0 +1,229 @@
+use serde::{Deserialize, Serialize};
+
+use crate::core::{Car, LifecycleEvent, ParticipantChange, SessionInfo, TelemetryPacket, Weather};
+
+/// Max number of cars in a session
+pub const MAX_CARS: usize = 22;
+
+/// Which part of the [`RaceState`] was changed by a packet.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RaceStateChange {
+    /// A packet from a different session was received,
+    /// and the state was reset for the new session.
+    NewSession,
+    Session,
+    Weather,
+    Drivers,
+    Laps,
+
+    /// The running order changed. Sent along with `Laps`.
+    Positions,
+    Telemetry,
+    Status,
+    Damage,
+    Motion,
+    TyreSets {
+        vehicle_idx: u8,
+    },
+
+    /// The driver of a car changed, see [`Participants`](crate::core::Participants)
+    Participant {
+        vehicle_idx: u8,
+        change: ParticipantChange,
+    },
+
+    /// The players in the multiplayer lobby changed
+    Lobby,
+
+    /// A car's lap and tyre history was updated, see [`HistoryAssembler`](crate::core::HistoryAssembler)
+    History {
+        vehicle_idx: u8,
+    },
+
+    /// The history of every car in the session has been received
+    HistoryComplete,
+
+    /// A session started or ended, see [`SessionLifecycle`](crate::core::SessionLifecycle)
+    Lifecycle(LifecycleEvent),
+}
+
+/// A change to the [`RaceState`], published on the [`PacketBus`](crate::core::PacketBus).
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub struct RaceStateUpdate {
+    pub change: RaceStateChange,
+
+    /// Frame the change happened on
+    pub frame_identifier: u32,
+    pub session_time: f32,
+}
+
+/// A copy of everything known about the current session.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RaceSnapshot {
+    pub session_uid: u64,
+    pub packet_format: u16,
+
+    /// Frame of the latest packet applied. Goes back after a flashback.
+    pub frame_identifier: u32,
+    pub session_time: f32,
+    pub player_car_index: u8,
+    pub num_active_cars: u8,
+    pub session: Option<SessionInfo>,
+    pub weather: Option<Weather>,
+
+    /// Every car slot, indexed by vehicle index
+    pub cars: Vec<Car>,
+}
+
+impl Default for RaceSnapshot {
+    fn default() -> Self {
+        Self {
+            session_uid: 0,
+            packet_format: 0,
+            frame_identifier: 0,
+            session_time: 0.0,
+            player_car_index: 0,
+            num_active_cars: 0,
+            session: None,
+            weather: None,
+            cars: (0..MAX_CARS as u8).map(Car::new).collect(),
+        }
+    }
+}
+
+impl RaceSnapshot {
+    pub fn car(&self, vehicle_idx: u8) -> Option<&Car> {
+        self.cars.get(vehicle_idx as usize)
+    }
+
+    /// Cars with a driver, in vehicle index order
+    pub fn active_cars(&self) -> impl Iterator<Item = &Car> {
+        self.cars.iter().filter(|car| car.driver.is_some())
+    }
+
+    /// The car being driven by the player, if known
+    pub fn player_car(&self) -> Option<&Car> {
+        self.car(self.player_car_index)
+    }
+
+    /// Vehicle indexes of active cars ordered by race position
+    pub fn running_order(&self) -> Vec<u8> {
+        let mut order: Vec<(u8, u8)> = self
+            .active_cars()
+            .filter_map(|car| Some((car.lap.as_ref()?.position, car.vehicle_idx)))
+            .filter(|(position, _)| *position > 0)
+            .collect();
+        order.sort();
+
+        order.into_iter().map(|(_, idx)| idx).collect()
+    }
+}
+
+/// The live state of a race, fused from every telemetry packet received.
+///
+/// All parts of Solis (UI, engineer, strategy) read the same state from
+/// here instead of interpreting packets themselves. Packets are applied
+/// in the order they are received with [`RaceState::ingest`], and the
+/// changes they make are published on the session's [`PacketBus`](crate::core::PacketBus).
+#[derive(Debug, Default)]
+pub struct RaceState {
+    snapshot: RaceSnapshot,
+}
+
+impl RaceState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The current state. Clone it to keep a copy
+    /// that is not changed by later packets.
+    pub fn snapshot(&self) -> &RaceSnapshot {
+        &self.snapshot
+    }
+
+    /// Forget everything about the current session. The next
+    /// packet starts a new state and sends `NewSession`.
+    pub fn reset(&mut self) {
+        self.snapshot = RaceSnapshot::default();
+    }
+
+    /// Apply a packet to the state.
+    ///
+    /// Returns the changes made by the packet.
+    pub fn ingest(&mut self, packet: &TelemetryPacket) -> Vec<RaceStateChange> {
+        let header = packet.header();
+        let mut changes = Vec::new();
+
+        if header.session_uid != self.snapshot.session_uid {
+            self.snapshot = RaceSnapshot {
+                session_uid: header.session_uid,
+                ..Default::default()
+            };
+            changes.push(RaceStateChange::NewSession);
+        }
+
+        let state = &mut self.snapshot;
+        state.packet_format = header.packet_format;
+        state.frame_identifier = header.frame_identifier;
+        state.session_time = header.session_time;
+        state.player_car_index = header.player_car_index;
+
+        match packet {
+            TelemetryPacket::Session(session) => {
+                let info = session.info();
+                if state.session.as_ref() != Some(&info) {
+                    state.session = Some(info);
+                    changes.push(RaceStateChange::Session);
+                }
+
+                let weather = session.weather();
+                if state.weather.as_ref() != Some(&weather) {
+                    state.weather = Some(weather);
+                    changes.push(RaceStateChange::Weather);
+                }
+            }
+            TelemetryPacket::Participants(participants) => {
+                state.num_active_cars = participants.num_active_cars;
+                state.cars.iter_mut().for_each(|car| {
+                    car.update(packet);
+                });
+                changes.push(RaceStateChange::Drivers);
+            }
+            TelemetryPacket::LapData(_) => {
+                let previous_order = state.running_order();
+                state.cars.iter_mut().for_each(|car| {
+                    car.update(packet);
+                });
+                changes.push(RaceStateChange::Laps);
+
+                if state.running_order() != previous_order {
+                    changes.push(RaceStateChange::Positions);
+                }
+            }
+            TelemetryPacket::TyreSets(tyre_sets) => {
+                let vehicle_idx = tyre_sets.car_idx;
+                if let Some(car) = state.cars.get_mut(vehicle_idx as usize) {
+                    car.update(packet);
+                    changes.push(RaceStateChange::TyreSets { vehicle_idx });
+                }
+            }
+            TelemetryPacket::CarTelemetry(_)
+            | TelemetryPacket::CarStatus(_)
+            | TelemetryPacket::CarDamage(_)
+            | TelemetryPacket::Motion(_) => {
+                state.cars.iter_mut().for_each(|car| {
+                    car.update(packet);
+                });
+                changes.push(match packet {
+                    TelemetryPacket::CarTelemetry(_) => RaceStateChange::Telemetry,
+                    TelemetryPacket::CarStatus(_) => RaceStateChange::Status,
+                    TelemetryPacket::CarDamage(_) => RaceStateChange::Damage,
+                    _ => RaceStateChange::Motion,
+                });
+            }
+            _ => {}
+        }
+
+        changes
+    }
+}
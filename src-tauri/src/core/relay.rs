@@ -0,0 +1,182 @@
+use std::{
+    io,
+    net::{SocketAddr, UdpSocket},
+    sync::{Arc, Mutex},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::core::{ids::PacketType, parse_header};
+
+/// A downstream tool that received datagrams are forwarded to.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct RelayTarget {
+    /// e.g 127.0.0.1:20778
+    pub address: SocketAddr,
+
+    /// Packet types to forward, or every datagram if empty
+    #[serde(default)]
+    pub packet_types: Vec<PacketType>,
+}
+
+impl RelayTarget {
+    /// Forward every datagram to `address`
+    pub fn new(address: SocketAddr) -> Self {
+        Self {
+            address,
+            packet_types: Vec::new(),
+        }
+    }
+
+    fn wants(&self, packet_type: Option<PacketType>) -> bool {
+        self.packet_types.is_empty()
+            || packet_type.is_some_and(|packet_type| self.packet_types.contains(&packet_type))
+    }
+}
+
+/// Counters for a [`Relay`]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RelayStats {
+    /// Datagrams sent, counted once per target
+    pub forwarded: u64,
+    pub failed: u64,
+}
+
+#[derive(Debug, Default)]
+struct RelayInner {
+    /// Address the datagrams being relayed are received on
+    listener: Option<SocketAddr>,
+
+    /// Created when the first target of each address family is added
+    socket_v4: Option<UdpSocket>,
+    socket_v6: Option<UdpSocket>,
+    targets: Vec<RelayTarget>,
+    stats: RelayStats,
+}
+
+impl RelayInner {
+    fn socket(&self, address: SocketAddr) -> Option<&UdpSocket> {
+        match address {
+            SocketAddr::V4(_) => self.socket_v4.as_ref(),
+            SocketAddr::V6(_) => self.socket_v6.as_ref(),
+        }
+    }
+
+    /// Whether datagrams sent to `address` would arrive back at the listener
+    fn loops_back(&self, address: SocketAddr) -> bool {
+        let Some(listener) = self.listener else {
+            return false;
+        };
+
+        let (listener_ip, ip) = (listener.ip(), address.ip());
+        listener.port() == address.port()
+            && (listener_ip == ip
+                || ip.is_unspecified()
+                || (listener_ip.is_unspecified() && ip.is_loopback()))
+    }
+}
+
+/// Re-sends every datagram received to a list of downstream targets,
+/// so other dashboards, motion rigs and loggers can run alongside Solis
+/// from the game's single UDP output.
+///
+/// Datagrams are forwarded exactly as received, before they're decoded.
+/// Clones share the same targets.
+#[derive(Debug, Clone, Default)]
+pub struct Relay {
+    inner: Arc<Mutex<RelayInner>>,
+}
+
+impl Relay {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a relay for datagrams received on `listener`,
+    /// which can't be one of its targets.
+    pub fn for_listener(listener: SocketAddr) -> Self {
+        let relay = Self::default();
+        relay.inner.lock().unwrap().listener = Some(listener);
+        relay
+    }
+
+    /// Start forwarding to `target`, replacing any
+    /// target with the same address.
+    ///
+    /// Fails if `target` is the listener's own address,
+    /// which would loop datagrams back into the listener.
+    pub fn add_target(&self, target: RelayTarget) -> io::Result<()> {
+        let mut inner = self.inner.lock().unwrap();
+        if inner.loops_back(target.address) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "can't relay to {}, packets are received there",
+                    target.address
+                ),
+            ));
+        }
+
+        match target.address {
+            SocketAddr::V4(_) if inner.socket_v4.is_none() => {
+                inner.socket_v4 = Some(UdpSocket::bind("0.0.0.0:0")?);
+            }
+            SocketAddr::V6(_) if inner.socket_v6.is_none() => {
+                inner.socket_v6 = Some(UdpSocket::bind("[::]:0")?);
+            }
+            _ => {}
+        }
+
+        inner.targets.retain(|t| t.address != target.address);
+        println!("[backend] Relaying packets to {}", target.address);
+        inner.targets.push(target);
+        Ok(())
+    }
+
+    /// Stop forwarding to `address`.
+    ///
+    /// Returns false if there was no such target.
+    pub fn remove_target(&self, address: SocketAddr) -> bool {
+        let mut inner = self.inner.lock().unwrap();
+        let len = inner.targets.len();
+        inner.targets.retain(|t| t.address != address);
+        inner.targets.len() != len
+    }
+
+    pub fn targets(&self) -> Vec<RelayTarget> {
+        self.inner.lock().unwrap().targets.clone()
+    }
+
+    pub fn stats(&self) -> RelayStats {
+        self.inner.lock().unwrap().stats
+    }
+
+    /// Forward a datagram to every target that wants it
+    pub fn forward(&self, datagram: &[u8]) {
+        let mut inner = self.inner.lock().unwrap();
+        if inner.targets.is_empty() {
+            return;
+        }
+
+        // Only read the header if a target filters by packet type
+        let packet_type = if inner.targets.iter().any(|t| !t.packet_types.is_empty()) {
+            parse_header(datagram).ok().map(|header| header.packet_id)
+        } else {
+            None
+        };
+
+        let (mut forwarded, mut failed) = (0, 0);
+        for target in inner.targets.iter().filter(|t| t.wants(packet_type)) {
+            let sent = inner
+                .socket(target.address)
+                .map(|socket| socket.send_to(datagram, target.address));
+            match sent {
+                Some(Ok(_)) => forwarded += 1,
+                _ => failed += 1,
+            }
+        }
+
+        inner.stats.forwarded += forwarded;
+        inner.stats.failed += failed;
+    }
+}
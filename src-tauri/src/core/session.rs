@@ -1,10 +1,18 @@
-use crate::core::{ids::PacketType, parse_packet, ParticipantData, TelemetryPacket};
+use crate::core::{
+    ArchivedSession, DerivedEventUpdate, Driver, ErsModel, EventDetector, FuelModel,
+    HistoryAssembler, IngestStats, LifecycleEvent, PacketBus, PacketReceiver, Participants,
+    RaceState, RaceStateChange, RaceStateUpdate, ReceivedDatagram, Relay, SessionLifecycle,
+    TelemetryPacket, TimingEngine, TyreModel, WeatherTracker, MAX_ARCHIVED_SESSIONS,
+};
+use crate::storage::Recorder;
 use socket2::{Domain, Socket, Type};
 use std::{
-    mem::MaybeUninit,
+    collections::VecDeque,
+    io,
     net::SocketAddr,
-    sync::{Arc, Mutex},
-    time::Duration,
+    path::PathBuf,
+    sync::{mpsc::Receiver, Arc, Mutex},
+    time::{Duration, Instant},
 };
 
 /// This struct provides detailed information
@@ -73,42 +81,103 @@ impl Connection {
     pub fn is_active(&self) -> bool {
         self.active && self.socket.as_ref().is_some()
     }
+
+    /// The address and port the socket is bound to, if bound
+    pub fn local_addr(&self) -> Option<SocketAddr> {
+        let socket = self.socket.as_ref().filter(|_| self.active)?;
+        socket.local_addr().ok()?.as_socket()
+    }
 }
 
 /// A struct describing an F1 session with a telemetry connection active.
 ///
-/// Contains information about the players and number of active cars from
-/// the Participant Data packet.
+/// Contains the drivers of every car, tracked from the Participants and
+/// Lobby Info packets, and the [`RaceState`] built from every
+/// packet received. Both are reset when a new F1 session starts, and the
+/// state of every finished session is kept in 'archive'.
 #[derive(Debug, Default)]
 pub struct Session {
     pub connection: Connection,
     pub max_packet_buffer_len: u16,
-    pub num_active_cars: u8,
-    /// A list of all current players
+    /// Drivers of every car and who drove them before.
     /// Accessed by the players vehicle index which can be found
     /// from other packets & events.
-    pub players: [ParticipantData; 22],
+    pub participants: Participants,
+
+    /// Live state of the session, see [`RaceState::ingest`]
+    pub race_state: RaceState,
+
+    /// Detects when F1 sessions start and end
+    pub lifecycle: SessionLifecycle,
+
+    /// Infers events the game doesn't send, e.g. overtakes and pit stops
+    pub detector: EventDetector,
+
+    /// Lap tables and gaps of every car
+    pub timing: TimingEngine,
+
+    /// Every lap and tyre stint of every car, from Session History packets
+    pub history: HistoryAssembler,
+
+    /// Wear, temperature and predicted life of every car's tyres
+    pub tyres: TyreModel,
+
+    /// Fuel burn and end of race projection of every car
+    pub fuel: FuelModel,
+
+    /// Forecast of every session of the weekend, and when to switch between slicks and wets
+    pub weather: WeatherTracker,
+
+    /// Energy every car deploys and harvests, and where along the lap
+    pub ers: ErsModel,
+
+    /// State of the last [`MAX_ARCHIVED_SESSIONS`] finished sessions, oldest first
+    pub archive: VecDeque<ArchivedSession>,
+
+    /// Records every datagram received while Some
+    pub recorder: Option<Recorder>,
+
+    /// Receiving thread started with [`Session::start_receiver`]
+    pub receiver: Option<PacketReceiver>,
+
+    /// Every packet ingested and race state change is published here
+    pub bus: PacketBus,
+
+    /// Forwards every datagram received to other tools
+    pub relay: Relay,
 }
 
 impl Session {
     /// Create a new telemetry connection for an F1 session.
     ///
-    /// Attempts to asynchronously and automatically get information on all players in the session
-    /// to fill out the 'players' field will valid data.
+    /// The 'participants' field is filled out as Participants packets are ingested,
+    /// see [`Session::ingest`].
     pub fn new(address: String, port: String) -> Arc<Mutex<Self>> {
-        let session = Arc::new(Mutex::new(Session {
-            connection: Connection::new(address, port, Some(Duration::from_millis(500))),
-            max_packet_buffer_len: 250,
-            num_active_cars: 0,
-            players: Default::default(),
-        }));
-
-        if session.lock().unwrap().connection.is_active() {
-            let session_clone = Arc::clone(&session);
-            tauri::async_runtime::spawn(async move { Session::get_players(session_clone).await });
-        }
+        let connection = Connection::new(address, port, Some(Duration::from_millis(500)));
+        let relay = match connection.local_addr() {
+            Some(listener) => Relay::for_listener(listener),
+            None => Relay::new(),
+        };
 
-        session
+        Arc::new(Mutex::new(Session {
+            connection,
+            max_packet_buffer_len: 250,
+            participants: Participants::new(),
+            race_state: RaceState::new(),
+            lifecycle: SessionLifecycle::new(),
+            detector: EventDetector::new(),
+            timing: TimingEngine::new(),
+            history: HistoryAssembler::new(),
+            tyres: TyreModel::new(),
+            fuel: FuelModel::new(),
+            weather: WeatherTracker::new(),
+            ers: ErsModel::new(),
+            archive: VecDeque::new(),
+            recorder: None,
+            receiver: None,
+            bus: PacketBus::new(),
+            relay,
+        }))
     }
 
     /// Set the 'active' flag in the 'connection' field to
@@ -118,65 +187,30 @@ impl Session {
         println!("Dropped connection");
         self.connection.active = false;
         self.connection.socket = None;
+
+        // Dropping the receiver stops its thread
+        self.receiver = None;
+
+        if let Err(e) = self.stop_recording() {
+            println!("[backend] Failed to finish recording: {e}");
+        }
     }
 
-    /// Asynchronously get information on all players in the session specified.
-    ///
-    /// The session must have an active telemetry UDP socket. This function will receive
-    /// all telemtry packets until it receives a valid PacketParticipantData, however, it
-    /// is assumed that the socket has a read timeout, if constructed with Connection::new(), it will.
-    ///
-    /// Extracts the 'num_active_cars' and 'participants' field and saves it to
-    /// the 'players' field of 'session'
-    pub async fn get_players(session: Arc<Mutex<Session>>) {
-        let socket = {
-            let guard = session.lock().unwrap();
-            match &guard.connection.socket {
-                Some(s) => s.try_clone().ok(), // clone so we don’t hold the lock
-                None => None,
-            }
-        };
+    /// Start recording every datagram received to session files
+    /// in `directory`, one file per F1 session.
+    pub fn start_recording(&mut self, directory: PathBuf) -> io::Result<()> {
+        self.stop_recording()?;
+        self.recorder = Some(Recorder::new(directory)?);
+        Ok(())
+    }
 
-        let Some(socket) = socket else { return };
-
-        let mut buf = [MaybeUninit::<u8>::uninit(); 2048];
-        loop {
-            match socket.recv_from(&mut buf) {
-                Ok((bytes_received, _)) => {
-                    // Check if the packet received is the packet containing
-                    // all the participant data. If it is, update the session
-                    // players array and the number of players
-
-                    let initialized = unsafe {
-                        std::slice::from_raw_parts(buf.as_ptr() as *const u8, bytes_received)
-                    };
-                    let packet = match parse_packet(initialized) {
-                        Some(packet) => packet,
-                        None => continue,
-                    };
-
-                    // Checking the packet_id in the packet header first is
-                    // less performance heavy than attempting to interpret the packet
-                    // as PacketParticipantData
-                    if packet.packet_id() == PacketType::Participants {
-                        if let Some(packet) = packet.as_participants() {
-                            let mut session_guard = session.lock().unwrap();
-                            session_guard.players = packet.participants;
-                            session_guard.num_active_cars = packet.num_active_cars;
-
-                            println!("[backend] Player information loaded.");
-                            break;
-                        }
-                    }
-                }
-                Err(ref e)
-                    if e.kind() == std::io::ErrorKind::WouldBlock
-                        || e.kind() == std::io::ErrorKind::TimedOut =>
-                {
-                    continue
-                }
-                Err(_) => break,
-            }
+    /// Stop recording, finishing the current session file.
+    ///
+    /// Returns the path of the finished file, if a session was being recorded.
+    pub fn stop_recording(&mut self) -> io::Result<Option<PathBuf>> {
+        match self.recorder.take() {
+            Some(mut recorder) => recorder.finish(),
+            None => Ok(None),
         }
     }
 
@@ -184,28 +218,175 @@ impl Session {
     ///
     /// If the player with the vehicle index does not exist, return None.
     /// A player's vehicle index can be found from events and other packets.
-    pub fn get_player(&self, plr_vehicle_idx: u8) -> Option<&ParticipantData> {
-        self.players.get(plr_vehicle_idx as usize)
+    pub fn get_player(&self, plr_vehicle_idx: u8) -> Option<&Driver> {
+        self.participants.driver(plr_vehicle_idx)
     }
 
-    /// Receive the latest F1 telemetry packet
-    pub fn get_latest_packet(&self) -> Option<TelemetryPacket> {
-        let mut buf = [MaybeUninit::<u8>::uninit(); 2048];
+    /// Start receiving packets from the connection on a dedicated thread.
+    ///
+    /// Received datagrams are queued on the returned channel, up to `queue_len`
+    /// of them, and should be passed to [`Session::ingest`]. The channel is
+    /// closed once the connection is dropped.
+    pub fn start_receiver(&mut self, queue_len: usize) -> io::Result<Receiver<ReceivedDatagram>> {
         let socket = match &self.connection.socket {
-            Some(s) => s,
-            None => return None,
+            Some(socket) => socket.try_clone()?,
+            None => return Err(io::Error::from(io::ErrorKind::NotConnected)),
+        };
+
+        let (receiver, datagrams) = PacketReceiver::spawn(socket, queue_len, self.relay.clone())?;
+        self.receiver = Some(receiver);
+        Ok(datagrams)
+    }
+
+    /// Counters of the receiver, if started
+    pub fn ingest_stats(&self) -> Option<IngestStats> {
+        self.receiver.as_ref().map(PacketReceiver::stats)
+    }
+
+    /// Apply a received datagram to the session.
+    ///
+    /// The datagram is recorded if recording, even if it could not be decoded.
+    /// The packet and the race state changes it caused, including the start
+    /// or end of a session, are published on the bus.
+    pub fn ingest(&mut self, datagram: &ReceivedDatagram) {
+        if let Some(recorder) = &mut self.recorder {
+            if let Err(e) = recorder.record(&datagram.data, datagram.received_at) {
+                println!("[backend] Failed to record packet: {e}");
+            }
+        }
+
+        let Some(packet) = &datagram.packet else {
+            return;
+        };
+
+        // Session boundaries are handled first so the finished
+        // session is archived before the packet is applied
+        let header = packet.header();
+        let mut updates: Vec<RaceStateUpdate> = self
+            .lifecycle
+            .ingest(packet, datagram.received_at)
+            .into_iter()
+            .map(|event| {
+                let mut update = self.apply_lifecycle(event);
+
+                // A session ends on the last frame it was received for
+                if !matches!(event, LifecycleEvent::SessionEnded { .. }) {
+                    update.frame_identifier = header.frame_identifier;
+                    update.session_time = header.session_time;
+                }
+                update
+            })
+            .collect();
+
+        let participant_changes = self.participants.ingest(packet);
+
+        self.bus.publish_packet(packet);
+
+        // Packets sent from the menus aren't part of any session's state
+        let changes = if header.session_uid != 0 {
+            let mut changes = self.race_state.ingest(packet);
+            changes.extend(self.history.ingest(packet));
+            changes
+        } else {
+            Vec::new()
         };
+        let snapshot = self.race_state.snapshot();
+        updates.extend(
+            participant_changes
+                .into_iter()
+                .chain(changes)
+                .map(|change| RaceStateUpdate {
+                    change,
+                    frame_identifier: snapshot.frame_identifier,
+                    session_time: snapshot.session_time,
+                }),
+        );
+        self.bus.publish_events(&updates);
+
+        if header.session_uid != 0 {
+            self.timing.ingest(packet);
+            self.tyres.ingest(packet);
+            self.fuel.ingest(packet);
+            self.weather.ingest(packet);
+            self.ers.ingest(packet);
+
+            let derived: Vec<DerivedEventUpdate> = self
+                .detector
+                .ingest(packet)
+                .into_iter()
+                .map(|event| DerivedEventUpdate {
+                    event,
+                    frame_identifier: header.frame_identifier,
+                    session_time: header.session_time,
+                })
+                .collect();
+            self.bus.publish_derived(&derived);
+        }
+    }
 
-        match socket.recv_from(&mut buf) {
-            Ok((bytes_received, _)) => {
-                let initialized = unsafe {
-                    std::slice::from_raw_parts(buf.as_ptr() as *const u8, bytes_received)
-                };
-                let packet = parse_packet(initialized)?;
-                Some(packet)
+    /// End the current F1 session if no packets have arrived for
+    /// [`SESSION_IDLE_TIMEOUT`](crate::core::SESSION_IDLE_TIMEOUT),
+    /// e.g. the player quit to the menus. Should be called regularly
+    /// while no packets are arriving.
+    pub fn check_idle(&mut self) {
+        if let Some(event) = self.lifecycle.check_idle(Instant::now()) {
+            let update = self.apply_lifecycle(event);
+            self.bus.publish_events(&[update]);
+        }
+    }
+
+    /// Archive or reset the per-session state at a session boundary
+    fn apply_lifecycle(&mut self, event: LifecycleEvent) -> RaceStateUpdate {
+        let snapshot = self.race_state.snapshot();
+        let update = RaceStateUpdate {
+            change: RaceStateChange::Lifecycle(event),
+            frame_identifier: snapshot.frame_identifier,
+            session_time: snapshot.session_time,
+        };
+
+        match event {
+            LifecycleEvent::SessionEnded {
+                session_uid,
+                reason,
+            } => {
+                println!("[backend] Session {session_uid:016x} ended ({reason:?})");
+                if self.archive.len() == MAX_ARCHIVED_SESSIONS {
+                    self.archive.pop_front();
+                }
+                self.archive.push_back(ArchivedSession {
+                    session_uid,
+                    reason,
+                    state: snapshot.clone(),
+                });
+            }
+            LifecycleEvent::SessionStarted { session_uid } => {
+                println!("[backend] Session {session_uid:016x} started");
+
+                // Participants are reloaded from the new session's packets
+                self.participants.reset();
+                self.detector.reset();
+                self.timing.reset();
+                self.history.reset();
+                self.tyres.reset();
+                self.fuel.reset();
+                self.weather.reset();
+                self.ers.reset();
+                self.race_state.reset();
+            }
+            LifecycleEvent::SessionResumed { session_uid } => {
+                println!("[backend] Session {session_uid:016x} resumed");
             }
-            Err(_) => None,
         }
+
+        update
+    }
+
+    /// Parse a datagram that did not arrive through the connection,
+    /// e.g. one being replayed, and apply it to the session.
+    pub fn ingest_datagram(&mut self, datagram: &[u8]) -> Option<TelemetryPacket> {
+        let datagram = ReceivedDatagram::decode(datagram.to_vec());
+        self.ingest(&datagram);
+        datagram.packet
     }
 
     /// Check if a session has an active UDP socket
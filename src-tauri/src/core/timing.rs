@@ -0,0 +1,668 @@
+use std::{collections::VecDeque, time::Duration};
+
+use serde::{Deserialize, Serialize};
+
+use crate::core::{
+    ids::SessionType, Lap, LapTime, PitStatus, ResultStatus, TelemetryPacket, MAX_CARS,
+};
+
+/// Min distance between two samples of a car's progress
+const SAMPLE_SPACING_M: f32 = 10.0;
+
+/// Max samples of a car's progress kept, a few laps of the longest track
+const MAX_SAMPLES: usize = 3000;
+
+/// A lap is only timed along its distance if
+/// it was followed from this close to the line
+const LAP_START_TOLERANCE_M: f32 = 50.0;
+
+/// How far a car is behind another
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum Gap {
+    Time(Duration),
+
+    /// A whole number of laps down
+    Laps(u8),
+}
+
+/// The fastest time of the session and who set it
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct TimingBest {
+    pub vehicle_idx: u8,
+    pub time: Duration,
+}
+
+/// A car's trip through the pit lane
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct PitStop {
+    pub vehicle_idx: u8,
+
+    /// Lap the car entered the pit lane on
+    pub lap: u8,
+    pub time_in_lane: Duration,
+
+    /// Time lost compared to covering the same distance on
+    /// track at the pace of the car's best lap, if known
+    pub loss: Option<Duration>,
+}
+
+/// A car's line in the [`TimingTower`].
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TimingEntry {
+    pub vehicle_idx: u8,
+    pub position: u8,
+
+    /// Lap being driven
+    pub lap: u8,
+
+    /// Current sector, 0 indexed
+    pub sector: u8,
+
+    /// None for the leader, or while not known
+    pub gap_to_leader: Option<Gap>,
+
+    /// Gap to the car one position ahead, see `gap_to_leader`
+    pub interval: Option<Gap>,
+    pub last_lap: Option<LapTime>,
+    pub best_lap: Option<LapTime>,
+
+    /// Last lap compared to the car's best lap
+    pub last_lap_delta_s: Option<f32>,
+
+    /// Current lap compared to the car's best lap at the same
+    /// point of the lap, negative when on a faster lap
+    pub live_delta_s: Option<f32>,
+
+    /// Fastest valid time of the car in each sector
+    pub best_sectors: [Option<Duration>; 3],
+    pub pit_status: PitStatus,
+    pub num_pit_stops: u8,
+    pub result_status: ResultStatus,
+}
+
+/// Live timing of every car, in running order.
+///
+/// In races cars are timed by distance covered, otherwise by best lap.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct TimingTower {
+    pub session_type: SessionType,
+    pub session_time: f32,
+    pub entries: Vec<TimingEntry>,
+    pub fastest_lap: Option<TimingBest>,
+    pub fastest_sectors: [Option<TimingBest>; 3],
+}
+
+impl TimingTower {
+    pub fn entry(&self, vehicle_idx: u8) -> Option<&TimingEntry> {
+        self.entries
+            .iter()
+            .find(|entry| entry.vehicle_idx == vehicle_idx)
+    }
+}
+
+/// Where a car was at a point in time
+#[derive(Debug, Clone, Copy)]
+struct Sample {
+    distance_m: f32,
+    time_s: f32,
+}
+
+/// A car's progress, ordered by distance
+#[derive(Debug, Clone, Default)]
+struct Trace {
+    samples: VecDeque<Sample>,
+}
+
+impl Trace {
+    fn push(&mut self, distance_m: f32, time_s: f32) {
+        // Going backwards means a flashback, the
+        // samples after that point never happened
+        while self
+            .samples
+            .back()
+            .is_some_and(|last| last.distance_m > distance_m)
+        {
+            self.samples.pop_back();
+        }
+
+        // The latest sample is always kept, replacing
+        // the previous one if it was too close to it
+        let len = self.samples.len();
+        if len >= 2 && distance_m - self.samples[len - 2].distance_m < SAMPLE_SPACING_M {
+            self.samples.pop_back();
+        }
+
+        self.samples.push_back(Sample { distance_m, time_s });
+        if self.samples.len() > MAX_SAMPLES {
+            self.samples.pop_front();
+        }
+    }
+
+    fn first(&self) -> Option<Sample> {
+        self.samples.front().copied()
+    }
+
+    fn last(&self) -> Option<Sample> {
+        self.samples.back().copied()
+    }
+
+    /// When the car was at `distance_m`, interpolated between samples.
+    /// None if it wasn't followed that far back, or hasn't got there yet.
+    fn time_at(&self, distance_m: f32) -> Option<f32> {
+        let idx = self
+            .samples
+            .partition_point(|sample| sample.distance_m < distance_m);
+        let after = *self.samples.get(idx)?;
+        if after.distance_m == distance_m {
+            return Some(after.time_s);
+        }
+
+        let before = *self.samples.get(idx.checked_sub(1)?)?;
+        let fraction = (distance_m - before.distance_m) / (after.distance_m - before.distance_m);
+        Some(before.time_s + fraction * (after.time_s - before.time_s))
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct CarTiming {
+    lap: Option<Lap>,
+
+    /// Completed laps, oldest first
+    laps: Vec<LapTime>,
+
+    /// Total distance covered in the session
+    trace: Trace,
+
+    /// Distance covered in the current lap
+    lap_trace: Trace,
+
+    /// The lap trace of the car's best lap, for the live delta
+    best_lap_trace: Option<(Duration, Trace)>,
+
+    /// Distance around the lap the car entered the pit lane at
+    pit_entry_distance_m: Option<f32>,
+}
+
+impl CarTiming {
+    fn best_lap(&self) -> Option<&LapTime> {
+        self.laps
+            .iter()
+            .filter(|lap| lap.valid)
+            .min_by_key(|lap| lap.time)
+    }
+
+    fn best_sectors(&self) -> [Option<Duration>; 3] {
+        let mut best = [None; 3];
+        for lap in &self.laps {
+            for (sector, best) in best.iter_mut().enumerate() {
+                let time = lap.sectors[sector];
+                if lap.sectors_valid[sector]
+                    && !time.is_zero()
+                    && best.is_none_or(|best| time < best)
+                {
+                    *best = Some(time);
+                }
+            }
+        }
+
+        best
+    }
+
+    fn update(&mut self, lap: Lap, time_s: f32) {
+        if let Some(previous) = self.lap.take() {
+            if lap.lap_number > previous.lap_number && previous.lap_number > 0 {
+                self.complete_lap(&previous, &lap);
+            } else if lap.lap_number < previous.lap_number {
+                // Flashback to a previous lap
+                self.laps.retain(|completed| completed.lap < lap.lap_number);
+                self.lap_trace = Trace::default();
+            }
+        }
+
+        // Distances are frozen once the car has finished,
+        // so gaps stay what they were at the line
+        if lap.result_status == ResultStatus::Active {
+            self.trace.push(lap.total_distance_m, time_s);
+            if lap.lap_distance_m >= 0.0 {
+                self.lap_trace
+                    .push(lap.lap_distance_m, lap.current_lap_time.as_secs_f32());
+            }
+        }
+
+        self.lap = Some(lap);
+    }
+
+    fn complete_lap(&mut self, previous: &Lap, lap: &Lap) {
+        let (sector1, sector2) = (previous.sector1_time, previous.sector2_time);
+        let valid = !previous.lap_invalid;
+        let completed = LapTime {
+            lap: previous.lap_number,
+            time: lap.last_lap_time,
+            sectors: [
+                sector1,
+                sector2,
+                lap.last_lap_time.saturating_sub(sector1 + sector2),
+            ],
+            valid,
+            sectors_valid: [valid; 3],
+        };
+
+        // Session History is more accurate, so only
+        // add laps it hasn't been received for yet
+        if !self.laps.iter().any(|known| known.lap == completed.lap) {
+            self.laps.push(completed);
+        }
+
+        let lap_trace = std::mem::take(&mut self.lap_trace);
+        let followed_whole_lap = lap_trace
+            .first()
+            .is_some_and(|first| first.distance_m < LAP_START_TOLERANCE_M);
+        let is_best = self
+            .best_lap_trace
+            .as_ref()
+            .is_none_or(|(best, _)| completed.time < *best);
+        if valid && followed_whole_lap && is_best && !completed.time.is_zero() {
+            self.best_lap_trace = Some((completed.time, lap_trace));
+        }
+    }
+
+    /// Returns the stop once the car leaves the pit lane
+    fn update_pit(&mut self, vehicle_idx: u8, lap: &Lap, track_length_m: f32) -> Option<PitStop> {
+        let previous = self.lap.as_ref()?;
+        let in_pits = |lap: &Lap| lap.pit_status != PitStatus::None;
+
+        match (in_pits(previous), in_pits(lap)) {
+            (false, true) => {
+                self.pit_entry_distance_m = Some(lap.lap_distance_m);
+                None
+            }
+            (true, false) => {
+                let entry_m = self.pit_entry_distance_m.take();
+                let time_in_lane = previous.pit_lane_time?;
+                let best_lap = self.best_lap().map(|best| best.time);
+
+                let loss = entry_m.filter(|_| track_length_m > 0.0).zip(best_lap).map(
+                    |(entry_m, best_lap)| {
+                        let lane_m = (lap.lap_distance_m - entry_m).rem_euclid(track_length_m);
+                        time_in_lane.saturating_sub(best_lap.mul_f32(lane_m / track_length_m))
+                    },
+                );
+
+                Some(PitStop {
+                    vehicle_idx,
+                    lap: previous.lap_number,
+                    time_in_lane,
+                    loss,
+                })
+            }
+            _ => None,
+        }
+    }
+
+    /// Merge the laps from a Session History packet,
+    /// keeping any completed since it was sent
+    fn update_history(&mut self, laps: Vec<LapTime>) {
+        let newer: Vec<LapTime> = self
+            .laps
+            .iter()
+            .filter(|lap| laps.last().is_none_or(|last| lap.lap > last.lap))
+            .copied()
+            .collect();
+
+        self.laps = laps;
+        self.laps.extend(newer);
+    }
+
+    /// Current lap compared to the best lap at the same point of the lap
+    fn live_delta_s(&self) -> Option<f32> {
+        let (_, best) = self.best_lap_trace.as_ref()?;
+        let now = self.lap_trace.last()?;
+        Some(now.time_s - best.time_at(now.distance_m)?)
+    }
+}
+
+/// Builds lap and sector tables of every car from Lap Data and
+/// Session History packets, and times the gaps between cars.
+#[derive(Debug, Clone)]
+pub struct TimingEngine {
+    session_type: SessionType,
+    track_length_m: f32,
+
+    /// Session time of the last Lap Data packet
+    session_time: f32,
+    cars: Vec<CarTiming>,
+    pit_stops: Vec<PitStop>,
+}
+
+impl Default for TimingEngine {
+    fn default() -> Self {
+        Self {
+            session_type: SessionType::Unknown,
+            track_length_m: 0.0,
+            session_time: 0.0,
+            cars: vec![CarTiming::default(); MAX_CARS],
+            pit_stops: Vec::new(),
+        }
+    }
+}
+
+impl TimingEngine {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Forget every lap, e.g. when a new session starts
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    /// Apply a Session, Lap Data or Session History packet,
+    /// other packets are ignored
+    pub fn ingest(&mut self, packet: &TelemetryPacket) {
+        match packet {
+            TelemetryPacket::Session(session) => {
+                self.session_type = session.session_type;
+                self.track_length_m = session.track_length as f32;
+            }
+            TelemetryPacket::LapData(lap_data) => {
+                self.session_time = lap_data.header.session_time;
+                for (idx, car) in self.cars.iter_mut().enumerate() {
+                    match lap_data.lap(idx as u8).filter(is_classified) {
+                        Some(lap) => {
+                            let pit_stop = car.update_pit(idx as u8, &lap, self.track_length_m);
+                            self.pit_stops.extend(pit_stop);
+                            car.update(lap, self.session_time);
+                        }
+                        None => *car = CarTiming::default(),
+                    }
+                }
+            }
+            TelemetryPacket::SessionHistory(history) => {
+                if let Some(car) = self.cars.get_mut(history.car_idx as usize) {
+                    car.update_history(history.laps());
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Completed laps of the car at `vehicle_idx`, oldest first
+    pub fn laps(&self, vehicle_idx: u8) -> &[LapTime] {
+        self.cars
+            .get(vehicle_idx as usize)
+            .map_or(&[], |car| &car.laps)
+    }
+
+    /// Fastest valid lap of the car at `vehicle_idx`
+    pub fn best_lap(&self, vehicle_idx: u8) -> Option<&LapTime> {
+        self.cars.get(vehicle_idx as usize)?.best_lap()
+    }
+
+    /// Every pit stop made in the session, in order
+    pub fn pit_stops(&self) -> &[PitStop] {
+        &self.pit_stops
+    }
+
+    /// Typical time lost by a pit stop, the median of every stop
+    /// measured in the session
+    pub fn pit_loss(&self) -> Option<Duration> {
+        let mut losses: Vec<Duration> =
+            self.pit_stops.iter().filter_map(|stop| stop.loss).collect();
+        losses.sort();
+
+        losses.get(losses.len() / 2).copied()
+    }
+
+    /// How far the car at `behind` is behind the car at `ahead`.
+    ///
+    /// In races this is how long ago `ahead` was where `behind` is now,
+    /// otherwise the difference between their best laps.
+    pub fn gap(&self, ahead: u8, behind: u8) -> Option<Gap> {
+        let ahead = self.cars.get(ahead as usize)?;
+        let behind = self.cars.get(behind as usize)?;
+
+        if !self.session_type.is_race() {
+            let time = behind
+                .best_lap()?
+                .time
+                .saturating_sub(ahead.best_lap()?.time);
+            return Some(Gap::Time(time));
+        }
+
+        let now = behind.trace.last()?;
+        let ahead_now = ahead.trace.last()?;
+        let laps_down = (ahead_now.distance_m - now.distance_m) / self.track_length_m;
+        if self.track_length_m > 0.0 && laps_down >= 1.0 {
+            return Some(Gap::Laps(laps_down as u8));
+        }
+
+        let time_s = now.time_s - ahead.trace.time_at(now.distance_m)?;
+        Some(Gap::Time(Duration::from_secs_f32(time_s.max(0.0))))
+    }
+
+    /// Live timing of every car, in running order
+    pub fn tower(&self) -> TimingTower {
+        let mut order: Vec<(u8, u8)> = self
+            .cars
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, car)| Some((car.lap.as_ref()?.position, idx as u8)))
+            .filter(|(position, _)| *position > 0)
+            .collect();
+        order.sort();
+
+        let leader = order.first().map(|&(_, idx)| idx);
+        let mut entries = Vec::with_capacity(order.len());
+        let mut ahead = None;
+        for (position, idx) in order {
+            let car = &self.cars[idx as usize];
+            let Some(lap) = &car.lap else {
+                continue;
+            };
+
+            // The game's own gaps are used until there are samples to
+            // interpolate, e.g. just after joining a session
+            let game_gap = |delta: Duration| {
+                (self.session_type.is_race() && !delta.is_zero()).then_some(Gap::Time(delta))
+            };
+            let gap_to_leader = leader.filter(|&leader| leader != idx).and_then(|leader| {
+                self.gap(leader, idx)
+                    .or_else(|| game_gap(lap.delta_to_race_leader))
+            });
+            let interval = ahead.and_then(|ahead| {
+                self.gap(ahead, idx)
+                    .or_else(|| game_gap(lap.delta_to_car_in_front))
+            });
+
+            let best_lap = car.best_lap().copied();
+            let last_lap = car.laps.last().copied();
+            let last_lap_delta_s = last_lap
+                .zip(best_lap)
+                .map(|(last, best)| last.time.as_secs_f32() - best.time.as_secs_f32());
+
+            entries.push(TimingEntry {
+                vehicle_idx: idx,
+                position,
+                lap: lap.lap_number,
+                sector: lap.sector,
+                gap_to_leader,
+                interval,
+                last_lap,
+                best_lap,
+                last_lap_delta_s,
+                live_delta_s: car.live_delta_s(),
+                best_sectors: car.best_sectors(),
+                pit_status: lap.pit_status,
+                num_pit_stops: lap.num_pit_stops,
+                result_status: lap.result_status,
+            });
+            ahead = Some(idx);
+        }
+
+        TimingTower {
+            session_type: self.session_type,
+            session_time: self.session_time,
+            fastest_lap: fastest(
+                entries
+                    .iter()
+                    .map(|entry| (entry.vehicle_idx, entry.best_lap.map(|lap| lap.time))),
+            ),
+            fastest_sectors: [0, 1, 2].map(|sector| {
+                fastest(
+                    entries
+                        .iter()
+                        .map(|entry| (entry.vehicle_idx, entry.best_sectors[sector])),
+                )
+            }),
+            entries,
+        }
+    }
+}
+
+fn fastest(times: impl Iterator<Item = (u8, Option<Duration>)>) -> Option<TimingBest> {
+    times
+        .filter_map(|(vehicle_idx, time)| {
+            Some(TimingBest {
+                vehicle_idx,
+                time: time?,
+            })
+        })
+        .min_by_key(|best| best.time)
+}
+
+/// Cars that aren't in the session have no timing
+fn is_classified(lap: &Lap) -> bool {
+    !matches!(
+        lap.result_status,
+        ResultStatus::Invalid | ResultStatus::Inactive
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn trace(samples: &[(f32, f32)]) -> Trace {
+        let mut trace = Trace::default();
+        for &(distance_m, time_s) in samples {
+            trace.push(distance_m, time_s);
+        }
+
+        trace
+    }
+
+    fn distances(trace: &Trace) -> Vec<f32> {
+        trace
+            .samples
+            .iter()
+            .map(|sample| sample.distance_m)
+            .collect()
+    }
+
+    fn lap_time(lap: u8, time_s: f32) -> LapTime {
+        LapTime {
+            lap,
+            time: Duration::from_secs_f32(time_s),
+            sectors: [Duration::ZERO; 3],
+            valid: true,
+            sectors_valid: [true; 3],
+        }
+    }
+
+    fn pit_stop(loss_s: Option<u64>) -> PitStop {
+        PitStop {
+            vehicle_idx: 0,
+            lap: 10,
+            time_in_lane: Duration::from_secs(40),
+            loss: loss_s.map(Duration::from_secs),
+        }
+    }
+
+    #[test]
+    fn trace_interpolates_between_samples() {
+        let trace = trace(&[(0.0, 0.0), (100.0, 10.0), (200.0, 30.0)]);
+
+        assert_eq!(trace.time_at(0.0), Some(0.0));
+        assert_eq!(trace.time_at(50.0), Some(5.0));
+        assert_eq!(trace.time_at(100.0), Some(10.0));
+        assert_eq!(trace.time_at(150.0), Some(20.0));
+        assert_eq!(trace.time_at(200.0), Some(30.0));
+
+        // Before the first sample, and not got there yet
+        assert_eq!(trace.time_at(-10.0), None);
+        assert_eq!(trace.time_at(250.0), None);
+    }
+
+    #[test]
+    fn trace_forgets_samples_after_a_flashback() {
+        let mut trace = trace(&[(0.0, 0.0), (100.0, 10.0), (200.0, 20.0), (300.0, 30.0)]);
+        trace.push(150.0, 15.0);
+
+        assert_eq!(distances(&trace), [0.0, 100.0, 150.0]);
+        assert_eq!(trace.time_at(150.0), Some(15.0));
+        assert_eq!(trace.time_at(250.0), None);
+    }
+
+    #[test]
+    fn trace_replaces_samples_too_close_to_the_previous_one() {
+        let trace = trace(&[(0.0, 0.0), (100.0, 10.0), (105.0, 10.5), (108.0, 10.8)]);
+
+        assert_eq!(distances(&trace), [0.0, 100.0, 108.0]);
+    }
+
+    #[test]
+    fn gaps_in_races_are_timed_by_distance() {
+        let mut engine = TimingEngine {
+            session_type: SessionType::R,
+            track_length_m: 1000.0,
+            ..Default::default()
+        };
+        engine.cars[0].trace = trace(&[(0.0, 0.0), (1000.0, 50.0), (1500.0, 100.0)]);
+        engine.cars[1].trace = trace(&[(0.0, 0.0), (900.0, 100.0)]);
+        engine.cars[2].trace = trace(&[(0.0, 0.0), (400.0, 100.0)]);
+
+        let Some(Gap::Time(gap)) = engine.gap(0, 1) else {
+            panic!("expected a time gap");
+        };
+        assert!((gap.as_secs_f32() - 55.0).abs() < 0.01);
+
+        assert_eq!(engine.gap(0, 2), Some(Gap::Laps(1)));
+    }
+
+    #[test]
+    fn history_keeps_laps_completed_since_it_was_sent() {
+        let mut car = CarTiming {
+            laps: (1..=4).map(|lap| lap_time(lap, 90.0)).collect(),
+            ..Default::default()
+        };
+        car.update_history(vec![lap_time(1, 91.0), lap_time(2, 92.0)]);
+
+        assert_eq!(
+            car.laps,
+            [
+                lap_time(1, 91.0),
+                lap_time(2, 92.0),
+                lap_time(3, 90.0),
+                lap_time(4, 90.0)
+            ]
+        );
+
+        // An empty history has no laps to replace
+        car.update_history(Vec::new());
+        assert_eq!(car.laps.len(), 4);
+    }
+
+    #[test]
+    fn pit_loss_is_the_median_of_measured_stops() {
+        let mut engine = TimingEngine::new();
+        assert_eq!(engine.pit_loss(), None);
+
+        engine.pit_stops = vec![
+            pit_stop(Some(30)),
+            pit_stop(Some(20)),
+            pit_stop(None),
+            pit_stop(Some(25)),
+        ];
+        assert_eq!(engine.pit_loss(), Some(Duration::from_secs(25)));
+    }
+}
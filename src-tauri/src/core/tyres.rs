@@ -0,0 +1,507 @@
+use serde::{Deserialize, Serialize};
+
+use crate::core::{
+    ids::TrackId, CarDamage, CarStatus, CarTelemetry, PitStatus, TelemetryPacket, TyreCompound,
+    VisualTyreCompound, MAX_CARS,
+};
+
+/// Wear at which a tyre falls off the cliff and loses most of its grip
+pub const TYRE_CLIFF_WEAR_PERCENT: f32 = 70.0;
+
+/// Number of recent laps a car's own wear rate is averaged over
+const RECENT_LAPS: usize = 3;
+
+/// Predictions further ahead than this aren't made
+const MAX_PREDICTED_LAPS: u8 = 100;
+
+/// Fewest laps on a compound before its degradation curve is used
+const MIN_CURVE_SAMPLES: usize = 3;
+
+/// How a tyre's temperature compares to the operating window of its compound
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TyreTemperature {
+    #[default]
+    Unknown,
+    Cold,
+    Optimal,
+
+    /// The surface is too cold while the carcass is up to temperature,
+    /// so the surface slides and tears
+    Graining,
+    Overheating,
+}
+
+impl TyreTemperature {
+    fn from_temperatures(compound: TyreCompound, surface_c: u8, inner_c: u8) -> Self {
+        let (min_c, max_c) = operating_window(compound);
+        if surface_c == 0 && inner_c == 0 {
+            TyreTemperature::Unknown
+        } else if surface_c > max_c || inner_c > max_c {
+            TyreTemperature::Overheating
+        } else if surface_c < min_c && inner_c >= min_c {
+            TyreTemperature::Graining
+        } else if surface_c < min_c {
+            TyreTemperature::Cold
+        } else {
+            TyreTemperature::Optimal
+        }
+    }
+}
+
+/// Range of temperatures a compound works in, in celsius
+fn operating_window(compound: TyreCompound) -> (u8, u8) {
+    match compound {
+        TyreCompound::Inter => (60, 95),
+        TyreCompound::Wet | TyreCompound::ClassicWet | TyreCompound::F2Wet => (50, 85),
+        _ => (85, 110),
+    }
+}
+
+/// A lap driven on a set of tyres
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct TyreLap {
+    pub lap: u8,
+
+    /// Laps on the set at the end of the lap
+    pub age_laps: u8,
+
+    /// Wear of every tyre at the end of the lap
+    pub wear_percent: [f32; 4],
+
+    /// Wear added to every tyre during the lap
+    pub wear_added_percent: [f32; 4],
+}
+
+impl TyreLap {
+    /// Wear added to the most worn tyre
+    fn max_wear_added(&self) -> f32 {
+        self.wear_added_percent.into_iter().fold(0.0, f32::max)
+    }
+}
+
+/// How quickly a compound wears at a track, fitted to the laps of every
+/// car as `wear per lap = base + increase * age` by least squares.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct DegradationCurve {
+    pub track_id: TrackId,
+    pub compound: TyreCompound,
+
+    /// Wear added to the most worn tyre on a lap of a new set
+    pub base_wear_per_lap: f32,
+
+    /// How much more wear is added every lap the set gets older
+    pub wear_increase_per_lap: f32,
+
+    /// Number of laps the curve was fitted to
+    pub num_samples: usize,
+
+    /// (age in laps, wear added) of every lap
+    #[serde(skip)]
+    samples: Vec<(f32, f32)>,
+}
+
+impl DegradationCurve {
+    fn new(track_id: TrackId, compound: TyreCompound) -> Self {
+        Self {
+            track_id,
+            compound,
+            base_wear_per_lap: 0.0,
+            wear_increase_per_lap: 0.0,
+            num_samples: 0,
+            samples: Vec::new(),
+        }
+    }
+
+    /// Expected wear added to the most worn tyre on a lap
+    /// driven when the set is `age_laps` old
+    pub fn wear_per_lap(&self, age_laps: f32) -> f32 {
+        (self.base_wear_per_lap + self.wear_increase_per_lap * age_laps).max(0.0)
+    }
+
+    fn add(&mut self, age_laps: u8, wear_added: f32) {
+        self.samples.push((age_laps as f32, wear_added));
+        self.num_samples = self.samples.len();
+
+        let n = self.samples.len() as f32;
+        let mean_age = self.samples.iter().map(|(age, _)| age).sum::<f32>() / n;
+        let mean_wear = self.samples.iter().map(|(_, wear)| wear).sum::<f32>() / n;
+        let (covariance, variance) =
+            self.samples
+                .iter()
+                .fold((0.0, 0.0), |(covariance, variance), (age, wear)| {
+                    (
+                        covariance + (age - mean_age) * (wear - mean_wear),
+                        variance + (age - mean_age).powi(2),
+                    )
+                });
+
+        // Every lap at the same age gives no trend, only an average
+        self.wear_increase_per_lap = if variance > 0.0 {
+            covariance / variance
+        } else {
+            0.0
+        };
+        self.base_wear_per_lap = mean_wear - self.wear_increase_per_lap * mean_age;
+    }
+}
+
+/// State of the tyres fitted to a car.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TyreAnalysis {
+    pub vehicle_idx: u8,
+    pub compound: TyreCompound,
+    pub visual_compound: VisualTyreCompound,
+    pub age_laps: u8,
+    pub wear_percent: [f32; 4],
+
+    /// Wear added to every tyre per lap, averaged over the last few laps
+    pub wear_per_lap_percent: [f32; 4],
+
+    /// Laps until the most worn tyre reaches [`TYRE_CLIFF_WEAR_PERCENT`]
+    pub laps_to_cliff: Option<f32>,
+    pub temperatures: [TyreTemperature; 4],
+
+    /// Every lap driven on the set that was seen from start to finish
+    pub laps: Vec<TyreLap>,
+}
+
+#[derive(Debug, Clone, Default)]
+struct CarTyres {
+    status: Option<CarStatus>,
+    damage: Option<CarDamage>,
+    telemetry: Option<CarTelemetry>,
+    lap_number: u8,
+
+    /// Wear when the current lap started, None if the lap
+    /// wasn't seen from the start or the set was changed
+    lap_start_wear: Option<[f32; 4]>,
+    pitted_this_lap: bool,
+
+    /// Laps of the current set
+    laps: Vec<TyreLap>,
+}
+
+impl CarTyres {
+    fn wear(&self) -> Option<[f32; 4]> {
+        self.damage.as_ref().map(|damage| damage.tyres_wear)
+    }
+
+    fn recent_wear_per_lap(&self) -> Option<[f32; 4]> {
+        let recent = &self.laps[self.laps.len().saturating_sub(RECENT_LAPS)..];
+        if recent.is_empty() {
+            return None;
+        }
+
+        let mut wear = [0.0; 4];
+        for lap in recent {
+            for (tyre, added) in wear.iter_mut().zip(lap.wear_added_percent) {
+                *tyre += added / recent.len() as f32;
+            }
+        }
+
+        Some(wear)
+    }
+
+    /// A set that isn't the same compound, or has fewer laps on it, is a new set
+    fn update_status(&mut self, status: CarStatus) {
+        let new_set = self.status.as_ref().is_some_and(|previous| {
+            previous.tyres.compound != status.tyres.compound
+                || previous.tyres.visual_compound != status.tyres.visual_compound
+                || status.tyres.age_laps < previous.tyres.age_laps
+        });
+
+        if new_set {
+            self.laps.clear();
+            self.lap_start_wear = None;
+        }
+
+        self.status = Some(status);
+    }
+
+    /// Returns the lap that was completed, if any
+    fn update_lap(&mut self, lap_number: u8, pit_status: PitStatus) -> Option<TyreLap> {
+        self.pitted_this_lap |= pit_status != PitStatus::None;
+        if lap_number == self.lap_number {
+            return None;
+        }
+
+        let completed = lap_number == self.lap_number + 1;
+        let start_wear = self.lap_start_wear.take();
+        let pitted = std::mem::take(&mut self.pitted_this_lap);
+        self.lap_number = lap_number;
+        self.lap_start_wear = self.wear();
+
+        // Laps the car pitted on or that weren't fully seen aren't timed
+        let (start_wear, wear) = (start_wear?, self.wear()?);
+        if !completed || pitted {
+            return None;
+        }
+
+        let tyre_lap = TyreLap {
+            lap: lap_number - 1,
+            age_laps: self.status.as_ref()?.tyres.age_laps?,
+            wear_percent: wear,
+            wear_added_percent: [0, 1, 2, 3].map(|tyre| (wear[tyre] - start_wear[tyre]).max(0.0)),
+        };
+        self.laps.push(tyre_lap);
+
+        Some(tyre_lap)
+    }
+}
+
+/// Tracks the wear and temperature of every car's tyres from the Car
+/// Status, Car Damage, Car Telemetry and Lap Data packets, learning how
+/// quickly each compound wears at each track to predict tyre life.
+#[derive(Debug, Clone)]
+pub struct TyreModel {
+    track_id: TrackId,
+    cars: Vec<CarTyres>,
+
+    /// Kept between sessions, so practice laps inform the race
+    curves: Vec<DegradationCurve>,
+}
+
+impl Default for TyreModel {
+    fn default() -> Self {
+        Self {
+            track_id: TrackId::Unknown,
+            cars: vec![CarTyres::default(); MAX_CARS],
+            curves: Vec::new(),
+        }
+    }
+}
+
+impl TyreModel {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Forget every car's tyres, e.g. when a new session starts.
+    /// Degradation curves are kept.
+    pub fn reset(&mut self) {
+        self.cars = vec![CarTyres::default(); MAX_CARS];
+    }
+
+    /// Apply a Session, Lap Data, Car Status, Car Damage or
+    /// Car Telemetry packet, other packets are ignored
+    pub fn ingest(&mut self, packet: &TelemetryPacket) {
+        match packet {
+            TelemetryPacket::Session(session) => self.track_id = session.track_id,
+            TelemetryPacket::LapData(lap_data) => {
+                for idx in 0..MAX_CARS as u8 {
+                    let Some(lap) = lap_data.lap(idx) else {
+                        continue;
+                    };
+
+                    let car = &mut self.cars[idx as usize];
+                    let Some(tyre_lap) = car.update_lap(lap.lap_number, lap.pit_status) else {
+                        continue;
+                    };
+
+                    let compound = car.status.as_ref().map(|status| status.tyres.compound);
+                    if let Some(compound) = compound {
+                        self.curve_mut(compound)
+                            .add(tyre_lap.age_laps, tyre_lap.max_wear_added());
+                    }
+                }
+            }
+            TelemetryPacket::CarStatus(car_status) => {
+                for (idx, car) in self.cars.iter_mut().enumerate() {
+                    if let Some(status) = car_status.status(idx as u8) {
+                        car.update_status(status);
+                    }
+                }
+            }
+            TelemetryPacket::CarDamage(car_damage) => {
+                for (idx, car) in self.cars.iter_mut().enumerate() {
+                    if let Some(damage) = car_damage.damage(idx as u8) {
+                        car.damage = Some(damage);
+                    }
+                }
+            }
+            TelemetryPacket::CarTelemetry(car_telemetry) => {
+                for (idx, car) in self.cars.iter_mut().enumerate() {
+                    if let Some(telemetry) = car_telemetry.telemetry(idx as u8) {
+                        car.telemetry = Some(telemetry);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Degradation of `compound` at the current track, if enough laps were driven on it
+    pub fn curve(&self, compound: TyreCompound) -> Option<&DegradationCurve> {
+        self.curves
+            .iter()
+            .find(|curve| curve.track_id == self.track_id && curve.compound == compound)
+            .filter(|curve| curve.num_samples >= MIN_CURVE_SAMPLES)
+    }
+
+    /// Degradation of every compound at every track seen
+    pub fn curves(&self) -> &[DegradationCurve] {
+        &self.curves
+    }
+
+    fn curve_mut(&mut self, compound: TyreCompound) -> &mut DegradationCurve {
+        let track_id = self.track_id;
+        let idx = match self
+            .curves
+            .iter()
+            .position(|curve| curve.track_id == track_id && curve.compound == compound)
+        {
+            Some(idx) => idx,
+            None => {
+                self.curves.push(DegradationCurve::new(track_id, compound));
+                self.curves.len() - 1
+            }
+        };
+
+        &mut self.curves[idx]
+    }
+
+    /// Laps until any tyre of the car at `vehicle_idx` reaches `wear_percent`.
+    ///
+    /// The car's own recent wear rate is used, getting worse as fast as the
+    /// compound's degradation curve, or only the curve until a lap was timed.
+    pub fn laps_until_wear(&self, vehicle_idx: u8, wear_percent: f32) -> Option<f32> {
+        let car = self.cars.get(vehicle_idx as usize)?;
+        let wear = car.wear()?;
+        let tyres = &car.status.as_ref()?.tyres;
+        let age_laps = tyres.age_laps? as f32;
+        let curve = self.curve(tyres.compound);
+
+        let recent = car.recent_wear_per_lap();
+        let increase = curve.map_or(0.0, |curve| curve.wear_increase_per_lap);
+
+        (0..4)
+            .filter_map(|tyre| {
+                let rate = |laps: f32| match (recent, curve) {
+                    (Some(recent), _) => (recent[tyre] + increase * laps).max(0.0),
+                    (None, Some(curve)) => curve.wear_per_lap(age_laps + laps),
+                    (None, None) => 0.0,
+                };
+                laps_until(wear[tyre], wear_percent, rate)
+            })
+            .min_by(f32::total_cmp)
+    }
+
+    /// State of the tyres fitted to the car at `vehicle_idx`
+    pub fn analysis(&self, vehicle_idx: u8) -> Option<TyreAnalysis> {
+        let car = self.cars.get(vehicle_idx as usize)?;
+        let tyres = &car.status.as_ref()?.tyres;
+        let temperatures = match &car.telemetry {
+            Some(telemetry) => [0, 1, 2, 3].map(|tyre| {
+                TyreTemperature::from_temperatures(
+                    tyres.compound,
+                    telemetry.tyres_surface_temperature_c[tyre],
+                    telemetry.tyres_inner_temperature_c[tyre],
+                )
+            }),
+            None => [TyreTemperature::Unknown; 4],
+        };
+
+        Some(TyreAnalysis {
+            vehicle_idx,
+            compound: tyres.compound,
+            visual_compound: tyres.visual_compound,
+            age_laps: tyres.age_laps.unwrap_or_default(),
+            wear_percent: car.wear().unwrap_or_default(),
+            wear_per_lap_percent: car.recent_wear_per_lap().unwrap_or_default(),
+            laps_to_cliff: self.laps_until_wear(vehicle_idx, TYRE_CLIFF_WEAR_PERCENT),
+            temperatures,
+            laps: car.laps.clone(),
+        })
+    }
+}
+
+/// Laps until `wear` reaches `target` when each lap adds `rate(laps driven)`
+fn laps_until(mut wear: f32, target: f32, rate: impl Fn(f32) -> f32) -> Option<f32> {
+    for lap in 0..MAX_PREDICTED_LAPS {
+        if wear >= target {
+            return Some(lap as f32);
+        }
+
+        let added = rate(lap as f32);
+        if wear + added >= target {
+            return Some(lap as f32 + (target - wear) / added);
+        }
+        wear += added;
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn curve(samples: &[(u8, f32)]) -> DegradationCurve {
+        let mut curve = DegradationCurve::new(TrackId::Melbourne, TyreCompound::C3);
+        for &(age_laps, wear_added) in samples {
+            curve.add(age_laps, wear_added);
+        }
+
+        curve
+    }
+
+    fn assert_close(actual: f32, expected: f32) {
+        assert!(
+            (actual - expected).abs() < 1e-4,
+            "expected {expected}, got {actual}"
+        );
+    }
+
+    #[test]
+    fn curve_is_fitted_by_least_squares() {
+        let curve = curve(&[(1, 1.0), (2, 1.2), (3, 1.4), (4, 1.6)]);
+
+        assert_eq!(curve.num_samples, 4);
+        assert_close(curve.base_wear_per_lap, 0.8);
+        assert_close(curve.wear_increase_per_lap, 0.2);
+        assert_close(curve.wear_per_lap(10.0), 2.8);
+    }
+
+    #[test]
+    fn curve_of_laps_at_the_same_age_is_their_average() {
+        let curve = curve(&[(5, 1.0), (5, 2.0)]);
+
+        assert_close(curve.base_wear_per_lap, 1.5);
+        assert_close(curve.wear_increase_per_lap, 0.0);
+    }
+
+    #[test]
+    fn laps_until_interpolates_the_last_lap() {
+        assert_eq!(laps_until(10.0, 20.0, |_| 2.0), Some(5.0));
+        assert_eq!(laps_until(10.0, 15.0, |_| 2.0), Some(2.5));
+        assert_eq!(laps_until(25.0, 20.0, |_| 2.0), Some(0.0));
+
+        // 10 + 1 + 2 = 13 after two laps, then 3 more a lap
+        assert_close(
+            laps_until(10.0, 15.0, |laps| 1.0 + laps).unwrap(),
+            2.0 + 2.0 / 3.0,
+        );
+    }
+
+    #[test]
+    fn laps_until_is_none_beyond_the_predicted_laps() {
+        assert_eq!(laps_until(10.0, 20.0, |_| 0.0), None);
+        assert_eq!(laps_until(0.0, 100.0, |_| 0.5), None);
+    }
+
+    #[test]
+    fn temperatures_are_compared_to_the_compound_window() {
+        let dry = |surface_c, inner_c| {
+            TyreTemperature::from_temperatures(TyreCompound::C3, surface_c, inner_c)
+        };
+        assert_eq!(dry(0, 0), TyreTemperature::Unknown);
+        assert_eq!(dry(90, 95), TyreTemperature::Optimal);
+        assert_eq!(dry(115, 100), TyreTemperature::Overheating);
+        assert_eq!(dry(100, 115), TyreTemperature::Overheating);
+        assert_eq!(dry(70, 90), TyreTemperature::Graining);
+        assert_eq!(dry(70, 70), TyreTemperature::Cold);
+
+        let inter = TyreTemperature::from_temperatures(TyreCompound::Inter, 70, 70);
+        assert_eq!(inter, TyreTemperature::Optimal);
+    }
+}
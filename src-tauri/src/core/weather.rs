@@ -0,0 +1,614 @@
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::core::{
+    ids::{SessionType, WeatherType},
+    PitStatus, TelemetryPacket, VisualTyreCompound, WeatherForecast, MAX_CARS,
+};
+
+/// Fewest laps in the same conditions before their measured
+/// lap time replaces the default difference between tyres
+const MIN_MEASURED_LAPS: usize = 2;
+
+/// Used until a lap was measured in any condition
+const DEFAULT_LAP_TIME_S: f32 = 90.0;
+
+/// A tyre has to be this much faster per lap before switching to it
+const CROSSOVER_MARGIN_S: f32 = 0.5;
+
+/// Lap time compared to slicks in the dry, by rain level and tyre category
+const DEFAULT_DELTAS_S: [[f32; 3]; 3] = [
+    // Slick, intermediate, wet
+    [0.0, 4.0, 8.0],
+    [5.0, 0.0, 2.0],
+    [15.0, 3.0, 0.0],
+];
+
+/// Which conditions a tyre is made for
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TyreCategory {
+    Slick,
+    Intermediate,
+    Wet,
+}
+
+impl TyreCategory {
+    const ALL: [TyreCategory; 3] = [
+        TyreCategory::Slick,
+        TyreCategory::Intermediate,
+        TyreCategory::Wet,
+    ];
+
+    pub fn from_visual(compound: VisualTyreCompound) -> Option<Self> {
+        match compound {
+            VisualTyreCompound::Unknown => None,
+            VisualTyreCompound::Inter => Some(TyreCategory::Intermediate),
+            VisualTyreCompound::Wet
+            | VisualTyreCompound::ClassicWet
+            | VisualTyreCompound::F2Wet => Some(TyreCategory::Wet),
+            _ => Some(TyreCategory::Slick),
+        }
+    }
+}
+
+/// How hard it is raining
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RainLevel {
+    Dry,
+    Light,
+    Heavy,
+}
+
+impl From<WeatherType> for RainLevel {
+    fn from(weather: WeatherType) -> Self {
+        match weather {
+            WeatherType::LightRain => RainLevel::Light,
+            WeatherType::HeavyRain | WeatherType::Storm => RainLevel::Heavy,
+            _ => RainLevel::Dry,
+        }
+    }
+}
+
+/// Latest forecast for a session of the weekend
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ForecastTimeline {
+    pub session_type: SessionType,
+
+    /// Ordered by time offset, which is from `session_time`
+    pub forecast: Vec<WeatherForecast>,
+
+    /// Session time of the packet the forecast is from
+    pub session_time: f32,
+}
+
+/// A forecast compared to the weather that came
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct ForecastCheck {
+    /// Session time the forecast was for
+    pub target_time_s: f32,
+
+    /// How far ahead the forecast was first made
+    pub lead: Duration,
+    pub predicted: WeatherType,
+    pub rain_percentage: u8,
+    pub actual: WeatherType,
+
+    /// Whether the rain level was predicted
+    pub correct: bool,
+}
+
+/// The forecast for a point in the current session changed
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct ForecastChange {
+    pub session_time: f32,
+
+    /// Session time the forecast is for
+    pub target_time_s: f32,
+    pub from: WeatherType,
+    pub to: WeatherType,
+    pub rain_percentage_from: u8,
+    pub rain_percentage_to: u8,
+}
+
+/// A lap completed without pitting and in the same conditions throughout
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct WeatherLap {
+    pub vehicle_idx: u8,
+    pub rain: RainLevel,
+    pub tyres: TyreCategory,
+    pub time_s: f32,
+}
+
+/// When to switch tyres as the forecast changes
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct Crossover {
+    pub lap: u8,
+
+    /// How far from now the forecast is for
+    pub time_offset: Duration,
+    pub from: TyreCategory,
+    pub to: TyreCategory,
+    pub weather: WeatherType,
+    pub rain_percentage: u8,
+
+    /// Expected lap time gained by switching
+    pub gain_per_lap_s: f32,
+}
+
+/// Weather, forecast and tyre crossovers of a car.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct WeatherAnalysis {
+    pub vehicle_idx: u8,
+    pub weather: WeatherType,
+    pub rain: RainLevel,
+    pub tyres: TyreCategory,
+
+    /// Expected lap time of every tyre category in the current weather,
+    /// measured or from default differences between tyres
+    pub lap_times_s: Vec<(TyreCategory, f32)>,
+
+    /// Upcoming tyre changes, in order. Empty if the current tyres
+    /// are the fastest for the whole forecast.
+    pub crossovers: Vec<Crossover>,
+
+    /// Share of forecasts that got the rain level right, None until one was checked
+    pub forecast_hit_rate: Option<f32>,
+}
+
+/// The first and latest forecast for a point in the current session
+#[derive(Debug, Clone, Copy)]
+struct Prediction {
+    target_time_s: f32,
+    lead: Duration,
+    first: (WeatherType, u8),
+    latest: (WeatherType, u8),
+}
+
+#[derive(Debug, Clone, Default)]
+struct CarWeather {
+    tyres: Option<TyreCategory>,
+    lap_number: u8,
+
+    /// Conditions the current lap started in, None if they changed during the lap
+    lap_rain: Option<RainLevel>,
+    lap_tyres: Option<TyreCategory>,
+    pitted: bool,
+}
+
+/// Keeps the weather forecast of every session of the weekend from the
+/// Session packet, checks the forecast for the current session against
+/// the weather that comes, and advises when to switch between slicks,
+/// intermediates and wets from lap times measured in each condition.
+#[derive(Debug, Clone)]
+pub struct WeatherTracker {
+    /// Weekend the timelines and checks are from
+    weekend_link_identifier: u32,
+    session_type: SessionType,
+    session_time: f32,
+    weather: WeatherType,
+    timelines: Vec<ForecastTimeline>,
+    pending: Vec<Prediction>,
+    checks: Vec<ForecastCheck>,
+    changes: Vec<ForecastChange>,
+    laps: Vec<WeatherLap>,
+    cars: Vec<CarWeather>,
+}
+
+impl Default for WeatherTracker {
+    fn default() -> Self {
+        Self {
+            weekend_link_identifier: 0,
+            session_type: SessionType::Unknown,
+            session_time: 0.0,
+            weather: WeatherType::Clear,
+            timelines: Vec::new(),
+            pending: Vec::new(),
+            checks: Vec::new(),
+            changes: Vec::new(),
+            laps: Vec::new(),
+            cars: vec![CarWeather::default(); MAX_CARS],
+        }
+    }
+}
+
+impl WeatherTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Forget the current session's forecast changes and every lap, e.g. when
+    /// a new session starts. The forecast of every session of the weekend,
+    /// and how accurate it was, are kept.
+    pub fn reset(&mut self) {
+        *self = Self {
+            weekend_link_identifier: self.weekend_link_identifier,
+            timelines: std::mem::take(&mut self.timelines),
+            checks: std::mem::take(&mut self.checks),
+            ..Self::default()
+        };
+    }
+
+    /// Apply a Session, Lap Data or Car Status packet, other packets are ignored
+    pub fn ingest(&mut self, packet: &TelemetryPacket) {
+        match packet {
+            TelemetryPacket::Session(session) => {
+                let session_time = session.header.session_time;
+                let weather = session.weather();
+
+                // Forecasts made after a flashback's time never happened
+                if session_time < self.session_time {
+                    self.pending.clear();
+                }
+
+                // Another weekend has its own forecast
+                if session.weekend_link_identifier != self.weekend_link_identifier {
+                    self.weekend_link_identifier = session.weekend_link_identifier;
+                    self.timelines.clear();
+                    self.checks.clear();
+                }
+
+                self.session_type = session.session_type;
+                self.session_time = session_time;
+                self.weather = weather.weather;
+                self.update_timelines(weather.forecast, session_time);
+                self.check_predictions();
+
+                let rain = RainLevel::from(self.weather);
+                for car in &mut self.cars {
+                    if car.lap_rain.is_some_and(|lap_rain| lap_rain != rain) {
+                        car.lap_rain = None;
+                    }
+                }
+            }
+            TelemetryPacket::LapData(lap_data) => {
+                let rain = RainLevel::from(self.weather);
+                for (idx, car) in self.cars.iter_mut().enumerate() {
+                    let Some(lap) = lap_data.lap(idx as u8) else {
+                        continue;
+                    };
+
+                    car.pitted |= lap.pit_status != PitStatus::None;
+                    if lap.lap_number == car.lap_number {
+                        continue;
+                    }
+
+                    let completed = lap.lap_number == car.lap_number + 1 && car.lap_number > 0;
+                    if let (true, false, Some(rain), Some(tyres)) =
+                        (completed, car.pitted, car.lap_rain, car.lap_tyres)
+                    {
+                        if car.tyres == Some(tyres) && !lap.last_lap_time.is_zero() {
+                            self.laps.push(WeatherLap {
+                                vehicle_idx: idx as u8,
+                                rain,
+                                tyres,
+                                time_s: lap.last_lap_time.as_secs_f32(),
+                            });
+                        }
+                    }
+
+                    car.lap_number = lap.lap_number;
+                    car.lap_rain = Some(rain);
+                    car.lap_tyres = car.tyres;
+                    car.pitted = false;
+                }
+            }
+            TelemetryPacket::CarStatus(car_status) => {
+                for (idx, car) in self.cars.iter_mut().enumerate() {
+                    if let Some(status) = car_status.status(idx as u8) {
+                        car.tyres = TyreCategory::from_visual(status.tyres.visual_compound);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn update_timelines(&mut self, forecast: Vec<WeatherForecast>, session_time: f32) {
+        let mut session_types: Vec<SessionType> = Vec::new();
+        for sample in &forecast {
+            if !session_types.contains(&sample.session_type) {
+                session_types.push(sample.session_type);
+            }
+        }
+
+        for session_type in session_types {
+            let mut samples: Vec<WeatherForecast> = forecast
+                .iter()
+                .filter(|sample| sample.session_type == session_type)
+                .cloned()
+                .collect();
+            samples.sort_by_key(|sample| sample.time_offset);
+
+            if session_type == self.session_type {
+                self.predict(&samples, session_time);
+            }
+
+            let timeline = ForecastTimeline {
+                session_type,
+                forecast: samples,
+                session_time,
+            };
+            match self
+                .timelines
+                .iter_mut()
+                .find(|timeline| timeline.session_type == session_type)
+            {
+                Some(existing) => *existing = timeline,
+                None => self.timelines.push(timeline),
+            }
+        }
+    }
+
+    /// Remember what the forecast says about every minute ahead
+    fn predict(&mut self, samples: &[WeatherForecast], session_time: f32) {
+        for sample in samples
+            .iter()
+            .filter(|sample| !sample.time_offset.is_zero())
+        {
+            let target_time_s =
+                ((session_time + sample.time_offset.as_secs_f32()) / 60.0).round() * 60.0;
+            let forecast = (sample.weather, sample.rain_percentage);
+
+            match self
+                .pending
+                .iter_mut()
+                .find(|prediction| prediction.target_time_s == target_time_s)
+            {
+                Some(prediction) => {
+                    if prediction.latest.0 != forecast.0 {
+                        self.changes.push(ForecastChange {
+                            session_time,
+                            target_time_s,
+                            from: prediction.latest.0,
+                            to: forecast.0,
+                            rain_percentage_from: prediction.latest.1,
+                            rain_percentage_to: forecast.1,
+                        });
+                    }
+                    prediction.latest = forecast;
+                }
+                None => self.pending.push(Prediction {
+                    target_time_s,
+                    lead: sample.time_offset,
+                    first: forecast,
+                    latest: forecast,
+                }),
+            }
+        }
+    }
+
+    /// Compare forecasts whose time has come with the actual weather
+    fn check_predictions(&mut self) {
+        let (due, pending) = self
+            .pending
+            .iter()
+            .partition(|prediction| prediction.target_time_s <= self.session_time);
+        self.pending = pending;
+
+        let actual = self.weather;
+        self.checks
+            .extend(due.into_iter().map(|prediction: Prediction| ForecastCheck {
+                target_time_s: prediction.target_time_s,
+                lead: prediction.lead,
+                predicted: prediction.first.0,
+                rain_percentage: prediction.first.1,
+                actual,
+                correct: RainLevel::from(prediction.first.0) == RainLevel::from(actual),
+            }));
+    }
+
+    /// Latest forecast of every session of the weekend
+    pub fn timelines(&self) -> &[ForecastTimeline] {
+        &self.timelines
+    }
+
+    pub fn timeline(&self, session_type: SessionType) -> Option<&ForecastTimeline> {
+        self.timelines
+            .iter()
+            .find(|timeline| timeline.session_type == session_type)
+    }
+
+    /// Forecasts for every session of the weekend compared to the weather that came
+    pub fn checks(&self) -> &[ForecastCheck] {
+        &self.checks
+    }
+
+    /// Every time the forecast for a point in the current session changed
+    pub fn changes(&self) -> &[ForecastChange] {
+        &self.changes
+    }
+
+    /// Share of checked forecasts that got the rain level right
+    pub fn hit_rate(&self) -> Option<f32> {
+        let correct = self.checks.iter().filter(|check| check.correct).count();
+        (!self.checks.is_empty()).then(|| correct as f32 / self.checks.len() as f32)
+    }
+
+    /// Laps measured by every car, in the order they were completed
+    pub fn laps(&self) -> &[WeatherLap] {
+        &self.laps
+    }
+
+    /// Median lap time of every car on `tyres` in `rain`, if enough laps were measured
+    fn measured_lap_time_s(&self, tyres: TyreCategory, rain: RainLevel) -> Option<f32> {
+        let mut times: Vec<f32> = self
+            .laps
+            .iter()
+            .filter(|lap| lap.tyres == tyres && lap.rain == rain)
+            .map(|lap| lap.time_s)
+            .collect();
+        if times.len() < MIN_MEASURED_LAPS {
+            return None;
+        }
+
+        times.sort_by(f32::total_cmp);
+        Some(times[times.len() / 2])
+    }
+
+    /// Expected lap time on `tyres` in `rain`. Conditions that weren't
+    /// measured use default differences to the ones that were.
+    pub fn lap_time_s(&self, tyres: TyreCategory, rain: RainLevel) -> f32 {
+        if let Some(measured) = self.measured_lap_time_s(tyres, rain) {
+            return measured;
+        }
+
+        let default_delta =
+            |tyres: TyreCategory, rain: RainLevel| DEFAULT_DELTAS_S[rain as usize][tyres as usize];
+        let dry_slick_s = [RainLevel::Dry, RainLevel::Light, RainLevel::Heavy]
+            .into_iter()
+            .flat_map(|rain| TyreCategory::ALL.map(|tyres| (tyres, rain)))
+            .find_map(|(tyres, rain)| {
+                Some(self.measured_lap_time_s(tyres, rain)? - default_delta(tyres, rain))
+            })
+            .unwrap_or(DEFAULT_LAP_TIME_S);
+
+        dry_slick_s + default_delta(tyres, rain)
+    }
+
+    /// Expected lap time on `tyres` with a forecast of `weather` and a chance of rain
+    fn expected_lap_time_s(
+        &self,
+        tyres: TyreCategory,
+        weather: WeatherType,
+        rain_percentage: u8,
+    ) -> f32 {
+        let rain = match RainLevel::from(weather) {
+            RainLevel::Dry => RainLevel::Light,
+            rain => rain,
+        };
+        let chance = if RainLevel::from(weather) == RainLevel::Dry {
+            rain_percentage.min(100) as f32 / 100.0
+        } else {
+            1.0
+        };
+
+        (1.0 - chance) * self.lap_time_s(tyres, RainLevel::Dry)
+            + chance * self.lap_time_s(tyres, rain)
+    }
+
+    /// When the car at `vehicle_idx` should switch tyres over the forecast for the current session
+    pub fn crossovers(&self, vehicle_idx: u8) -> Vec<Crossover> {
+        let Some(car) = self.cars.get(vehicle_idx as usize) else {
+            return Vec::new();
+        };
+        let (Some(mut tyres), Some(timeline)) = (car.tyres, self.timeline(self.session_type))
+        else {
+            return Vec::new();
+        };
+
+        let lap_time_s = self.lap_time_s(tyres, RainLevel::from(self.weather));
+        let mut crossovers = Vec::new();
+        for sample in &timeline.forecast {
+            let current_s = self.expected_lap_time_s(tyres, sample.weather, sample.rain_percentage);
+            let (fastest, fastest_s) = TyreCategory::ALL
+                .into_iter()
+                .map(|category| {
+                    let time_s =
+                        self.expected_lap_time_s(category, sample.weather, sample.rain_percentage);
+                    (category, time_s)
+                })
+                .min_by(|a, b| a.1.total_cmp(&b.1))
+                .unwrap_or((tyres, current_s));
+
+            if fastest != tyres && current_s - fastest_s >= CROSSOVER_MARGIN_S {
+                let laps_ahead = (sample.time_offset.as_secs_f32() / lap_time_s).ceil();
+                crossovers.push(Crossover {
+                    lap: car.lap_number.saturating_add(laps_ahead as u8),
+                    time_offset: sample.time_offset,
+                    from: tyres,
+                    to: fastest,
+                    weather: sample.weather,
+                    rain_percentage: sample.rain_percentage,
+                    gain_per_lap_s: current_s - fastest_s,
+                });
+                tyres = fastest;
+            }
+        }
+
+        crossovers
+    }
+
+    /// Weather, forecast and tyre crossovers of the car at `vehicle_idx`
+    pub fn analysis(&self, vehicle_idx: u8) -> Option<WeatherAnalysis> {
+        let tyres = self.cars.get(vehicle_idx as usize)?.tyres?;
+        let rain = RainLevel::from(self.weather);
+
+        Some(WeatherAnalysis {
+            vehicle_idx,
+            weather: self.weather,
+            rain,
+            tyres,
+            lap_times_s: TyreCategory::ALL
+                .into_iter()
+                .map(|category| (category, self.lap_time_s(category, rain)))
+                .collect(),
+            crossovers: self.crossovers(vehicle_idx),
+            forecast_hit_rate: self.hit_rate(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::parse_packet;
+
+    /// A 2023 Session packet of `weekend` with a clear forecast of
+    /// (session type, minutes ahead) for every sample.
+    /// 1 is the first practice session and 5 the first qualifying session.
+    fn session(
+        weekend: u32,
+        session_type: u8,
+        session_time: f32,
+        samples: &[(u8, u8)],
+    ) -> TelemetryPacket {
+        let mut buffer = vec![0u8; 644];
+        buffer[0..2].copy_from_slice(&2023u16.to_le_bytes());
+        buffer[6] = 1;
+        buffer[15..19].copy_from_slice(&session_time.to_le_bytes());
+        buffer[35] = session_type;
+        buffer[155] = samples.len() as u8;
+        for (idx, &(session_type, time_offset)) in samples.iter().enumerate() {
+            buffer[156 + idx * 8] = session_type;
+            buffer[156 + idx * 8 + 1] = time_offset;
+        }
+        buffer[610..614].copy_from_slice(&weekend.to_le_bytes());
+        parse_packet(&buffer).unwrap()
+    }
+
+    fn session_types(weather: &WeatherTracker) -> Vec<SessionType> {
+        weather
+            .timelines()
+            .iter()
+            .map(|timeline| timeline.session_type)
+            .collect()
+    }
+
+    #[test]
+    fn keeps_the_weekend_forecast_between_sessions() {
+        let mut weather = WeatherTracker::new();
+        weather.ingest(&session(1, 1, 0.0, &[(1, 0), (1, 5), (5, 0)]));
+        weather.ingest(&session(1, 1, 300.0, &[(1, 0), (5, 0)]));
+        assert_eq!(session_types(&weather), [SessionType::P1, SessionType::Q1]);
+        assert_eq!(weather.checks().len(), 1);
+
+        weather.reset();
+        weather.ingest(&session(1, 5, 0.0, &[(5, 0)]));
+        assert_eq!(session_types(&weather), [SessionType::P1, SessionType::Q1]);
+        assert_eq!(weather.checks().len(), 1);
+        assert_eq!(weather.hit_rate(), Some(1.0));
+    }
+
+    #[test]
+    fn forgets_the_forecast_of_another_weekend() {
+        let mut weather = WeatherTracker::new();
+        weather.ingest(&session(1, 1, 0.0, &[(1, 0), (1, 5)]));
+        weather.ingest(&session(1, 1, 300.0, &[(1, 0)]));
+
+        weather.reset();
+        weather.ingest(&session(2, 5, 0.0, &[(5, 0)]));
+        assert_eq!(session_types(&weather), [SessionType::Q1]);
+        assert!(weather.checks().is_empty());
+    }
+}
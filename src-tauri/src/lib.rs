@@ -1,21 +1,35 @@
+// Decoding, session and race state (core), recording (storage) and pit
+// strategy only need std and serde. Everything else is behind a cargo
+// feature, see Cargo.toml.
+#[cfg(feature = "audio")]
 pub mod audio;
+#[cfg(feature = "ui")]
 pub mod bridge;
 pub mod core;
+pub mod storage;
 pub mod strategy;
 
-use crate::{
-    bridge::events::{
-        get_input_devices, get_output_devices, set_input_device, set_input_volume,
-        set_output_volume, start_audio_recording, start_udp_listener, stop_audio_recording,
-    },
-    core::TelemetryPacket,
-    strategy::{answer_question, init},
+#[cfg(feature = "ui")]
+use crate::bridge::events::{
+    get_ers_analysis, get_fuel_analysis, get_input_devices, get_lap_history, get_output_devices,
+    get_pit_strategy, get_rejoin_prediction, get_replay_status, get_safety_car_advice,
+    get_timing_tower, get_tyre_analysis, get_weather_analysis, get_weather_forecast,
+    list_listeners, listen_for_events, pause_replay, resume_replay, seek_replay, seek_replay_frame,
+    set_fuel_target_margin, set_input_device, set_input_volume, set_output_volume,
+    set_replay_speed, start_audio_recording, start_replay, start_session_recording,
+    start_udp_listener, step_replay, stop_audio_recording, stop_listener, stop_replay,
+    stop_session_recording,
 };
 
+#[cfg(feature = "ui")]
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
+        .setup(|app| {
+            listen_for_events(app.handle());
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
             start_udp_listener,
             start_audio_recording,
@@ -24,7 +38,31 @@ pub fn run() {
             set_output_volume,
             get_output_devices,
             get_input_devices,
-            set_input_device
+            set_input_device,
+            start_replay,
+            stop_replay,
+            pause_replay,
+            resume_replay,
+            seek_replay,
+            seek_replay_frame,
+            step_replay,
+            set_replay_speed,
+            get_replay_status,
+            stop_listener,
+            list_listeners,
+            start_session_recording,
+            stop_session_recording,
+            get_timing_tower,
+            get_lap_history,
+            get_tyre_analysis,
+            get_fuel_analysis,
+            set_fuel_target_margin,
+            get_pit_strategy,
+            get_rejoin_prediction,
+            get_weather_analysis,
+            get_weather_forecast,
+            get_safety_car_advice,
+            get_ers_analysis
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
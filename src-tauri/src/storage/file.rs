@@ -0,0 +1,775 @@
+/// Solis session file format
+///
+/// A session file holds every raw datagram received during one F1 session
+/// (one `session_uid`) along with the time it arrived, so the session can
+/// be inspected or replayed later exactly as it was received.
+///
+/// Layout, all integers little endian:
+///
+/// | Section  | Contents                                                                  |
+/// |----------|---------------------------------------------------------------------------|
+/// | Preamble | `SOLISREC` magic, file version u16, session uid u64, packet format u16      |
+/// | Chunks   | `CHNK`, [`ChunkIndex`] fields, deflate compressed records                   |
+/// | Footer   | `INFO`, length u32, JSON [`RecordingInfo`] and chunk index, offset u64, magic |
+///
+/// Each record inside a chunk is the arrival timestamp in microseconds (u64),
+/// the datagram length (u16) and the datagram bytes.
+///
+/// The footer is written when a recording is finished. If a recording was
+/// not finished (e.g. Solis crashed), the chunks are scanned instead so
+/// everything up to the last complete chunk can still be read.
+use std::{
+    fs::File,
+    io::{self, BufReader, BufWriter, Read, Seek, SeekFrom, Write},
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use flate2::{read::DeflateDecoder, write::DeflateEncoder, Compression};
+use serde::{Deserialize, Serialize};
+
+use crate::core::{parse_header, Driver};
+
+pub const MAGIC: &[u8; 8] = b"SOLISREC";
+pub const FILE_VERSION: u16 = 1;
+pub const FILE_EXTENSION: &str = "solis";
+
+const CHUNK_TAG: &[u8; 4] = b"CHNK";
+const FOOTER_TAG: &[u8; 4] = b"INFO";
+
+/// Size of the preamble at the start of the file
+const PREAMBLE_SIZE: u64 = 8 + 2 + 8 + 2;
+
+/// Size of a chunk header, tag included
+const CHUNK_HEADER_SIZE: u64 = 4 + 4 + 4 + 8 + 4 + 4;
+
+/// A chunk is written once it spans this much time, so seeking
+/// never needs to decompress more than this much of the session.
+const CHUNK_DURATION: Duration = Duration::from_secs(1);
+
+/// ... or once it holds this many uncompressed bytes.
+const CHUNK_MAX_BYTES: usize = 1024 * 1024;
+
+/// Most uncompressed bytes a chunk can hold, as the record
+/// that fills it can take it past [`CHUNK_MAX_BYTES`]
+const CHUNK_LIMIT_BYTES: u64 = CHUNK_MAX_BYTES as u64 + 10 + u16::MAX as u64;
+
+/// Describes the game and session a recording was made from.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct RecordingInfo {
+    pub file_version: u16,
+    pub session_uid: u64,
+
+    /// e.g 2024
+    pub packet_format: u16,
+    pub game_year: u8,
+    pub game_major_version: u8,
+    pub game_minor_version: u8,
+
+    /// Unix time in milliseconds the first datagram arrived at
+    pub started_at_ms: u64,
+
+    /// Arrival time of the last datagram, relative to the first
+    pub duration: Duration,
+    pub num_datagrams: u64,
+
+    /// Participants from the latest Participants packet in the session
+    pub participants: Vec<Driver>,
+}
+
+/// Location and contents of a compressed chunk of records.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default)]
+pub struct ChunkIndex {
+    /// Offset of the chunk header from the start of the file
+    pub offset: u64,
+    pub compressed_len: u32,
+    pub num_records: u32,
+
+    /// Arrival time of the first record in the chunk
+    pub first_timestamp: Duration,
+
+    /// Lowest and highest frame identifiers in the chunk
+    pub first_frame: u32,
+    pub last_frame: u32,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Footer {
+    info: RecordingInfo,
+    index: Vec<ChunkIndex>,
+}
+
+/// A single datagram as it was received
+#[derive(Debug, Clone)]
+pub struct Record {
+    /// Arrival time relative to the first datagram of the session
+    pub timestamp: Duration,
+    pub data: Vec<u8>,
+}
+
+impl Record {
+    /// Frame identifier of the datagram, if its header can be decoded
+    pub fn frame_identifier(&self) -> Option<u32> {
+        parse_header(&self.data)
+            .ok()
+            .map(|header| header.frame_identifier)
+    }
+}
+
+fn invalid_data(message: impl Into<String>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.into())
+}
+
+/// Writes a single session file. See [`Recorder`](crate::storage::Recorder)
+/// for recording a live stream of datagrams.
+#[derive(Debug)]
+pub struct SessionWriter {
+    path: PathBuf,
+    file: BufWriter<File>,
+    position: u64,
+    info: RecordingInfo,
+    index: Vec<ChunkIndex>,
+
+    // Records waiting to be written as the next chunk
+    pending: Vec<u8>,
+    pending_chunk: ChunkIndex,
+}
+
+impl SessionWriter {
+    /// Create a new session file at `path`, overwriting any existing file.
+    pub fn create(path: impl Into<PathBuf>, info: RecordingInfo) -> io::Result<Self> {
+        let path = path.into();
+        let mut file = BufWriter::new(File::create(&path)?);
+
+        file.write_all(MAGIC)?;
+        file.write_all(&FILE_VERSION.to_le_bytes())?;
+        file.write_all(&info.session_uid.to_le_bytes())?;
+        file.write_all(&info.packet_format.to_le_bytes())?;
+
+        Ok(Self {
+            path,
+            file,
+            position: PREAMBLE_SIZE,
+            info: RecordingInfo {
+                file_version: FILE_VERSION,
+                ..info
+            },
+            index: Vec::new(),
+            pending: Vec::new(),
+            pending_chunk: ChunkIndex::default(),
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn info(&self) -> &RecordingInfo {
+        &self.info
+    }
+
+    /// Replace the participants stored in the footer
+    pub fn set_participants(&mut self, participants: Vec<Driver>) {
+        self.info.participants = participants;
+    }
+
+    /// Append a datagram that arrived `timestamp` after the first one.
+    pub fn write(&mut self, timestamp: Duration, datagram: &[u8]) -> io::Result<()> {
+        let len = u16::try_from(datagram.len())
+            .map_err(|_| invalid_data("datagram too large to record"))?;
+        let frame = parse_header(datagram)
+            .ok()
+            .map(|header| header.frame_identifier);
+
+        let chunk = &mut self.pending_chunk;
+        if chunk.num_records == 0 {
+            chunk.first_timestamp = timestamp;
+            chunk.first_frame = frame.unwrap_or(0);
+            chunk.last_frame = frame.unwrap_or(0);
+        }
+        if let Some(frame) = frame {
+            chunk.first_frame = chunk.first_frame.min(frame);
+            chunk.last_frame = chunk.last_frame.max(frame);
+        }
+        chunk.num_records += 1;
+
+        self.pending
+            .extend_from_slice(&(timestamp.as_micros() as u64).to_le_bytes());
+        self.pending.extend_from_slice(&len.to_le_bytes());
+        self.pending.extend_from_slice(datagram);
+
+        self.info.duration = self.info.duration.max(timestamp);
+        self.info.num_datagrams += 1;
+
+        if timestamp.saturating_sub(chunk.first_timestamp) >= CHUNK_DURATION
+            || self.pending.len() >= CHUNK_MAX_BYTES
+        {
+            self.write_chunk()?;
+        }
+
+        Ok(())
+    }
+
+    /// Compress and write the pending records as a chunk
+    fn write_chunk(&mut self) -> io::Result<()> {
+        if self.pending_chunk.num_records == 0 {
+            return Ok(());
+        }
+
+        let mut encoder = DeflateEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(&self.pending)?;
+        let compressed = encoder.finish()?;
+
+        let chunk = ChunkIndex {
+            offset: self.position,
+            compressed_len: u32::try_from(compressed.len())
+                .map_err(|_| invalid_data("chunk too large"))?,
+            ..self.pending_chunk
+        };
+
+        self.file.write_all(CHUNK_TAG)?;
+        self.file.write_all(&chunk.compressed_len.to_le_bytes())?;
+        self.file.write_all(&chunk.num_records.to_le_bytes())?;
+        self.file
+            .write_all(&(chunk.first_timestamp.as_micros() as u64).to_le_bytes())?;
+        self.file.write_all(&chunk.first_frame.to_le_bytes())?;
+        self.file.write_all(&chunk.last_frame.to_le_bytes())?;
+        self.file.write_all(&compressed)?;
+        self.file.flush()?;
+
+        self.position += CHUNK_HEADER_SIZE + compressed.len() as u64;
+        self.index.push(chunk);
+        self.pending.clear();
+        self.pending_chunk = ChunkIndex::default();
+
+        Ok(())
+    }
+
+    /// Write any pending records and the footer, completing the file.
+    pub fn finish(mut self) -> io::Result<PathBuf> {
+        self.write_chunk()?;
+
+        let footer = serde_json::to_vec(&Footer {
+            info: self.info.clone(),
+            index: self.index.clone(),
+        })?;
+        let footer_len =
+            u32::try_from(footer.len()).map_err(|_| invalid_data("footer too large"))?;
+
+        self.file.write_all(FOOTER_TAG)?;
+        self.file.write_all(&footer_len.to_le_bytes())?;
+        self.file.write_all(&footer)?;
+        self.file.write_all(&self.position.to_le_bytes())?;
+        self.file.write_all(MAGIC)?;
+        self.file.flush()?;
+
+        Ok(self.path)
+    }
+}
+
+/// A session file opened for reading.
+pub struct SessionFile {
+    path: PathBuf,
+    file: BufReader<File>,
+    info: RecordingInfo,
+    index: Vec<ChunkIndex>,
+
+    /// False if the file was not finished and the index was rebuilt
+    complete: bool,
+}
+
+impl SessionFile {
+    pub fn open(path: impl Into<PathBuf>) -> io::Result<Self> {
+        let path = path.into();
+        let mut file = BufReader::new(File::open(&path)?);
+
+        let mut preamble = [0u8; PREAMBLE_SIZE as usize];
+        file.read_exact(&mut preamble)
+            .map_err(|_| invalid_data("not a Solis session file"))?;
+        if &preamble[0..8] != MAGIC {
+            return Err(invalid_data("not a Solis session file"));
+        }
+
+        let version = u16::from_le_bytes([preamble[8], preamble[9]]);
+        if version != FILE_VERSION {
+            return Err(invalid_data(format!(
+                "unsupported session file version: {version}"
+            )));
+        }
+
+        let mut session_file = Self {
+            path,
+            file,
+            info: RecordingInfo::default(),
+            index: Vec::new(),
+            complete: true,
+        };
+
+        if let Some(footer) = session_file.read_footer()? {
+            session_file.info = footer.info;
+            session_file.index = footer.index;
+        } else {
+            session_file.complete = false;
+            session_file.index = session_file.scan_chunks()?;
+            session_file.info = RecordingInfo {
+                file_version: version,
+                session_uid: u64::from_le_bytes(preamble[10..18].try_into().unwrap()),
+                packet_format: u16::from_le_bytes([preamble[18], preamble[19]]),
+                num_datagrams: session_file
+                    .index
+                    .iter()
+                    .map(|chunk| chunk.num_records as u64)
+                    .sum(),
+                ..Default::default()
+            };
+
+            // The game version and participants are only stored in the
+            // footer, recover what we can from the recorded packets.
+            if let Some(last) = session_file.index.len().checked_sub(1) {
+                let records = session_file.read_chunk(last)?;
+                session_file.info.duration = records
+                    .last()
+                    .map(|record| record.timestamp)
+                    .unwrap_or_default();
+            }
+        }
+
+        Ok(session_file)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn info(&self) -> &RecordingInfo {
+        &self.info
+    }
+
+    pub fn index(&self) -> &[ChunkIndex] {
+        &self.index
+    }
+
+    /// Whether the recording was finished. Unfinished files are
+    /// readable but have no participants or game version.
+    pub fn is_complete(&self) -> bool {
+        self.complete
+    }
+
+    fn read_footer(&mut self) -> io::Result<Option<Footer>> {
+        let len = self.file.seek(SeekFrom::End(0))?;
+        if len < PREAMBLE_SIZE + 16 {
+            return Ok(None);
+        }
+
+        let mut trailer = [0u8; 16];
+        self.file.seek(SeekFrom::End(-16))?;
+        self.file.read_exact(&mut trailer)?;
+        if &trailer[8..16] != MAGIC {
+            return Ok(None);
+        }
+
+        // The footer sits between the chunks and the trailer
+        let offset = u64::from_le_bytes(trailer[0..8].try_into().unwrap());
+        if offset < PREAMBLE_SIZE || offset > len - 16 - 8 {
+            return Err(invalid_data("corrupt session file footer"));
+        }
+        self.file.seek(SeekFrom::Start(offset))?;
+
+        let mut tag = [0u8; 8];
+        self.file.read_exact(&mut tag)?;
+        let footer_len = u32::from_le_bytes(tag[4..8].try_into().unwrap());
+        if &tag[0..4] != FOOTER_TAG || footer_len as u64 > len - 16 - offset - 8 {
+            return Err(invalid_data("corrupt session file footer"));
+        }
+
+        let mut footer = vec![0u8; footer_len as usize];
+        self.file.read_exact(&mut footer)?;
+        let footer: Footer = serde_json::from_slice(&footer)?;
+
+        if footer.index.iter().any(|chunk| {
+            chunk.offset < PREAMBLE_SIZE
+                || chunk
+                    .offset
+                    .saturating_add(CHUNK_HEADER_SIZE + chunk.compressed_len as u64)
+                    > offset
+        }) {
+            return Err(invalid_data("corrupt session file index"));
+        }
+
+        Ok(Some(footer))
+    }
+
+    /// Rebuild the chunk index by walking the chunk headers,
+    /// stopping at the first incomplete chunk.
+    fn scan_chunks(&mut self) -> io::Result<Vec<ChunkIndex>> {
+        let len = self.file.seek(SeekFrom::End(0))?;
+        let mut offset = self.file.seek(SeekFrom::Start(PREAMBLE_SIZE))?;
+        let mut index = Vec::new();
+
+        while offset + CHUNK_HEADER_SIZE <= len {
+            let mut header = [0u8; CHUNK_HEADER_SIZE as usize];
+            self.file.read_exact(&mut header)?;
+            if &header[0..4] != CHUNK_TAG {
+                break;
+            }
+
+            let chunk = ChunkIndex {
+                offset,
+                compressed_len: u32::from_le_bytes(header[4..8].try_into().unwrap()),
+                num_records: u32::from_le_bytes(header[8..12].try_into().unwrap()),
+                first_timestamp: Duration::from_micros(u64::from_le_bytes(
+                    header[12..20].try_into().unwrap(),
+                )),
+                first_frame: u32::from_le_bytes(header[20..24].try_into().unwrap()),
+                last_frame: u32::from_le_bytes(header[24..28].try_into().unwrap()),
+            };
+
+            let next = offset + CHUNK_HEADER_SIZE + chunk.compressed_len as u64;
+            if next > len {
+                break;
+            }
+
+            index.push(chunk);
+            offset = self.file.seek(SeekFrom::Start(next))?;
+        }
+
+        Ok(index)
+    }
+
+    /// Read and decompress every record in a chunk
+    pub fn read_chunk(&mut self, chunk_idx: usize) -> io::Result<Vec<Record>> {
+        let chunk = *self
+            .index
+            .get(chunk_idx)
+            .ok_or_else(|| invalid_data(format!("no chunk {chunk_idx}")))?;
+
+        self.file
+            .seek(SeekFrom::Start(chunk.offset + CHUNK_HEADER_SIZE))?;
+        let compressed = (&mut self.file).take(chunk.compressed_len as u64);
+
+        let mut data = Vec::new();
+        DeflateDecoder::new(compressed)
+            .take(CHUNK_LIMIT_BYTES + 1)
+            .read_to_end(&mut data)?;
+        if data.len() as u64 > CHUNK_LIMIT_BYTES {
+            return Err(invalid_data("chunk too large"));
+        }
+
+        let mut records = Vec::new();
+        let mut rest = data.as_slice();
+        while !rest.is_empty() {
+            if rest.len() < 10 {
+                return Err(invalid_data("truncated record"));
+            }
+
+            let timestamp = u64::from_le_bytes(rest[0..8].try_into().unwrap());
+            let len = u16::from_le_bytes([rest[8], rest[9]]) as usize;
+            let Some(datagram) = rest.get(10..10 + len) else {
+                return Err(invalid_data("truncated record"));
+            };
+
+            records.push(Record {
+                timestamp: Duration::from_micros(timestamp),
+                data: datagram.to_vec(),
+            });
+            rest = &rest[10 + len..];
+        }
+
+        Ok(records)
+    }
+
+    /// Index of the chunk containing the record that arrived at `timestamp`
+    pub fn chunk_at(&self, timestamp: Duration) -> usize {
+        self.index
+            .partition_point(|chunk| chunk.first_timestamp <= timestamp)
+            .saturating_sub(1)
+    }
+
+    /// Index of the first chunk that may contain `frame_identifier`
+    pub fn chunk_for_frame(&self, frame_identifier: u32) -> usize {
+        self.index
+            .iter()
+            .position(|chunk| chunk.last_frame >= frame_identifier)
+            .unwrap_or(self.index.len().saturating_sub(1))
+    }
+
+    /// Iterate over every record in the file, in arrival order.
+    pub fn records(&mut self) -> Records<'_> {
+        Records {
+            file: self,
+            chunk_idx: 0,
+            records: Vec::new().into_iter(),
+        }
+    }
+}
+
+/// Iterator over the records of a [`SessionFile`]
+pub struct Records<'a> {
+    file: &'a mut SessionFile,
+    chunk_idx: usize,
+    records: std::vec::IntoIter<Record>,
+}
+
+impl Iterator for Records<'_> {
+    type Item = io::Result<Record>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(record) = self.records.next() {
+                return Some(Ok(record));
+            }
+
+            if self.chunk_idx >= self.file.index.len() {
+                return None;
+            }
+
+            match self.file.read_chunk(self.chunk_idx) {
+                Ok(records) => self.records = records.into_iter(),
+                Err(e) => {
+                    self.chunk_idx = self.file.index.len();
+                    return Some(Err(e));
+                }
+            }
+            self.chunk_idx += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+    use crate::core::ParticipantData;
+
+    /// A path in the temp directory, removed when dropped
+    struct TempPath(PathBuf);
+
+    impl TempPath {
+        fn new(name: &str) -> Self {
+            let file_name = format!("solis-{name}-{}.{FILE_EXTENSION}", std::process::id());
+            Self(std::env::temp_dir().join(file_name))
+        }
+    }
+
+    impl Drop for TempPath {
+        fn drop(&mut self) {
+            let _ = fs::remove_file(&self.0);
+        }
+    }
+
+    /// A 2023 header for `frame`, padded like a small packet
+    fn datagram(frame: u32) -> Vec<u8> {
+        let mut datagram = vec![0u8; 45];
+        datagram[0..2].copy_from_slice(&2023u16.to_le_bytes());
+        datagram[6] = 3;
+        datagram[7..15].copy_from_slice(&7u64.to_le_bytes());
+        datagram[19..23].copy_from_slice(&frame.to_le_bytes());
+        datagram[29..45].fill(frame as u8);
+        datagram
+    }
+
+    fn info() -> RecordingInfo {
+        RecordingInfo {
+            session_uid: 7,
+            packet_format: 2023,
+            game_year: 23,
+            game_major_version: 1,
+            game_minor_version: 18,
+            started_at_ms: 1_700_000_000_000,
+            ..Default::default()
+        }
+    }
+
+    /// Write a datagram every 100ms for `secs` seconds, returning the records written
+    fn write_records(writer: &mut SessionWriter, secs: u32) -> Vec<(Duration, Vec<u8>)> {
+        let records: Vec<(Duration, Vec<u8>)> = (0..secs * 10)
+            .map(|frame| (Duration::from_millis(frame as u64 * 100), datagram(frame)))
+            .collect();
+        for (timestamp, data) in &records {
+            writer.write(*timestamp, data).unwrap();
+        }
+
+        records
+    }
+
+    fn read_records(file: &mut SessionFile) -> Vec<(Duration, Vec<u8>)> {
+        file.records()
+            .map(|record| record.map(|record| (record.timestamp, record.data)))
+            .collect::<io::Result<_>>()
+            .unwrap()
+    }
+
+    #[test]
+    fn round_trip() {
+        let path = TempPath::new("round-trip");
+        let participants = vec![Driver::from_participant(0, &ParticipantData::default())];
+
+        let mut writer = SessionWriter::create(&path.0, info()).unwrap();
+        let written = write_records(&mut writer, 4);
+        writer.set_participants(participants.clone());
+        writer.finish().unwrap();
+
+        let mut file = SessionFile::open(&path.0).unwrap();
+        assert!(file.is_complete());
+
+        let info = file.info();
+        assert_eq!(info.file_version, FILE_VERSION);
+        assert_eq!(info.session_uid, 7);
+        assert_eq!(info.packet_format, 2023);
+        assert_eq!(
+            (
+                info.game_year,
+                info.game_major_version,
+                info.game_minor_version
+            ),
+            (23, 1, 18)
+        );
+        assert_eq!(info.started_at_ms, 1_700_000_000_000);
+        assert_eq!(info.duration, Duration::from_millis(3900));
+        assert_eq!(info.num_datagrams, 40);
+        assert_eq!(info.participants, participants);
+
+        // A chunk is written once it spans a second,
+        // so each one ends with the record a second after its first
+        let index = file.index().to_vec();
+        assert_eq!(index.len(), 4);
+        for (i, chunk) in index.iter().enumerate() {
+            let first_frame = i as u32 * 11;
+            assert_eq!(
+                chunk.first_timestamp,
+                Duration::from_millis(first_frame as u64 * 100)
+            );
+            assert_eq!(chunk.first_frame, first_frame);
+        }
+        assert_eq!(index.last().unwrap().last_frame, 39);
+        assert_eq!(index.iter().map(|chunk| chunk.num_records).sum::<u32>(), 40);
+
+        assert_eq!(file.chunk_at(Duration::from_millis(2500)), 2);
+        assert_eq!(file.chunk_for_frame(11), 1);
+        assert_eq!(file.chunk_for_frame(100), 3);
+        assert_eq!(read_records(&mut file), written);
+    }
+
+    #[test]
+    fn reads_unfinished_and_truncated_files() {
+        let path = TempPath::new("truncated");
+
+        let mut writer = SessionWriter::create(&path.0, info()).unwrap();
+        let written = write_records(&mut writer, 4);
+        writer.finish().unwrap();
+
+        let complete = SessionFile::open(&path.0).unwrap();
+        let index = complete.index().to_vec();
+
+        // Cut off the footer and half of the last chunk
+        let last = index.last().unwrap();
+        let len = last.offset + CHUNK_HEADER_SIZE + last.compressed_len as u64 / 2;
+        fs::OpenOptions::new()
+            .write(true)
+            .open(&path.0)
+            .unwrap()
+            .set_len(len)
+            .unwrap();
+
+        let mut file = SessionFile::open(&path.0).unwrap();
+        assert!(!file.is_complete());
+        assert_eq!(file.index().len(), index.len() - 1);
+
+        let info = file.info();
+        let num_records = written.len() - last.num_records as usize;
+        assert_eq!(info.session_uid, 7);
+        assert_eq!(info.packet_format, 2023);
+        assert_eq!(info.num_datagrams, num_records as u64);
+        assert_eq!(info.duration, written[num_records - 1].0);
+        assert!(info.participants.is_empty());
+        assert_eq!(read_records(&mut file), written[..num_records]);
+
+        // Nothing but the preamble
+        fs::OpenOptions::new()
+            .write(true)
+            .open(&path.0)
+            .unwrap()
+            .set_len(PREAMBLE_SIZE)
+            .unwrap();
+        let mut file = SessionFile::open(&path.0).unwrap();
+        assert!(file.index().is_empty());
+        assert!(read_records(&mut file).is_empty());
+
+        // Not even the preamble
+        fs::OpenOptions::new()
+            .write(true)
+            .open(&path.0)
+            .unwrap()
+            .set_len(PREAMBLE_SIZE - 1)
+            .unwrap();
+        let error = SessionFile::open(&path.0).err().unwrap();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn rejects_corrupt_sizes() {
+        let path = TempPath::new("corrupt");
+        let mut writer = SessionWriter::create(&path.0, info()).unwrap();
+        write_records(&mut writer, 2);
+        writer.finish().unwrap();
+        let finished = fs::read(&path.0).unwrap();
+
+        let trailer = finished.len() - 16;
+        let offset = u64::from_le_bytes(finished[trailer..trailer + 8].try_into().unwrap());
+        let footer_start = offset as usize;
+
+        // A footer longer than the file
+        let mut bytes = finished.clone();
+        bytes[footer_start + 4..footer_start + 8].copy_from_slice(&u32::MAX.to_le_bytes());
+        fs::write(&path.0, &bytes).unwrap();
+        assert!(SessionFile::open(&path.0).is_err());
+
+        // A footer starting past the end of the file
+        let mut bytes = finished.clone();
+        bytes[trailer..trailer + 8].copy_from_slice(&u64::MAX.to_le_bytes());
+        fs::write(&path.0, &bytes).unwrap();
+        assert!(SessionFile::open(&path.0).is_err());
+
+        // An index with a chunk running past the end of the file
+        let footer_len = u32::from_le_bytes(
+            finished[footer_start + 4..footer_start + 8]
+                .try_into()
+                .unwrap(),
+        ) as usize;
+        let mut footer: serde_json::Value =
+            serde_json::from_slice(&finished[footer_start + 8..footer_start + 8 + footer_len])
+                .unwrap();
+        footer["index"][0]["compressed_len"] = u32::MAX.into();
+        let footer = serde_json::to_vec(&footer).unwrap();
+
+        let mut bytes = finished[..footer_start].to_vec();
+        bytes.extend_from_slice(FOOTER_TAG);
+        bytes.extend_from_slice(&(footer.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(&footer);
+        bytes.extend_from_slice(&offset.to_le_bytes());
+        bytes.extend_from_slice(MAGIC);
+        fs::write(&path.0, &bytes).unwrap();
+        assert!(SessionFile::open(&path.0).is_err());
+
+        // An unfinished file with a chunk claiming every record there can be,
+        // that decompresses to more than any chunk is written with
+        let mut encoder = DeflateEncoder::new(Vec::new(), Compression::default());
+        encoder
+            .write_all(&vec![0u8; CHUNK_LIMIT_BYTES as usize + 1])
+            .unwrap();
+        let compressed = encoder.finish().unwrap();
+
+        let mut bytes = finished[..PREAMBLE_SIZE as usize].to_vec();
+        bytes.extend_from_slice(CHUNK_TAG);
+        bytes.extend_from_slice(&(compressed.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(&u32::MAX.to_le_bytes());
+        bytes.extend_from_slice(&[0; 16]);
+        bytes.extend_from_slice(&compressed);
+        fs::write(&path.0, &bytes).unwrap();
+
+        // Opening an unfinished file reads its last chunk
+        let error = SessionFile::open(&path.0).err().unwrap();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    }
+}
@@ -0,0 +1,7 @@
+mod file;
+mod recorder;
+mod replay;
+
+pub use file::*;
+pub use recorder::*;
+pub use replay::*;
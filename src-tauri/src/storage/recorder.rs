@@ -0,0 +1,163 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    time::{Instant, SystemTime, UNIX_EPOCH},
+};
+
+use crate::{
+    core::{ids::PacketType, parse_header, parse_packet, PacketHeader},
+    storage::{RecordingInfo, SessionWriter, FILE_EXTENSION},
+};
+
+/// Records every datagram received into a session file per `session_uid`.
+///
+/// A new file is started whenever a datagram from a different session
+/// arrives, and the previous file is finished.
+#[derive(Debug)]
+pub struct Recorder {
+    directory: PathBuf,
+    writer: Option<SessionWriter>,
+
+    /// When the first datagram of the current session arrived
+    started: Instant,
+
+    /// Files finished by this recorder, oldest first
+    finished: Vec<PathBuf>,
+}
+
+impl Recorder {
+    /// Create a recorder writing session files to `directory`,
+    /// creating the directory if it doesn't exist.
+    pub fn new(directory: impl Into<PathBuf>) -> io::Result<Self> {
+        let directory = directory.into();
+        fs::create_dir_all(&directory)?;
+
+        Ok(Self {
+            directory,
+            writer: None,
+            started: Instant::now(),
+            finished: Vec::new(),
+        })
+    }
+
+    pub fn directory(&self) -> &Path {
+        &self.directory
+    }
+
+    /// Path of the session file currently being written
+    pub fn current_path(&self) -> Option<&Path> {
+        self.writer.as_ref().map(|writer| writer.path())
+    }
+
+    /// Session files finished by this recorder, oldest first
+    pub fn finished(&self) -> &[PathBuf] {
+        &self.finished
+    }
+
+    /// Record a datagram that arrived at `received_at`.
+    ///
+    /// Datagrams are recorded as received, even if they can not be decoded.
+    /// The header is only used to pick the session file; a datagram with no
+    /// readable header is added to the current file, or dropped if no
+    /// session has been seen yet.
+    pub fn record(&mut self, datagram: &[u8], received_at: Instant) -> io::Result<()> {
+        let header = parse_header(datagram).ok();
+
+        if let Some(header) = &header {
+            let current_uid = self.writer.as_ref().map(|w| w.info().session_uid);
+            if header.session_uid != 0 && current_uid != Some(header.session_uid) {
+                self.finish()?;
+                self.start(header, received_at)?;
+            }
+        }
+
+        let Some(writer) = self.writer.as_mut() else {
+            return Ok(());
+        };
+
+        writer.write(
+            received_at.saturating_duration_since(self.started),
+            datagram,
+        )?;
+
+        // Keep the participants in the footer up to date so
+        // the file describes who was in the session.
+        if header.is_some_and(|header| header.packet_id == PacketType::Participants) {
+            if let Some(participants) = parse_packet(datagram)
+                .ok()
+                .and_then(|packet| packet.as_participants().map(|p| p.drivers()))
+            {
+                writer.set_participants(participants);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Start a new session file for the session in `header`,
+    /// whose first datagram arrived at `received_at`
+    fn start(&mut self, header: &PacketHeader, received_at: Instant) -> io::Result<()> {
+        let started_at_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as u64;
+
+        let info = RecordingInfo {
+            session_uid: header.session_uid,
+            packet_format: header.packet_format,
+            game_year: header.game_year,
+            game_major_version: header.game_major_version,
+            game_minor_version: header.game_minor_version,
+            started_at_ms,
+            ..Default::default()
+        };
+
+        let path = self.unused_path(header.session_uid);
+        println!("[backend] Recording session to {}", path.display());
+
+        self.writer = Some(SessionWriter::create(path, info)?);
+        self.started = received_at;
+        Ok(())
+    }
+
+    /// A session that is resumed (e.g. after restarting Solis) gets a new
+    /// file instead of overwriting the earlier recording.
+    fn unused_path(&self, session_uid: u64) -> PathBuf {
+        let mut path = self
+            .directory
+            .join(format!("{session_uid:016x}.{FILE_EXTENSION}"));
+
+        let mut n = 1;
+        while path.exists() {
+            path = self
+                .directory
+                .join(format!("{session_uid:016x}-{n}.{FILE_EXTENSION}"));
+            n += 1;
+        }
+
+        path
+    }
+
+    /// Finish the current session file, if any.
+    ///
+    /// Returns the path of the finished file.
+    pub fn finish(&mut self) -> io::Result<Option<PathBuf>> {
+        let Some(writer) = self.writer.take() else {
+            return Ok(None);
+        };
+
+        let path = writer.finish()?;
+        println!("[backend] Finished recording {}", path.display());
+        self.finished.push(path.clone());
+
+        Ok(Some(path))
+    }
+}
+
+impl Drop for Recorder {
+    fn drop(&mut self) {
+        if let Err(e) = self.finish() {
+            println!("[backend] Failed to finish recording: {e}");
+        }
+    }
+}
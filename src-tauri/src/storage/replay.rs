@@ -0,0 +1,401 @@
+use std::{
+    collections::VecDeque,
+    io,
+    net::{SocketAddr, UdpSocket},
+    path::PathBuf,
+    sync::{
+        mpsc::{channel, Receiver, RecvTimeoutError, Sender},
+        Arc, Mutex,
+    },
+    thread::{self, JoinHandle},
+    time::{Duration, Instant},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::storage::{Record, RecordingInfo, SessionFile};
+
+/// How long the replay thread waits for a command
+/// before checking if the next datagram is due.
+const MAX_WAIT: Duration = Duration::from_millis(5);
+
+/// Function receiving replayed datagrams
+pub type DatagramHandler = Box<dyn FnMut(&[u8]) + Send>;
+
+/// Where replayed datagrams are sent.
+pub enum ReplayOutput {
+    /// Re-emit each datagram to a UDP address, acting as the game.
+    Udp(SocketAddr),
+
+    /// Hand each datagram to a function, e.g. to parse it
+    /// without going through a socket.
+    Direct(DatagramHandler),
+}
+
+enum Sink {
+    Udp(UdpSocket, SocketAddr),
+    Direct(DatagramHandler),
+}
+
+impl Sink {
+    fn new(output: ReplayOutput) -> io::Result<Self> {
+        Ok(match output {
+            ReplayOutput::Udp(address) => Sink::Udp(UdpSocket::bind("0.0.0.0:0")?, address),
+            ReplayOutput::Direct(f) => Sink::Direct(f),
+        })
+    }
+
+    fn send(&mut self, datagram: &[u8]) {
+        match self {
+            Sink::Udp(socket, address) => {
+                if let Err(e) = socket.send_to(datagram, *address) {
+                    println!("[backend] Failed to replay packet: {e}");
+                }
+            }
+            Sink::Direct(f) => f(datagram),
+        }
+    }
+}
+
+enum ReplayCommand {
+    Play,
+    Pause,
+    Seek(Duration),
+    SeekFrame(u32),
+    Step(u32),
+    Speed(f32),
+    Stop,
+}
+
+/// Progress of a replay
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct ReplayStatus {
+    pub path: PathBuf,
+    pub playing: bool,
+
+    /// Reached the end of the recording
+    pub finished: bool,
+    pub speed: f32,
+
+    /// Arrival time of the last datagram replayed
+    pub position: Duration,
+    pub duration: Duration,
+
+    /// Frame identifier of the last datagram replayed
+    pub frame_identifier: u32,
+    pub datagrams_sent: u64,
+}
+
+/// Plays a recorded session file back at real time, or
+/// faster/slower, as if the game was sending it.
+///
+/// The replay runs on its own thread and is controlled through
+/// this handle. Dropping the handle stops the replay.
+pub struct Replay {
+    commands: Sender<ReplayCommand>,
+    status: Arc<Mutex<ReplayStatus>>,
+    info: RecordingInfo,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl Replay {
+    /// Open the session file at `path` and start a paused replay of it.
+    pub fn open(path: impl Into<PathBuf>, output: ReplayOutput) -> io::Result<Self> {
+        let path = path.into();
+        let file = SessionFile::open(&path)?;
+        let sink = Sink::new(output)?;
+        let info = file.info().clone();
+
+        let status = Arc::new(Mutex::new(ReplayStatus {
+            path,
+            speed: 1.0,
+            duration: info.duration,
+            ..Default::default()
+        }));
+
+        let (commands, receiver) = channel();
+        let mut player = Player {
+            file,
+            sink,
+            status: Arc::clone(&status),
+            commands: receiver,
+            chunk_idx: 0,
+            queue: VecDeque::new(),
+            clock: None,
+            speed: 1.0,
+            position: Duration::ZERO,
+        };
+
+        let thread = thread::spawn(move || player.run());
+
+        Ok(Self {
+            commands,
+            status,
+            info,
+            thread: Some(thread),
+        })
+    }
+
+    /// Information about the session being replayed
+    pub fn info(&self) -> &RecordingInfo {
+        &self.info
+    }
+
+    pub fn status(&self) -> ReplayStatus {
+        self.status.lock().unwrap().clone()
+    }
+
+    pub fn play(&self) {
+        let _ = self.commands.send(ReplayCommand::Play);
+    }
+
+    pub fn pause(&self) {
+        let _ = self.commands.send(ReplayCommand::Pause);
+    }
+
+    /// Jump to `position` into the recording. Datagrams before
+    /// that point are skipped, not sent.
+    pub fn seek(&self, position: Duration) {
+        let _ = self.commands.send(ReplayCommand::Seek(position));
+    }
+
+    /// Jump to the first datagram of `frame_identifier`.
+    pub fn seek_frame(&self, frame_identifier: u32) {
+        let _ = self
+            .commands
+            .send(ReplayCommand::SeekFrame(frame_identifier));
+    }
+
+    /// Send every datagram of the next `frames` frames, then pause.
+    pub fn step(&self, frames: u32) {
+        let _ = self.commands.send(ReplayCommand::Step(frames));
+    }
+
+    /// Set the playback speed, e.g 2.0 for double speed
+    pub fn set_speed(&self, speed: f32) {
+        let _ = self.commands.send(ReplayCommand::Speed(speed));
+    }
+}
+
+impl Drop for Replay {
+    fn drop(&mut self) {
+        let _ = self.commands.send(ReplayCommand::Stop);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// State owned by the replay thread
+struct Player {
+    file: SessionFile,
+    sink: Sink,
+    status: Arc<Mutex<ReplayStatus>>,
+    commands: Receiver<ReplayCommand>,
+
+    /// Next chunk to load into the queue
+    chunk_idx: usize,
+    queue: VecDeque<Record>,
+
+    /// Wall time and recording position playback was last (re)started
+    /// at. None while paused.
+    clock: Option<(Instant, Duration)>,
+    speed: f32,
+
+    /// Arrival time of the last datagram sent
+    position: Duration,
+}
+
+impl Player {
+    fn run(&mut self) {
+        loop {
+            let wait = if self.clock.is_some() {
+                MAX_WAIT
+            } else {
+                Duration::MAX
+            };
+
+            let command = match self.commands.recv_timeout(wait) {
+                Ok(command) => Some(command),
+                Err(RecvTimeoutError::Timeout) => None,
+                Err(RecvTimeoutError::Disconnected) => return,
+            };
+
+            match command {
+                Some(ReplayCommand::Play) => self.play(),
+                Some(ReplayCommand::Pause) => self.pause(),
+                Some(ReplayCommand::Seek(position)) => self.seek(position),
+                Some(ReplayCommand::SeekFrame(frame)) => self.seek_frame(frame),
+                Some(ReplayCommand::Step(frames)) => self.step(frames),
+                Some(ReplayCommand::Speed(speed)) => self.set_speed(speed),
+                Some(ReplayCommand::Stop) => return,
+                None => {}
+            }
+
+            if self.clock.is_some() {
+                self.send_due();
+            }
+        }
+    }
+
+    /// Current position in the recording according to the playback clock
+    fn clock_position(&self) -> Duration {
+        match self.clock {
+            Some((started, position)) => position + started.elapsed().mul_f32(self.speed),
+            None => self.position,
+        }
+    }
+
+    fn play(&mut self) {
+        if self.peek().is_none() {
+            return;
+        }
+
+        self.clock = Some((Instant::now(), self.position));
+        self.update_status(|status| status.playing = true);
+    }
+
+    fn pause(&mut self) {
+        self.clock = None;
+        self.update_status(|status| status.playing = false);
+    }
+
+    fn set_speed(&mut self, speed: f32) {
+        if !speed.is_finite() || speed <= 0.0 {
+            return;
+        }
+
+        // Restart the clock from the current position so
+        // the change only affects playback from now on.
+        if self.clock.is_some() {
+            self.clock = Some((Instant::now(), self.clock_position()));
+        }
+
+        self.speed = speed;
+        self.update_status(|status| status.speed = speed);
+    }
+
+    /// Send every queued datagram that is due according to the clock
+    fn send_due(&mut self) {
+        let now = self.clock_position();
+        while self.peek().is_some_and(|record| record.timestamp <= now) {
+            self.send_next();
+        }
+
+        if self.peek().is_none() {
+            self.pause();
+            self.update_status(|status| status.finished = true);
+        }
+    }
+
+    fn step(&mut self, frames: u32) {
+        self.pause();
+
+        for _ in 0..frames {
+            let Some(frame) = self.peek().and_then(Record::frame_identifier) else {
+                // Send datagrams with no readable frame on their own
+                if self.send_next().is_none() {
+                    break;
+                }
+                continue;
+            };
+
+            while self
+                .peek()
+                .is_some_and(|record| record.frame_identifier() == Some(frame))
+            {
+                self.send_next();
+            }
+        }
+
+        let finished = self.peek().is_none();
+        self.update_status(|status| status.finished = finished);
+    }
+
+    fn seek(&mut self, position: Duration) {
+        self.load_from(self.file.chunk_at(position));
+        while self
+            .peek()
+            .is_some_and(|record| record.timestamp < position)
+        {
+            self.queue.pop_front();
+        }
+
+        self.jump_to(position);
+    }
+
+    fn seek_frame(&mut self, frame_identifier: u32) {
+        self.load_from(self.file.chunk_for_frame(frame_identifier));
+        while self.peek().is_some_and(|record| {
+            record
+                .frame_identifier()
+                .is_none_or(|frame| frame < frame_identifier)
+        }) {
+            self.queue.pop_front();
+        }
+
+        let position = self
+            .peek()
+            .map(|record| record.timestamp)
+            .unwrap_or(self.position);
+        self.jump_to(position);
+    }
+
+    /// Move the playback position, keeping the play/pause state
+    fn jump_to(&mut self, position: Duration) {
+        self.position = position;
+        if self.clock.is_some() {
+            self.clock = Some((Instant::now(), position));
+        }
+
+        let finished = self.peek().is_none();
+        self.update_status(|status| {
+            status.position = position;
+            status.finished = finished;
+        });
+    }
+
+    /// Empty the queue and continue reading from `chunk_idx`
+    fn load_from(&mut self, chunk_idx: usize) {
+        self.queue.clear();
+        self.chunk_idx = chunk_idx;
+    }
+
+    /// The next record to send, loading the next chunk if needed
+    fn peek(&mut self) -> Option<&Record> {
+        while self.queue.is_empty() && self.chunk_idx < self.file.index().len() {
+            match self.file.read_chunk(self.chunk_idx) {
+                Ok(records) => self.queue.extend(records),
+                Err(e) => println!("[backend] Skipping unreadable chunk: {e}"),
+            }
+            self.chunk_idx += 1;
+        }
+
+        self.queue.front()
+    }
+
+    /// Send the next record, returning its timestamp
+    fn send_next(&mut self) -> Option<Duration> {
+        self.peek()?;
+        let record = self.queue.pop_front()?;
+        self.sink.send(&record.data);
+        self.position = record.timestamp;
+
+        let frame = record.frame_identifier();
+        self.update_status(|status| {
+            status.position = record.timestamp;
+            status.datagrams_sent += 1;
+            if let Some(frame) = frame {
+                status.frame_identifier = frame;
+            }
+        });
+
+        Some(record.timestamp)
+    }
+
+    fn update_status(&self, update: impl FnOnce(&mut ReplayStatus)) {
+        if let Ok(mut status) = self.status.lock() {
+            update(&mut status);
+        }
+    }
+}
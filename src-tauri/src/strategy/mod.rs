@@ -1,3 +1,11 @@
+#[cfg(feature = "llm")]
 mod decision_making;
+mod neutralisation;
+mod pit;
+mod rejoin;
 
+#[cfg(feature = "llm")]
 pub use decision_making::*;
+pub use neutralisation::*;
+pub use pit::*;
+pub use rejoin::*;
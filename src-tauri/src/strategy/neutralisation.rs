@@ -0,0 +1,208 @@
+use serde::{Deserialize, Serialize};
+
+use crate::core::{SafetyCarStatus, Session, VisualTyreCompound};
+use crate::strategy::{plan_after_stop, plan_strategy, StrategyInput, StrategyPlan};
+
+/// Share of the green flag pit loss still lost by stopping
+/// while the field is slowed by a safety car
+const SAFETY_CAR_PIT_LOSS_FACTOR: f32 = 0.5;
+const VIRTUAL_SAFETY_CAR_PIT_LOSS_FACTOR: f32 = 0.6;
+
+/// Rivals are expected to box for new tyres once theirs are this worn,
+/// less if they still have to make a stop
+const RIVAL_BOX_WEAR_PERCENT: f32 = 40.0;
+const RIVAL_FIRST_STOP_BOX_WEAR_PERCENT: f32 = 20.0;
+
+/// Nobody boxes for tyres this close to the end
+const MIN_LAPS_TO_BOX: u8 = 3;
+
+/// What to do with a safety car out
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum PitCall {
+    BoxNow { compound: VisualTyreCompound },
+    StayOut,
+}
+
+/// What a rival is expected to do under the safety car
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RivalReaction {
+    pub vehicle_idx: u8,
+    pub name: Option<String>,
+    pub position: u8,
+
+    /// Seconds behind, negative if ahead
+    pub gap_s: f32,
+    pub boxes: bool,
+}
+
+/// Everything safety car advice is planned from, for one car.
+///
+/// Gathered while the session is locked, so the plans can
+/// be simulated without holding up ingest.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SafetyCarInput {
+    pub kind: SafetyCarStatus,
+    pub strategy: StrategyInput,
+
+    /// Names of the rivals, in the order of `strategy.rivals`
+    pub rival_names: Vec<Option<String>>,
+}
+
+impl SafetyCarInput {
+    /// Gather the race for the car at `vehicle_idx` while a safety car or virtual
+    /// safety car is out. None under green flags, or until the car's pace and tyres are known.
+    pub fn new(session: &Session, vehicle_idx: u8) -> Option<Self> {
+        let kind = session.race_state.snapshot().session.as_ref()?.safety_car;
+        if !matches!(kind, SafetyCarStatus::Full | SafetyCarStatus::Virtual) {
+            return None;
+        }
+
+        let strategy = StrategyInput::new(session, vehicle_idx)?;
+        let rival_names = strategy
+            .rivals
+            .iter()
+            .map(|rival| {
+                session
+                    .participants
+                    .name(rival.vehicle_idx)
+                    .map(String::from)
+            })
+            .collect();
+
+        Some(Self {
+            kind,
+            strategy,
+            rival_names,
+        })
+    }
+}
+
+/// Whether a car should make the most of a safety car or virtual
+/// safety car to pit, and what the cars around it will do.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SafetyCarAdvice {
+    pub vehicle_idx: u8,
+    pub kind: SafetyCarStatus,
+    pub lap: u8,
+
+    /// Time lost by a pit stop under green flags, and with the field neutralised
+    pub green_pit_loss_s: f32,
+    pub neutralised_pit_loss_s: f32,
+    pub call: PitCall,
+
+    /// Race time gained by boxing now instead of staying out on the
+    /// best plan, negative if staying out is faster. None if either
+    /// can't be planned.
+    pub box_gain_s: Option<f32>,
+    pub stay_out: Option<StrategyPlan>,
+    pub box_now: Option<StrategyPlan>,
+    pub rivals: Vec<RivalReaction>,
+
+    /// The call as the engineer would say it on the radio
+    pub message: String,
+}
+
+impl SafetyCarAdvice {
+    /// Plan staying out and boxing now from `input`. The plans are simulated,
+    /// so `input` should be gathered first and the session unlocked.
+    pub fn new(input: &SafetyCarInput) -> Self {
+        let (kind, rival_names) = (input.kind, &input.rival_names);
+        let input = &input.strategy;
+        let factor = match kind {
+            SafetyCarStatus::Virtual => VIRTUAL_SAFETY_CAR_PIT_LOSS_FACTOR,
+            _ => SAFETY_CAR_PIT_LOSS_FACTOR,
+        };
+        let neutralised_pit_loss_s = input.pit_loss_s * factor;
+
+        let stay_out = plan_strategy(input).plans.into_iter().next();
+        let box_now = input
+            .compounds
+            .iter()
+            .filter_map(|compound| plan_after_stop(input, compound, neutralised_pit_loss_s))
+            .min_by(|a, b| a.race_time_s.total_cmp(&b.race_time_s));
+
+        let box_gain_s = match (&stay_out, &box_now) {
+            (Some(stay_out), Some(box_now)) => Some(stay_out.race_time_s - box_now.race_time_s),
+            _ => None,
+        };
+        let box_faster = box_gain_s.map_or(stay_out.is_none(), |gain_s| gain_s > 0.0);
+        let call = match box_now.as_ref().and_then(|plan| plan.stints.get(1)) {
+            Some(stint) if box_faster => PitCall::BoxNow {
+                compound: stint.visual_compound,
+            },
+            _ => PitCall::StayOut,
+        };
+
+        let rivals = input
+            .rivals
+            .iter()
+            .zip(rival_names)
+            .map(|(rival, name)| {
+                let wear_percent = rival.tyres.map_or(0.0, |tyres| tyres.wear_percent);
+                let box_wear_percent = if rival.num_pit_stops == 0 {
+                    RIVAL_FIRST_STOP_BOX_WEAR_PERCENT
+                } else {
+                    RIVAL_BOX_WEAR_PERCENT
+                };
+
+                RivalReaction {
+                    vehicle_idx: rival.vehicle_idx,
+                    name: name.clone(),
+                    position: rival.position,
+                    gap_s: rival.gap_s,
+                    boxes: input.laps_remaining > MIN_LAPS_TO_BOX
+                        && wear_percent >= box_wear_percent,
+                }
+            })
+            .collect();
+
+        let mut advice = Self {
+            vehicle_idx: input.vehicle_idx,
+            kind,
+            lap: input.lap,
+            green_pit_loss_s: input.pit_loss_s,
+            neutralised_pit_loss_s,
+            call,
+            box_gain_s,
+            stay_out,
+            box_now,
+            rivals,
+            message: String::new(),
+        };
+        advice.message = advice.radio_message();
+
+        advice
+    }
+
+    fn radio_message(&self) -> String {
+        let kind = match self.kind {
+            SafetyCarStatus::Virtual => "Virtual safety car",
+            _ => "Safety car",
+        };
+        let call = match self.call {
+            PitCall::BoxNow { compound } => format!("Box, box for {compound:?}."),
+            PitCall::StayOut => "Stay out.".to_string(),
+        };
+
+        // The nearest cars decide whether track position is lost
+        let mut nearest: Vec<&RivalReaction> = self.rivals.iter().collect();
+        nearest.sort_by(|a, b| a.gap_s.abs().total_cmp(&b.gap_s.abs()));
+        let reactions: Vec<String> = nearest
+            .iter()
+            .take(2)
+            .map(|rival| {
+                let name = rival
+                    .name
+                    .clone()
+                    .unwrap_or_else(|| format!("Car {}", rival.vehicle_idx));
+                let action = if rival.boxes { "boxing" } else { "staying out" };
+                format!("{name} expected {action}.")
+            })
+            .collect();
+
+        [format!("{kind}. {call}"), reactions.join(" ")]
+            .join(" ")
+            .trim_end()
+            .to_string()
+    }
+}
@@ -0,0 +1,892 @@
+use serde::{Deserialize, Serialize};
+
+use crate::core::{
+    Gap, Session, TimingEntry, TyreCompound, VisualTyreCompound, TYRE_CLIFF_WEAR_PERCENT,
+};
+
+/// Lap time gained for every kg of fuel burned
+pub const FUEL_EFFECT_S_PER_KG: f32 = 0.03;
+
+/// Lap time lost for every percent of wear on the most worn tyre
+const WEAR_LOSS_S_PER_PERCENT: f32 = 0.04;
+
+/// Extra lap time lost for every percent of wear past the cliff
+const CLIFF_LOSS_S_PER_PERCENT: f32 = 0.25;
+
+/// Time lost on the lap out of the pits while new tyres warm up
+const OUT_LAP_WARM_UP_S: f32 = 1.0;
+
+/// Rejoining less than this behind a car means being held up by it
+pub(crate) const TRAFFIC_WINDOW_S: f32 = 1.0;
+
+/// Time lost to traffic on every lap spent behind a car after rejoining
+const TRAFFIC_LOSS_S: f32 = 0.5;
+const TRAFFIC_LAPS: f32 = 2.0;
+
+/// Used until a pit stop, or a lap's burn, has been measured
+const DEFAULT_PIT_LOSS_S: f32 = 20.0;
+const DEFAULT_FUEL_BURN_KG: f32 = 1.5;
+
+/// Fewest laps on a new set of tyres
+const MIN_STINT_LAPS: u8 = 3;
+const MAX_STOPS: u8 = 3;
+
+/// Number of plans kept for every number of stops
+const PLANS_PER_STOP_COUNT: usize = 3;
+
+/// Number of recent laps a car's race pace is averaged over
+const RECENT_LAPS: usize = 3;
+
+/// How a compound performs over a stint.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct CompoundModel {
+    pub compound: TyreCompound,
+    pub visual_compound: VisualTyreCompound,
+
+    /// Lap time compared to the softest compound when both are new
+    pub pace_offset_s: f32,
+
+    /// Wear added to the most worn tyre on a lap of a new set
+    pub base_wear_per_lap: f32,
+
+    /// How much more wear is added every lap the set gets older
+    pub wear_increase_per_lap: f32,
+
+    /// Whether the wear was measured this session,
+    /// or is a default for the compound
+    pub measured: bool,
+}
+
+impl CompoundModel {
+    /// Model of a compound, from its degradation curve at the
+    /// track if enough laps were driven on it
+    fn new(session: &Session, compound: TyreCompound, visual: VisualTyreCompound) -> Option<Self> {
+        let (pace_offset_s, default_wear) = compound_defaults(visual)?;
+        let curve = session.tyres.curve(compound);
+
+        Some(Self {
+            compound,
+            visual_compound: visual,
+            pace_offset_s,
+            base_wear_per_lap: curve.map_or(default_wear, |curve| curve.base_wear_per_lap),
+            wear_increase_per_lap: curve.map_or(0.0, |curve| curve.wear_increase_per_lap),
+            measured: curve.is_some(),
+        })
+    }
+
+    fn wear_per_lap(&self, age_laps: u8) -> f32 {
+        (self.base_wear_per_lap + self.wear_increase_per_lap * age_laps as f32).max(0.0)
+    }
+
+    /// Lap time lost to the compound and its wear
+    pub(crate) fn lap_loss_s(&self, wear_percent: f32) -> f32 {
+        let past_cliff = (wear_percent - TYRE_CLIFF_WEAR_PERCENT).max(0.0);
+        self.pace_offset_s
+            + WEAR_LOSS_S_PER_PERCENT * wear_percent
+            + CLIFF_LOSS_S_PER_PERCENT * past_cliff
+    }
+}
+
+/// Lap time compared to the softest compound, and wear per lap,
+/// of the dry compounds. Wet tyres aren't planned for.
+fn compound_defaults(visual: VisualTyreCompound) -> Option<(f32, f32)> {
+    let defaults = match visual {
+        VisualTyreCompound::Soft | VisualTyreCompound::F2SuperSoft => (0.0, 2.5),
+        VisualTyreCompound::Medium | VisualTyreCompound::F2Soft => (0.5, 1.8),
+        VisualTyreCompound::Hard | VisualTyreCompound::F2Medium => (1.0, 1.3),
+        VisualTyreCompound::F2Hard => (1.4, 1.0),
+        VisualTyreCompound::ClassicDry => (0.0, 1.8),
+        _ => return None,
+    };
+
+    Some(defaults)
+}
+
+/// A set of tyres, and how worn it is
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct TyreState {
+    pub model: CompoundModel,
+    pub age_laps: u8,
+
+    /// Wear of the most worn tyre
+    pub wear_percent: f32,
+}
+
+impl TyreState {
+    fn new_set(model: CompoundModel) -> Self {
+        Self {
+            model,
+            age_laps: 0,
+            wear_percent: 0.0,
+        }
+    }
+
+    /// Lap time lost on the next lap, after which the set is a lap older
+    fn drive_lap(&mut self) -> f32 {
+        let loss = self.model.lap_loss_s(self.wear_percent);
+        self.age_laps = self.age_laps.saturating_add(1);
+        self.wear_percent += self.model.wear_per_lap(self.age_laps);
+        loss
+    }
+}
+
+/// Another car in the race
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct Rival {
+    pub vehicle_idx: u8,
+    pub position: u8,
+
+    /// Seconds behind the car being planned for, negative if ahead
+    pub gap_s: f32,
+
+    /// Recent race pace
+    pub lap_time_s: f32,
+    pub num_pit_stops: u8,
+    pub tyres: Option<TyreState>,
+}
+
+/// Everything a pit strategy is planned from, for one car.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct StrategyInput {
+    pub vehicle_idx: u8,
+
+    /// Lap being driven
+    pub lap: u8,
+
+    /// Laps left including the current lap
+    pub laps_remaining: u8,
+    pub position: u8,
+
+    /// Recent race pace
+    pub lap_time_s: f32,
+    pub fuel_burn_kg: f32,
+
+    /// Time lost by a pit stop, measured or a default
+    pub pit_loss_s: f32,
+    pub tyres: TyreState,
+
+    /// Compounds that can be fitted at a stop
+    pub compounds: Vec<CompoundModel>,
+
+    /// Compounds already raced on, including the fitted set
+    pub used_compounds: Vec<VisualTyreCompound>,
+    pub rivals: Vec<Rival>,
+
+    /// The game's pit window and expected rejoin position. Only sent for the player's car.
+    pub game_pit_window: Option<(u8, u8)>,
+    pub game_rejoin_position: Option<u8>,
+}
+
+impl StrategyInput {
+    /// Gather the state of the race for the car at `vehicle_idx`.
+    ///
+    /// None outside of races, or until a lap was timed and
+    /// the car's tyres and gap to the leader are known.
+    pub fn new(session: &Session, vehicle_idx: u8) -> Option<Self> {
+        let snapshot = session.race_state.snapshot();
+        let info = snapshot.session.as_ref()?;
+        if !info.session_type.is_race() || info.total_laps == 0 {
+            return None;
+        }
+
+        let car = snapshot.car(vehicle_idx)?;
+        let lap = car.lap.as_ref()?;
+        let tyres = tyre_state(session, vehicle_idx)?;
+        let lap_time_s = race_pace_s(session, vehicle_idx)?;
+
+        // Sets allocated to the car, or the usual dry compounds if not sent
+        let mut compounds: Vec<CompoundModel> = Vec::new();
+        let sets: Vec<(TyreCompound, VisualTyreCompound)> = if car.tyre_sets.is_empty() {
+            [
+                VisualTyreCompound::Soft,
+                VisualTyreCompound::Medium,
+                VisualTyreCompound::Hard,
+            ]
+            .into_iter()
+            .map(|visual| {
+                let compound = if visual == tyres.model.visual_compound {
+                    tyres.model.compound
+                } else {
+                    TyreCompound::Unknown
+                };
+                (compound, visual)
+            })
+            .collect()
+        } else {
+            car.tyre_sets
+                .iter()
+                .filter(|set| set.available && !set.fitted)
+                .map(|set| (set.compound, set.visual_compound))
+                .collect()
+        };
+        for (compound, visual) in sets {
+            let model = CompoundModel::new(session, compound, visual);
+            if let Some(model) = model.filter(|model| !compounds.contains(model)) {
+                compounds.push(model);
+            }
+        }
+
+        let mut used_compounds: Vec<VisualTyreCompound> = session
+            .history
+            .car(vehicle_idx)
+            .map(|history| {
+                history
+                    .stints
+                    .iter()
+                    .map(|stint| stint.visual_compound)
+                    .collect()
+            })
+            .unwrap_or_default();
+        used_compounds.push(tyres.model.visual_compound);
+        used_compounds.dedup();
+
+        let tower = session.timing.tower();
+        let our_gap = tower.entry(vehicle_idx).and_then(gap_s)?;
+        let rivals = tower
+            .entries
+            .iter()
+            .filter(|entry| entry.vehicle_idx != vehicle_idx)
+            .filter_map(|entry| {
+                let gap_to_leader = gap_s(entry)?;
+                Some(Rival {
+                    vehicle_idx: entry.vehicle_idx,
+                    position: entry.position,
+                    gap_s: gap_to_leader - our_gap,
+                    lap_time_s: race_pace_s(session, entry.vehicle_idx)?,
+                    num_pit_stops: entry.num_pit_stops,
+                    tyres: tyre_state(session, entry.vehicle_idx),
+                })
+            })
+            .collect();
+
+        let is_player = vehicle_idx == snapshot.player_car_index;
+        Some(Self {
+            vehicle_idx,
+            lap: lap.lap_number,
+            laps_remaining: (info.total_laps + 1).saturating_sub(lap.lap_number),
+            position: lap.position,
+            lap_time_s,
+            fuel_burn_kg: session
+                .fuel
+                .analysis(vehicle_idx)
+                .and_then(|fuel| fuel.burn_per_lap_kg)
+                .unwrap_or(DEFAULT_FUEL_BURN_KG),
+            pit_loss_s: session
+                .timing
+                .pit_loss()
+                .map_or(DEFAULT_PIT_LOSS_S, |loss| loss.as_secs_f32()),
+            tyres,
+            compounds,
+            used_compounds,
+            rivals,
+            game_pit_window: (is_player && info.pit_stop_window_ideal_lap > 0).then_some((
+                info.pit_stop_window_ideal_lap,
+                info.pit_stop_window_latest_lap,
+            )),
+            game_rejoin_position: (is_player && info.pit_stop_rejoin_position > 0)
+                .then_some(info.pit_stop_rejoin_position),
+        })
+    }
+}
+
+/// Gap to the leader in seconds, None if laps down or unknown
+fn gap_s(entry: &TimingEntry) -> Option<f32> {
+    if entry.position == 1 {
+        return Some(0.0);
+    }
+
+    match entry.gap_to_leader? {
+        Gap::Time(time) => Some(time.as_secs_f32()),
+        Gap::Laps(_) => None,
+    }
+}
+
+fn tyre_state(session: &Session, vehicle_idx: u8) -> Option<TyreState> {
+    let analysis = session.tyres.analysis(vehicle_idx)?;
+    Some(TyreState {
+        model: CompoundModel::new(session, analysis.compound, analysis.visual_compound)?,
+        age_laps: analysis.age_laps,
+        wear_percent: analysis.wear_percent.into_iter().fold(0.0, f32::max),
+    })
+}
+
+/// Average of the car's last few valid laps, leaving out
+/// the laps into and out of the pits
+fn race_pace_s(session: &Session, vehicle_idx: u8) -> Option<f32> {
+    let pit_laps: Vec<u8> = session
+        .timing
+        .pit_stops()
+        .iter()
+        .filter(|stop| stop.vehicle_idx == vehicle_idx)
+        .flat_map(|stop| [stop.lap, stop.lap + 1])
+        .collect();
+
+    let recent: Vec<f32> = session
+        .timing
+        .laps(vehicle_idx)
+        .iter()
+        .rev()
+        .filter(|lap| lap.valid && !pit_laps.contains(&lap.lap))
+        .take(RECENT_LAPS)
+        .map(|lap| lap.time.as_secs_f32())
+        .collect();
+
+    (!recent.is_empty()).then(|| recent.iter().sum::<f32>() / recent.len() as f32)
+}
+
+/// A run of laps on one set of tyres
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct PlannedStint {
+    pub compound: TyreCompound,
+    pub visual_compound: VisualTyreCompound,
+    pub start_lap: u8,
+    pub end_lap: u8,
+}
+
+/// A way of getting to the end of the race.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct StrategyPlan {
+    pub stops: u8,
+
+    /// Laps to pit at the end of
+    pub pit_laps: Vec<u8>,
+
+    /// The first stint is on the fitted set
+    pub stints: Vec<PlannedStint>,
+
+    /// Predicted time to the end of the race
+    pub race_time_s: f32,
+
+    /// Predicted finishing position among the cars on the same lap
+    pub position: u8,
+}
+
+/// Time gained on a rival by stopping before or after them.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct PitDuel {
+    pub rival: u8,
+
+    /// Seconds the rival is behind, negative if ahead
+    pub gap_s: f32,
+
+    /// Gained by pitting this lap, a lap before the rival.
+    /// Passes a rival ahead if more than the gap.
+    pub undercut_gain_s: f32,
+
+    /// Gained by staying out a lap after the rival pits this lap
+    pub overcut_gain_s: f32,
+}
+
+/// Every plan simulated for a car, fastest first.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct StrategyReport {
+    pub input: StrategyInput,
+    pub plans: Vec<StrategyPlan>,
+
+    /// Against the cars directly ahead and behind
+    pub duels: Vec<PitDuel>,
+}
+
+impl StrategyReport {
+    pub fn best(&self) -> Option<&StrategyPlan> {
+        self.plans.first()
+    }
+}
+
+/// Simulate staying out, and one, two and three stop plans for the
+/// rest of the race, ranked by predicted race time.
+pub fn plan_strategy(input: &StrategyInput) -> StrategyReport {
+    let laps = input.laps_remaining;
+    let mut plans = Vec::new();
+
+    // Time lost to tyres over the first `n` laps of the fitted set, and of
+    // a new set of every compound. Stints are looked up instead of driven.
+    let fitted_loss = stint_losses(input.tyres, laps);
+    let new_losses: Vec<Vec<f32>> = input
+        .compounds
+        .iter()
+        .map(|&model| stint_losses(TyreState::new_set(model), laps))
+        .collect();
+
+    // Time lost to traffic rejoining after a stop at the end of each lap
+    let traffic: Vec<f32> = (0..=laps).map(|lap| traffic_loss_s(input, lap)).collect();
+
+    let rival_times = rival_times(input, &new_losses);
+
+    // Staying out is only a plan once two compounds were used
+    for stops in 0..=MAX_STOPS {
+        let mut best: Vec<(f32, Vec<u8>, Vec<usize>)> = Vec::new();
+        let mut lengths = Vec::with_capacity(stops as usize + 1);
+        let mut compounds = Vec::with_capacity(stops as usize);
+
+        for_each_split(laps, stops, &mut lengths, &mut |lengths| {
+            for_each_sequence(
+                input.compounds.len(),
+                stops,
+                &mut compounds,
+                &mut |compounds| {
+                    if !uses_two_compounds(input, compounds) {
+                        return;
+                    }
+
+                    let mut time = fitted_loss[lengths[0] as usize];
+                    let mut lap = 0;
+                    for (stint, &compound) in compounds.iter().enumerate() {
+                        lap += lengths[stint];
+                        time += input.pit_loss_s + OUT_LAP_WARM_UP_S + traffic[lap as usize];
+                        time += new_losses[compound][lengths[stint + 1] as usize];
+                    }
+
+                    keep_best(&mut best, (time, lengths.to_vec(), compounds.to_vec()));
+                },
+            );
+        });
+
+        for (loss, lengths, compounds) in best {
+            plans.push(build_plan(input, loss, &lengths, &compounds, &rival_times));
+        }
+    }
+
+    plans.sort_by(|a, b| a.race_time_s.total_cmp(&b.race_time_s));
+
+    StrategyReport {
+        input: input.clone(),
+        duels: duels(input, plans.iter().find(|plan| plan.stops > 0)),
+        plans,
+    }
+}
+
+/// Best plan when stopping for `compound` at the end of the current lap,
+/// with the stop costing `pit_loss_s`, e.g. less under a safety car.
+///
+/// The race time can be compared with the plans of [`plan_strategy`].
+pub fn plan_after_stop(
+    input: &StrategyInput,
+    compound: &CompoundModel,
+    pit_loss_s: f32,
+) -> Option<StrategyPlan> {
+    if input.laps_remaining < 2 {
+        return None;
+    }
+
+    let current_lap_loss_s = input.tyres.model.lap_loss_s(input.tyres.wear_percent);
+    let base_s = input.lap_time_s - current_lap_loss_s;
+
+    // The rest of the race on the new set, from the next lap
+    let mut after = input.clone();
+    after.lap += 1;
+    after.laps_remaining -= 1;
+    after.tyres = TyreState::new_set(*compound);
+    after.lap_time_s = base_s + compound.lap_loss_s(0.0);
+    if !after.used_compounds.contains(&compound.visual_compound) {
+        after.used_compounds.push(compound.visual_compound);
+    }
+    let mut plan = plan_strategy(&after).plans.into_iter().next()?;
+
+    // Add the current lap and the stop. Every later lap
+    // has a lap more of fuel burned than `after` assumed.
+    let fuel_gain_s = FUEL_EFFECT_S_PER_KG * input.fuel_burn_kg * after.laps_remaining as f32;
+    plan.race_time_s += input.lap_time_s + pit_loss_s + OUT_LAP_WARM_UP_S - fuel_gain_s;
+
+    plan.stops += 1;
+    plan.pit_laps.insert(0, input.lap);
+    plan.stints.insert(
+        0,
+        PlannedStint {
+            compound: input.tyres.model.compound,
+            visual_compound: input.tyres.model.visual_compound,
+            start_lap: input.lap,
+            end_lap: input.lap,
+        },
+    );
+
+    let new_losses: Vec<Vec<f32>> = input
+        .compounds
+        .iter()
+        .map(|&model| stint_losses(TyreState::new_set(model), input.laps_remaining))
+        .collect();
+    plan.position = position(&rival_times(input, &new_losses), plan.race_time_s);
+
+    Some(plan)
+}
+
+/// Cumulative time lost over the first n laps on `tyres`, for n up to `laps`
+fn stint_losses(mut tyres: TyreState, laps: u8) -> Vec<f32> {
+    let mut losses = vec![0.0];
+    for _ in 0..laps {
+        let previous = losses[losses.len() - 1];
+        losses.push(previous + tyres.drive_lap());
+    }
+
+    losses
+}
+
+/// Time lost if a rival is just ahead when rejoining after a stop at the end of `lap`
+fn traffic_loss_s(input: &StrategyInput, lap: u8) -> f32 {
+    let held_up = input.rivals.iter().any(|rival| {
+        // Rivals close in by the difference in pace every lap
+        let gap_s = rival.gap_s - (input.lap_time_s - rival.lap_time_s) * lap as f32;
+        let ahead_after_stop_s = input.pit_loss_s - gap_s;
+        ahead_after_stop_s > 0.0 && ahead_after_stop_s < TRAFFIC_WINDOW_S
+    });
+
+    if held_up {
+        TRAFFIC_LOSS_S * TRAFFIC_LAPS
+    } else {
+        0.0
+    }
+}
+
+/// Every way to split `laps` into `stops + 1` stints, the first of which
+/// can be a single lap, and the rest at least [`MIN_STINT_LAPS`]
+fn for_each_split(laps: u8, stops: u8, lengths: &mut Vec<u8>, f: &mut impl FnMut(&[u8])) {
+    let stints_left = stops as usize + 1 - lengths.len();
+    let min_length = if lengths.is_empty() {
+        1
+    } else {
+        MIN_STINT_LAPS
+    };
+
+    if stints_left == 1 {
+        if laps >= min_length {
+            lengths.push(laps);
+            f(lengths);
+            lengths.pop();
+        }
+        return;
+    }
+
+    let reserved = MIN_STINT_LAPS * (stints_left as u8 - 1);
+    for length in min_length..=laps.saturating_sub(reserved) {
+        lengths.push(length);
+        for_each_split(laps - length, stops, lengths, f);
+        lengths.pop();
+    }
+}
+
+/// Every sequence of `stops` compounds, by index
+fn for_each_sequence(
+    num_compounds: usize,
+    stops: u8,
+    sequence: &mut Vec<usize>,
+    f: &mut impl FnMut(&[usize]),
+) {
+    if sequence.len() == stops as usize {
+        f(sequence);
+        return;
+    }
+
+    for compound in 0..num_compounds {
+        sequence.push(compound);
+        for_each_sequence(num_compounds, stops, sequence, f);
+        sequence.pop();
+    }
+}
+
+/// Dry races have to be run on at least two different compounds
+fn uses_two_compounds(input: &StrategyInput, compounds: &[usize]) -> bool {
+    let fitted = input.tyres.model.visual_compound;
+    input.used_compounds.iter().any(|&used| used != fitted)
+        || compounds
+            .iter()
+            .any(|&compound| input.compounds[compound].visual_compound != fitted)
+}
+
+fn keep_best<T>(best: &mut Vec<(f32, T, Vec<usize>)>, candidate: (f32, T, Vec<usize>)) {
+    if best.len() == PLANS_PER_STOP_COUNT
+        && best
+            .last()
+            .is_some_and(|(worst, _, _)| candidate.0 >= *worst)
+    {
+        return;
+    }
+
+    // Only the best split is kept for every compound sequence
+    if let Some(same) = best.iter_mut().find(|(_, _, seq)| *seq == candidate.2) {
+        if candidate.0 < same.0 {
+            *same = candidate;
+        }
+    } else {
+        best.push(candidate);
+    }
+
+    best.sort_by(|a, b| a.0.total_cmp(&b.0));
+    best.truncate(PLANS_PER_STOP_COUNT);
+}
+
+/// Time to the end of the race at a recent pace of `lap_time_s` on `tyres`,
+/// given the time lost to tyres and stops. Every lap gets faster as fuel burns.
+fn race_time_s(input: &StrategyInput, lap_time_s: f32, tyres: &TyreState, loss_s: f32) -> f32 {
+    let laps = input.laps_remaining as f32;
+    let base_s = lap_time_s - tyres.model.lap_loss_s(tyres.wear_percent);
+    let fuel_gain_s = FUEL_EFFECT_S_PER_KG * input.fuel_burn_kg * laps * (laps - 1.0) / 2.0;
+    base_s * laps + loss_s - fuel_gain_s
+}
+
+/// Race time of a rival on its best one stop plan, or staying out if it has
+/// already stopped. Rivals with unknown tyres are assumed to be on ours.
+fn rival_race_time_s(input: &StrategyInput, rival: &Rival, new_losses: &[Vec<f32>]) -> f32 {
+    let laps = input.laps_remaining as usize;
+    let tyres = rival.tyres.unwrap_or(input.tyres);
+    let fitted_loss = &stint_losses(tyres, input.laps_remaining);
+
+    let loss_s = if rival.num_pit_stops > 0 {
+        fitted_loss[laps]
+    } else {
+        let min_stint = MIN_STINT_LAPS as usize;
+        (1..=laps.saturating_sub(min_stint))
+            .flat_map(|length| {
+                new_losses.iter().map(move |new_loss| {
+                    fitted_loss[length]
+                        + input.pit_loss_s
+                        + OUT_LAP_WARM_UP_S
+                        + new_loss[laps - length]
+                })
+            })
+            .reduce(f32::min)
+            .unwrap_or(fitted_loss[laps])
+    };
+
+    rival.gap_s + race_time_s(input, rival.lap_time_s, &tyres, loss_s)
+}
+
+/// Predicted race time of every rival, behind us if positive
+fn rival_times(input: &StrategyInput, new_losses: &[Vec<f32>]) -> Vec<f32> {
+    input
+        .rivals
+        .iter()
+        .map(|rival| rival_race_time_s(input, rival, new_losses))
+        .collect()
+}
+
+/// Finishing position with a race time of `race_time_s`
+fn position(rival_times: &[f32], race_time_s: f32) -> u8 {
+    1 + rival_times
+        .iter()
+        .filter(|&&rival_time_s| rival_time_s < race_time_s)
+        .count() as u8
+}
+
+fn build_plan(
+    input: &StrategyInput,
+    loss_s: f32,
+    lengths: &[u8],
+    compounds: &[usize],
+    rival_times: &[f32],
+) -> StrategyPlan {
+    let race_time_s = race_time_s(input, input.lap_time_s, &input.tyres, loss_s);
+
+    let mut stints = Vec::with_capacity(lengths.len());
+    let mut start_lap = input.lap;
+    let models = std::iter::once(input.tyres.model)
+        .chain(compounds.iter().map(|&compound| input.compounds[compound]));
+    for (&length, model) in lengths.iter().zip(models) {
+        stints.push(PlannedStint {
+            compound: model.compound,
+            visual_compound: model.visual_compound,
+            start_lap,
+            end_lap: start_lap + length - 1,
+        });
+        start_lap += length;
+    }
+
+    StrategyPlan {
+        stops: compounds.len() as u8,
+        pit_laps: stints[..stints.len() - 1]
+            .iter()
+            .map(|stint| stint.end_lap)
+            .collect(),
+        stints,
+        race_time_s,
+        position: position(rival_times, race_time_s),
+    }
+}
+
+/// Undercut and overcut against the cars directly ahead and behind,
+/// onto the compound of the first stop of the best plan
+fn duels(input: &StrategyInput, best: Option<&StrategyPlan>) -> Vec<PitDuel> {
+    let Some(next) = best.and_then(|plan| plan.stints.get(1)).and_then(|stint| {
+        input
+            .compounds
+            .iter()
+            .find(|model| model.visual_compound == stint.visual_compound)
+    }) else {
+        return Vec::new();
+    };
+
+    let ahead = input
+        .rivals
+        .iter()
+        .filter(|rival| rival.gap_s < 0.0)
+        .max_by(|a, b| a.gap_s.total_cmp(&b.gap_s));
+    let behind = input
+        .rivals
+        .iter()
+        .filter(|rival| rival.gap_s > 0.0)
+        .min_by(|a, b| a.gap_s.total_cmp(&b.gap_s));
+
+    let our_base_s = input.lap_time_s - input.tyres.model.lap_loss_s(input.tyres.wear_percent);
+    ahead
+        .into_iter()
+        .chain(behind)
+        .filter_map(|rival| {
+            let tyres = rival.tyres?;
+            let rival_base_s = rival.lap_time_s - tyres.model.lap_loss_s(tyres.wear_percent);
+
+            // The two laps after the first car stops, until both have new tyres.
+            // Pit losses are the same for both cars.
+            let two_laps = |base_s: f32, old: TyreState, stops_first: bool| {
+                let mut old = old;
+                let mut new = TyreState::new_set(*next);
+                if stops_first {
+                    2.0 * base_s + OUT_LAP_WARM_UP_S + new.drive_lap() + new.drive_lap()
+                } else {
+                    2.0 * base_s + old.drive_lap() + OUT_LAP_WARM_UP_S + new.drive_lap()
+                }
+            };
+
+            Some(PitDuel {
+                rival: rival.vehicle_idx,
+                gap_s: rival.gap_s,
+                undercut_gain_s: two_laps(rival_base_s, tyres, false)
+                    - two_laps(our_base_s, input.tyres, true),
+                overcut_gain_s: two_laps(rival_base_s, tyres, true)
+                    - two_laps(our_base_s, input.tyres, false),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A compound that doesn't wear, so only its pace matters
+    fn compound(visual_compound: VisualTyreCompound, pace_offset_s: f32) -> CompoundModel {
+        CompoundModel {
+            compound: TyreCompound::Unknown,
+            visual_compound,
+            pace_offset_s,
+            base_wear_per_lap: 0.0,
+            wear_increase_per_lap: 0.0,
+            measured: false,
+        }
+    }
+
+    /// 10 laps left from lap 20 on new softs, a 20s pit loss and no fuel effect
+    fn input() -> StrategyInput {
+        let soft = compound(VisualTyreCompound::Soft, 0.0);
+        StrategyInput {
+            vehicle_idx: 0,
+            lap: 20,
+            laps_remaining: 10,
+            position: 1,
+            lap_time_s: 90.0,
+            fuel_burn_kg: 0.0,
+            pit_loss_s: 20.0,
+            tyres: TyreState::new_set(soft),
+            compounds: vec![
+                soft,
+                compound(VisualTyreCompound::Medium, 0.5),
+                compound(VisualTyreCompound::Hard, 1.0),
+            ],
+            used_compounds: vec![VisualTyreCompound::Soft],
+            rivals: Vec::new(),
+            game_pit_window: None,
+            game_rejoin_position: None,
+        }
+    }
+
+    fn splits(laps: u8, stops: u8) -> Vec<Vec<u8>> {
+        let mut splits = Vec::new();
+        for_each_split(laps, stops, &mut Vec::new(), &mut |lengths| {
+            splits.push(lengths.to_vec())
+        });
+        splits
+    }
+
+    #[test]
+    fn splits_cover_every_lap_in_long_enough_stints() {
+        for stops in 0..=MAX_STOPS {
+            for lengths in splits(20, stops) {
+                assert_eq!(lengths.len(), stops as usize + 1);
+                assert_eq!(lengths.iter().sum::<u8>(), 20);
+                assert!(lengths[0] >= 1);
+                assert!(lengths[1..].iter().all(|&length| length >= MIN_STINT_LAPS));
+            }
+        }
+
+        assert_eq!(splits(10, 0), vec![vec![10]]);
+
+        // The first stint is 1 to 7 laps, leaving at least 3 for the second
+        assert_eq!(splits(10, 1).len(), 7);
+        assert_eq!(splits(10, 1)[0], vec![1, 9]);
+        assert_eq!(splits(10, 1)[6], vec![7, 3]);
+
+        // Ways to share the 3 laps left over the minimum between 3 stints
+        assert_eq!(splits(10, 2).len(), 10);
+
+        assert!(splits(6, 2).is_empty());
+        assert_eq!(splits(7, 2), vec![vec![1, 3, 3]]);
+    }
+
+    #[test]
+    fn ranks_plans_by_race_time() {
+        let report = plan_strategy(&input());
+        let plans = &report.plans;
+        assert!(plans
+            .windows(2)
+            .all(|pair| pair[0].race_time_s <= pair[1].race_time_s));
+
+        // Only softs were used, so staying out isn't allowed. The best is
+        // as few laps as possible on the slowest compound that isn't soft.
+        assert!(plans.iter().all(|plan| plan.stops > 0));
+        let best = report.best().unwrap();
+        assert_eq!(best.stops, 1);
+        assert_eq!(best.pit_laps, vec![26]);
+        assert_eq!(best.stints[0].visual_compound, VisualTyreCompound::Soft);
+        assert_eq!((best.stints[0].start_lap, best.stints[0].end_lap), (20, 26));
+        assert_eq!(best.stints[1].visual_compound, VisualTyreCompound::Medium);
+        assert_eq!((best.stints[1].start_lap, best.stints[1].end_lap), (27, 29));
+
+        // 10 laps, the stop, the out lap and 3 laps half a second slower
+        assert!((best.race_time_s - (900.0 + 20.0 + 1.0 + 1.5)).abs() < 0.01);
+        assert_eq!(best.position, 1);
+
+        let second = &plans[1];
+        assert_eq!(second.stops, 1);
+        assert_eq!(second.stints[1].visual_compound, VisualTyreCompound::Hard);
+
+        // Once two compounds were used, staying out saves the stop
+        let mut input = input();
+        input.used_compounds.push(VisualTyreCompound::Medium);
+        let best = plan_strategy(&input).best().cloned().unwrap();
+        assert_eq!(best.stops, 0);
+        assert!(best.pit_laps.is_empty());
+        assert!((best.race_time_s - 900.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn predicts_position_against_rivals() {
+        let rival = |vehicle_idx: u8, gap_s: f32| Rival {
+            vehicle_idx,
+            position: 2,
+            gap_s,
+            lap_time_s: 90.0,
+            num_pit_stops: 0,
+            tyres: None,
+        };
+
+        // The rival can stop for new softs, 1.5s quicker than our plan,
+        // so it finishes ahead unless it's more than that behind
+        let mut input = input();
+        input.rivals = vec![rival(1, 5.0)];
+        assert_eq!(plan_strategy(&input).best().unwrap().position, 1);
+
+        input.rivals = vec![rival(1, 1.0)];
+        assert_eq!(plan_strategy(&input).best().unwrap().position, 2);
+
+        input.rivals = vec![rival(1, -5.0), rival(2, 5.0)];
+        assert_eq!(plan_strategy(&input).best().unwrap().position, 2);
+    }
+}
@@ -0,0 +1,168 @@
+use serde::{Deserialize, Serialize};
+
+use crate::core::Session;
+use crate::strategy::{StrategyInput, TRAFFIC_WINDOW_S};
+
+/// A car near where another would rejoin after a pit stop
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RejoinGap {
+    pub vehicle_idx: u8,
+    pub name: Option<String>,
+
+    /// Seconds between the cars after rejoining
+    pub gap_s: f32,
+}
+
+/// Where a car would rejoin if it pitted at the end of this lap,
+/// predicted from the gaps to the cars on the same lap.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RejoinPrediction {
+    pub vehicle_idx: u8,
+    pub lap: u8,
+    pub position: u8,
+    pub rejoin_position: u8,
+
+    /// Time lost by the stop, and whether it was measured this session
+    pub pit_loss_s: f32,
+    pub pit_loss_measured: bool,
+
+    /// Closest cars ahead and behind after rejoining
+    pub ahead: Option<RejoinGap>,
+    pub behind: Option<RejoinGap>,
+
+    /// Cars less than a second apart directly ahead after rejoining,
+    /// nearest first. Empty if rejoining in clean air.
+    pub stuck_behind: Vec<RejoinGap>,
+
+    /// Laps until catching the car ahead, capped at the laps left after the stop
+    pub clean_air_laps: u8,
+
+    /// The game's prediction. Only sent for the player's car.
+    pub game_rejoin_position: Option<u8>,
+
+    /// The prediction as the engineer would say it on the radio
+    pub message: String,
+}
+
+impl RejoinPrediction {
+    /// Predict where the car at `vehicle_idx` would rejoin if it pitted this lap.
+    ///
+    /// None outside of races, or until the car's pace and tyres are known.
+    pub fn new(session: &Session, vehicle_idx: u8) -> Option<Self> {
+        let input = StrategyInput::new(session, vehicle_idx)?;
+        let name = |vehicle_idx: u8| session.participants.name(vehicle_idx).map(String::from);
+
+        // Seconds behind after the stop, negative if ahead
+        let mut after_stop: Vec<(u8, f32, f32)> = input
+            .rivals
+            .iter()
+            .map(|rival| {
+                (
+                    rival.vehicle_idx,
+                    rival.gap_s - input.pit_loss_s,
+                    rival.lap_time_s,
+                )
+            })
+            .collect();
+        after_stop.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+        // Cars behind by less than the pit loss get past during the stop
+        let passed = input
+            .rivals
+            .iter()
+            .filter(|rival| rival.gap_s > 0.0 && rival.gap_s < input.pit_loss_s)
+            .count() as u8;
+
+        let behind = after_stop
+            .iter()
+            .rev()
+            .find(|(_, gap_s, _)| *gap_s >= 0.0)
+            .map(|&(vehicle_idx, gap_s, _)| RejoinGap {
+                vehicle_idx,
+                name: name(vehicle_idx),
+                gap_s,
+            });
+        let ahead_cars: Vec<&(u8, f32, f32)> = after_stop
+            .iter()
+            .filter(|(_, gap_s, _)| *gap_s < 0.0)
+            .collect();
+        let ahead = ahead_cars
+            .first()
+            .map(|&&(vehicle_idx, gap_s, _)| RejoinGap {
+                vehicle_idx,
+                name: name(vehicle_idx),
+                gap_s: -gap_s,
+            });
+
+        // A train of cars each within a second of the next
+        let mut stuck_behind = Vec::new();
+        let mut train_end_s = 0.0;
+        for &&(vehicle_idx, gap_s, _) in &ahead_cars {
+            if train_end_s - gap_s >= TRAFFIC_WINDOW_S {
+                break;
+            }
+
+            train_end_s = gap_s;
+            stuck_behind.push(RejoinGap {
+                vehicle_idx,
+                name: name(vehicle_idx),
+                gap_s: -gap_s,
+            });
+        }
+
+        // Closing on the car ahead at the pace of a new set
+        let laps_after_stop = input.laps_remaining.saturating_sub(1);
+        let new_pace_s = input.lap_time_s - input.tyres.model.lap_loss_s(input.tyres.wear_percent)
+            + input.tyres.model.lap_loss_s(0.0);
+        let clean_air_laps = match ahead_cars.first() {
+            _ if !stuck_behind.is_empty() => 0,
+            Some(&&(_, gap_s, lap_time_s)) if lap_time_s > new_pace_s => {
+                let laps = (-gap_s - TRAFFIC_WINDOW_S) / (lap_time_s - new_pace_s);
+                (laps.floor() as u8).min(laps_after_stop)
+            }
+            _ => laps_after_stop,
+        };
+
+        let mut prediction = Self {
+            vehicle_idx,
+            lap: input.lap,
+            position: input.position,
+            rejoin_position: input.position + passed,
+            pit_loss_s: input.pit_loss_s,
+            pit_loss_measured: session.timing.pit_loss().is_some(),
+            ahead,
+            behind,
+            stuck_behind,
+            clean_air_laps,
+            game_rejoin_position: input.game_rejoin_position,
+            message: String::new(),
+        };
+        prediction.message = prediction.radio_message(laps_after_stop);
+
+        Some(prediction)
+    }
+
+    fn radio_message(&self, laps_after_stop: u8) -> String {
+        let name = |gap: &RejoinGap| {
+            gap.name
+                .clone()
+                .unwrap_or_else(|| format!("car {}", gap.vehicle_idx))
+        };
+        let rejoin = format!("Box now and you rejoin P{}", self.rejoin_position);
+
+        if !self.stuck_behind.is_empty() {
+            let names: Vec<String> = self.stuck_behind.iter().map(name).collect();
+            return format!("{rejoin} in traffic behind {}.", names.join(", "));
+        }
+
+        match &self.ahead {
+            Some(ahead) if self.clean_air_laps < laps_after_stop => format!(
+                "{rejoin}, {:.1} behind {}. Clean air for {} laps.",
+                ahead.gap_s,
+                name(ahead),
+                self.clean_air_laps
+            ),
+            _ => format!("{rejoin} in clean air to the end."),
+        }
+    }
+}